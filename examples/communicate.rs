@@ -1,6 +1,7 @@
 use cpal::traits::{DeviceTrait, HostTrait, StreamTrait};
 use libpd_rs::{
     functions::{receive::on_float, send::send_list_to, util::calculate_ticks},
+    patch::PatchBuilder,
     Pd,
 };
 use sys_info::loadavg;
@@ -26,59 +27,59 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let mut pd = Pd::init_and_configure(0, output_channels, sample_rate)?;
     let ctx = pd.audio_context();
 
-    // Let's evaluate another pd patch.
-    // We could have opened a `.pd` file also.
-    pd.eval_patch(
-        r#"
-     #N canvas 832 310 625 448 12;
-     #X obj 18 27 r cpu_load;
-     #X obj 55 394 s response;
-     #X obj 13 261 *~;
-     #X obj 112 240 vline~;
-     #X obj 118 62 bng 15 250 50 0 empty empty empty 17 7 0 10 -262144 -1
-     -1;
-     #X obj 14 395 dac~;
-     #X obj 50 299 sig~;
-     #X floatatom 50 268 5 0 0 0 - - -;
-     #X obj 13 228 phasor~ 120;
-     #X obj 139 61 metro 2000;
-     #X obj 139 38 tgl 15 0 empty empty empty 17 7 0 10 -262144 -1 -1 1
-     1;
-     #X obj 18 52 unpack f f;
-     #X obj 14 362 *~ 2;
-     #X obj 14 336 vcf~ 12;
-     #X obj 139 12 loadbang;
-     #X msg 118 86 1 8 \, 0 0 10;
-     #X obj 149 197 expr (480 + 80) * ($f1 - 8) / (4 - 16) + 480;
-     #X obj 29 128 * 20;
-     #X obj 167 273 expr (520 + 120) * ($f1 - 5) / (12 - 5) + 120;
-     #X connect 0 0 11 0;
-     #X connect 2 0 13 0;
-     #X connect 3 0 2 1;
-     #X connect 4 0 15 0;
-     #X connect 6 0 13 1;
-     #X connect 7 0 6 0;
-     #X connect 8 0 2 0;
-     #X connect 9 0 15 0;
-     #X connect 10 0 9 0;
-     #X connect 11 0 16 0;
-     #X connect 11 0 18 0;
-     #X connect 11 1 17 0;
-     #X connect 12 0 5 0;
-     #X connect 12 0 5 1;
-     #X connect 13 0 12 0;
-     #X connect 14 0 10 0;
-     #X connect 15 0 3 0;
-     #X connect 16 0 9 1;
-     #X connect 17 0 1 0;
-     #X connect 17 0 13 2;
-     #X connect 18 0 7 0;
-         "#,
-    )?;
+    // Let's build a pd patch in code.
+    // We could have opened a `.pd` file or evaluated its contents also.
+    let mut builder = PatchBuilder::new();
+
+    // Cpu load is received as a list of two numbers.
+    let cpu_load = builder.obj("r cpu_load");
+    let unpack = builder.obj("unpack f f");
+
+    // An envelope which is played periodically.
+    let loadbang = builder.obj("loadbang");
+    let metro = builder.obj("metro 2000");
+    let envelope = builder.msg("1 8, 0 0 10");
+    let vline = builder.obj("vline~");
+
+    // A saw wave which goes through the envelope and a filter.
+    let phasor = builder.at(200, 20).obj("phasor~ 120");
+    let gate = builder.obj("*~");
+    let cutoff = builder.obj("expr (520 + 120) * ($f1 - 5) / (12 - 5) + 120");
+    let cutoff_signal = builder.obj("sig~");
+    let resonance = builder.obj("* 20");
+    let filter = builder.obj("vcf~ 12");
+    let gain = builder.obj("*~ 2");
+    let dac = builder.obj("dac~");
+
+    // The period of the envelope and the response to rust depend on the load too.
+    let period = builder.obj("expr (480 + 80) * ($f1 - 8) / (4 - 16) + 480");
+    let response = builder.obj("s response");
+
+    builder
+        .connect(cpu_load, 0, unpack, 0)
+        .connect(unpack, 0, period, 0)
+        .connect(unpack, 0, cutoff, 0)
+        .connect(unpack, 1, resonance, 0)
+        .connect(loadbang, 0, metro, 0)
+        .connect(period, 0, metro, 1)
+        .connect(metro, 0, envelope, 0)
+        .connect(envelope, 0, vline, 0)
+        .connect(phasor, 0, gate, 0)
+        .connect(vline, 0, gate, 1)
+        .connect(gate, 0, filter, 0)
+        .connect(cutoff, 0, cutoff_signal, 0)
+        .connect(cutoff_signal, 0, filter, 1)
+        .connect(resonance, 0, filter, 2)
+        .connect(resonance, 0, response, 0)
+        .connect(filter, 0, gain, 0)
+        .connect(gain, 0, dac, 0)
+        .connect(gain, 0, dac, 1);
+
+    let _patch = pd.load_patch(&builder)?;
 
     // Here we are registering a listener (hook in libpd lingo) for
     // float values which are received from the pd patch.
-    on_float(|source, value| {
+    let _hook = on_float(|source, value| {
         if source == "response" {
             print!("\r");
             print!("Pd says that the q value of the vcf~ is: {value}");
@@ -25,7 +25,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Let's evaluate a pd patch.
     // We could have opened a `.pd` file also.
     // This patch would play a sine wave at 440hz.
-    pd.eval_patch(
+    let patch = pd.eval_patch(
         r#"
     #N canvas 577 549 158 168 12;
     #X obj 23 116 dac~;
@@ -74,7 +74,7 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     output_stream.pause()?;
 
     // Close the patch
-    pd.close_patch()?;
+    patch.close()?;
 
     // Leave
     Ok(())
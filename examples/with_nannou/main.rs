@@ -16,6 +16,8 @@ fn main() {
 // This data structure will be shared across nannou functions.
 pub struct Model {
     pd: libpd_rs::Pd,
+    _print_hook: libpd_rs::types::HookHandle,
+    _bubbles_patch: Option<libpd_rs::patch::Patch>,
     output_stream: audio::Stream<PdAudioContext>,
     gravity: f32,
     bubbles: RefCell<Vec<Bubble>>,
@@ -67,7 +69,7 @@ fn model(app: &App) -> Model {
         .unwrap();
 
     // Listen for console messages from pd
-    libpd_rs::functions::receive::on_print(|val| {
+    let print_hook = libpd_rs::functions::receive::on_print(|val| {
         println!("{}", val);
     });
 
@@ -75,6 +77,8 @@ fn model(app: &App) -> Model {
     let mut model = Model {
         // Initialize pd
         pd,
+        _print_hook: print_hook,
+        _bubbles_patch: None,
         output_stream,
         gravity: 0.8,
         bubbles: RefCell::new(vec![]),
@@ -90,8 +94,8 @@ fn model(app: &App) -> Model {
     // We're using multiple patches this time, so we need to tell pd where to find them.
     model.pd.add_path_to_search_paths(&patches_dir).unwrap();
 
-    // Load our patches.
-    model.pd.open_patch(patches_dir.join("bubbles.pd")).unwrap();
+    // Load our patches, they stay open as long as the model holds them.
+    model._bubbles_patch = Some(model.pd.open_patch(patches_dir.join("bubbles.pd")).unwrap());
 
     // Initially pd needs to know how many bubbles we have.
     // Because it will create adequate amount of voices for them.
@@ -0,0 +1,341 @@
+mod owned;
+mod view;
+mod watch;
+mod wav;
+
+pub(crate) use owned::hidden_canvas;
+pub use owned::{ArrayOptions, OwnedArray, PlotStyle};
+pub(crate) use view::with_array;
+pub use view::ArrayView;
+pub use watch::ArrayWatcher;
+pub use wav::{load_wav, save_wav, LoadOptions};
+
+use std::{
+    ffi::CString,
+    ops::{Bound, Range, RangeBounds},
+    path::Path,
+    slice,
+};
+
+use libpd_sys::_pdinstance;
+
+use crate::{
+    error::{ArrayError, PdError, StringConversionError},
+    instance::{InstanceRef, LiveInstanceGuard},
+};
+
+/// A handle to a named array in a pd instance.
+///
+/// The handle holds the name of the array and its instance, the array itself is looked up by libpd
+/// on each operation. So the handle keeps working when the patch which defines the array is reopened,
+/// and returns [`FailedToFindArray`](crate::error::ArrayError::FailedToFindArray) while it is closed
+/// or after its instance is dropped.
+///
+/// Positions and ranges are in `usize` and are checked against the size of the array,
+/// every operation returns an [`ArrayError`](crate::error::ArrayError).
+///
+/// This is returned from [`Pd::array`](crate::Pd::array).
+///
+/// # Examples
+/// ```no_run
+/// use libpd_rs::Pd;
+///
+/// let pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+/// let _patch = pd.open_patch("tests/patches/array_sketch_pad.pd").unwrap();
+///
+/// let array = pd.array("sketch_pad").unwrap();
+/// array.resize(4).unwrap();
+/// array.write_from(&[0.0, 0.5, 1.0], 1).unwrap();
+/// assert_eq!(array.to_vec().unwrap(), [0.0, 0.0, 0.5, 1.0]);
+/// assert_eq!(array.slice(2..).unwrap(), [0.5, 1.0]);
+/// assert_eq!(array.get(3).unwrap(), 1.0);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Array {
+    name: CString,
+    instance: InstanceRef,
+}
+
+// Every operation checks that the instance of the array is alive and sets it as the active one before calling libpd.
+unsafe impl Send for Array {}
+
+impl Array {
+    /// Finds an array by its name in an instance.
+    pub(crate) fn find(name: &str, instance: *mut _pdinstance) -> Result<Self, ArrayError> {
+        let array = Self {
+            name: CString::new(name).map_err(StringConversionError::from)?,
+            instance: InstanceRef::new(instance),
+        };
+        array.len()?;
+        Ok(array)
+    }
+
+    /// Gets the name of the array.
+    pub fn name(&self) -> &str {
+        // The name is created from a `str`.
+        self.name.to_str().unwrap_or_default()
+    }
+
+    /// Gets the number of values in the array.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`FailedToFindArray`](crate::error::ArrayError::FailedToFindArray)
+    pub fn len(&self) -> Result<usize, ArrayError> {
+        let _guard = self.activate()?;
+        let len = unsafe { libpd_sys::libpd_arraysize(self.name.as_ptr()) };
+        usize::try_from(len).map_err(|_| ArrayError::FailedToFindArray)
+    }
+
+    /// Whether the array has no values, pd keeps at least one value in arrays.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`FailedToFindArray`](crate::error::ArrayError::FailedToFindArray)
+    pub fn is_empty(&self) -> Result<bool, ArrayError> {
+        Ok(self.len()? == 0)
+    }
+
+    /// Gets the value at an index.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`FailedToFindArray`](crate::error::ArrayError::FailedToFindArray)
+    /// - [`OutOfBounds`](crate::error::ArrayError::OutOfBounds)
+    pub fn get(&self, index: usize) -> Result<f32, ArrayError> {
+        let mut value = [0.0f32];
+        self.read_into(&mut value, index..=index)?;
+        let [value] = value;
+        Ok(value)
+    }
+
+    /// Sets the value at an index.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`FailedToFindArray`](crate::error::ArrayError::FailedToFindArray)
+    /// - [`OutOfBounds`](crate::error::ArrayError::OutOfBounds)
+    pub fn set(&self, index: usize, value: f32) -> Result<(), ArrayError> {
+        self.write_from(&[value], index)
+    }
+
+    /// Reads the values in a range of the array into a new vector.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`FailedToFindArray`](crate::error::ArrayError::FailedToFindArray)
+    /// - [`OutOfBounds`](crate::error::ArrayError::OutOfBounds)
+    pub fn slice<R: RangeBounds<usize>>(&self, range: R) -> Result<Vec<f32>, ArrayError> {
+        let range = self.resolve(range)?;
+        let mut values = vec![0.0f32; range.len()];
+        self.read_into(&mut values, range)?;
+        Ok(values)
+    }
+
+    /// Reads all the values of the array into a new vector.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`FailedToFindArray`](crate::error::ArrayError::FailedToFindArray)
+    pub fn to_vec(&self) -> Result<Vec<f32>, ArrayError> {
+        self.slice(..)
+    }
+
+    /// Reads the values in a range of the array to the start of `destination`.
+    ///
+    /// # Errors
+    ///
+    /// If the range is not in the array or `destination` is shorter than the range
+    /// [`OutOfBounds`](crate::error::ArrayError::OutOfBounds) is returned.
+    ///
+    /// A list of errors that can occur:
+    /// - [`FailedToFindArray`](crate::error::ArrayError::FailedToFindArray)
+    /// - [`OutOfBounds`](crate::error::ArrayError::OutOfBounds)
+    pub fn read_into<R: RangeBounds<usize>>(
+        &self,
+        destination: &mut [f32],
+        range: R,
+    ) -> Result<(), ArrayError> {
+        let range = self.resolve(range)?;
+        if destination.len() < range.len() {
+            return Err(ArrayError::OutOfBounds);
+        }
+        let offset = c_int(range.start)?;
+        let count = c_int(range.len())?;
+        let _guard = self.activate()?;
+        // Returns 0 on success, -1 if the array does not exist or -2 if the range exceeds it.
+        let result = unsafe {
+            libpd_sys::libpd_read_array(destination.as_mut_ptr(), self.name.as_ptr(), offset, count)
+        };
+        check(result)
+    }
+
+    /// Writes all the values of `source` to the array starting from `offset`.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`FailedToFindArray`](crate::error::ArrayError::FailedToFindArray)
+    /// - [`OutOfBounds`](crate::error::ArrayError::OutOfBounds)
+    pub fn write_from(&self, source: &[f32], offset: usize) -> Result<(), ArrayError> {
+        let count = c_int(source.len())?;
+        let offset = c_int(offset)?;
+        let _guard = self.activate()?;
+        // Returns 0 on success, -1 if the array does not exist or -2 if the range exceeds it.
+        let result = unsafe {
+            libpd_sys::libpd_write_array(self.name.as_ptr(), offset, source.as_ptr(), count)
+        };
+        check(result)
+    }
+
+    /// Sets all the values of the array to `value`.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`FailedToFindArray`](crate::error::ArrayError::FailedToFindArray)
+    pub fn fill(&self, value: f32) -> Result<(), ArrayError> {
+        self.write_from(&vec![value; self.len()?], 0)
+    }
+
+    /// Resizes the array, the values which are added are `0`.
+    ///
+    /// Pd clips sizes of `0` and over its size limit to `1`.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`FailedToFindArray`](crate::error::ArrayError::FailedToFindArray)
+    /// - [`OutOfBounds`](crate::error::ArrayError::OutOfBounds)
+    pub fn resize(&self, len: usize) -> Result<(), ArrayError> {
+        // The size is a `long` which differs in width between platforms.
+        let len = len.try_into().map_err(|_| ArrayError::OutOfBounds)?;
+        let _guard = self.activate()?;
+        // Returns 0 on success or a negative error code if the array does not exist.
+        match unsafe { libpd_sys::libpd_resize_array(self.name.as_ptr(), len) } {
+            0 => Ok(()),
+            _ => Err(ArrayError::FailedToFindArray),
+        }
+    }
+
+    /// Loads a channel of a wav file into the array, resizing the array to the length of the file.
+    ///
+    /// Integer samples are scaled to the range of `-1` to `1`, returns the number of frames which are loaded.
+    /// Unlike `[soundfiler]` the path is not looked up in the search paths of pd.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use libpd_rs::Pd;
+    ///
+    /// let pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+    /// let _patch = pd.open_patch("tests/patches/array_sketch_pad.pd").unwrap();
+    ///
+    /// let frames = pd.array("sketch_pad").unwrap().load_wav("kick.wav", 0).unwrap();
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`WavError`](crate::error::WavError)
+    ///   - [`InvalidFile`](crate::error::WavError::InvalidFile)
+    ///   - [`ChannelCount`](crate::error::WavError::ChannelCount)
+    /// - [`ArrayError`](crate::error::ArrayError)
+    ///   - [`FailedToFindArray`](crate::error::ArrayError::FailedToFindArray)
+    ///   - [`OutOfBounds`](crate::error::ArrayError::OutOfBounds)
+    pub fn load_wav<P: AsRef<Path>>(&self, path: P, channel: usize) -> Result<usize, PdError> {
+        self.load_wav_with(path, channel, &LoadOptions::default())
+    }
+
+    /// Loads a channel of a wav file into the array with options, see [`load_wav`](Array::load_wav).
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use libpd_rs::{array::LoadOptions, Pd};
+    ///
+    /// let pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+    /// let _patch = pd.open_patch("tests/patches/array_sketch_pad.pd").unwrap();
+    ///
+    /// // Resamples the file to the sample rate of the instance.
+    /// let options = LoadOptions {
+    ///     sample_rate: u32::try_from(pd.sample_rate()).ok(),
+    /// };
+    /// let sketch_pad = pd.array("sketch_pad").unwrap();
+    /// sketch_pad.load_wav_with("kick.wav", 0, &options).unwrap();
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// The errors of [`load_wav`](Array::load_wav).
+    pub fn load_wav_with<P: AsRef<Path>>(
+        &self,
+        path: P,
+        channel: usize,
+        options: &LoadOptions,
+    ) -> Result<usize, PdError> {
+        wav::load_channel(self, path.as_ref(), channel, *options)
+    }
+
+    /// Saves the values of the array to a mono wav file of 32 bit float samples.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`WavError`](crate::error::WavError)
+    ///   - [`InvalidFile`](crate::error::WavError::InvalidFile)
+    /// - [`ArrayError`](crate::error::ArrayError)
+    ///   - [`FailedToFindArray`](crate::error::ArrayError::FailedToFindArray)
+    pub fn save_wav<P: AsRef<Path>>(&self, path: P, sample_rate: u32) -> Result<(), PdError> {
+        save_wav(path, slice::from_ref(self), sample_rate)
+    }
+
+    /// Resizes the array to the length of the values and writes them.
+    fn replace(&self, values: &[f32]) -> Result<(), ArrayError> {
+        self.resize(values.len())?;
+        self.write_from(values, 0)
+    }
+
+    /// Converts a range to the indices it covers, unbounded ends are the ends of the array.
+    fn resolve<R: RangeBounds<usize>>(&self, range: R) -> Result<Range<usize>, ArrayError> {
+        let start = match range.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start.checked_add(1).ok_or(ArrayError::OutOfBounds)?,
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(&end) => end.checked_add(1).ok_or(ArrayError::OutOfBounds)?,
+            Bound::Excluded(&end) => end,
+            Bound::Unbounded => self.len()?,
+        };
+        if start > end {
+            return Err(ArrayError::OutOfBounds);
+        }
+        Ok(start..end)
+    }
+
+    /// Sets the instance of the array as the active one while it is alive.
+    fn activate(&self) -> Result<LiveInstanceGuard, ArrayError> {
+        self.instance
+            .activate()
+            .ok_or(ArrayError::FailedToFindArray)
+    }
+}
+
+/// Converts a position to the integer type of libpd.
+fn c_int(value: usize) -> Result<i32, ArrayError> {
+    i32::try_from(value).map_err(|_| ArrayError::OutOfBounds)
+}
+
+/// Converts the result of reading or writing an array.
+const fn check(result: i32) -> Result<(), ArrayError> {
+    match result {
+        0 => Ok(()),
+        -2 => Err(ArrayError::OutOfBounds),
+        _ => Err(ArrayError::FailedToFindArray),
+    }
+}
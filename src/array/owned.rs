@@ -0,0 +1,133 @@
+use std::ops::Deref;
+
+use crate::{
+    array::Array,
+    error::PdError,
+    patch::{
+        file::{self, Canvas, Entry, PatchFile, Record, Subpatch},
+        Patch,
+    },
+};
+
+/// How an array is drawn in its graph.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum PlotStyle {
+    /// A point for each value.
+    Points,
+    /// Lines between the values.
+    #[default]
+    Polygon,
+    /// A curve through the values.
+    Bezier,
+}
+
+/// Options for creating an array with [`Pd::create_array`](crate::Pd::create_array).
+///
+/// The defaults are the ones of the array dialog of pd.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ArrayOptions {
+    /// Whether the contents of the array are saved with its canvas.
+    pub save_contents: bool,
+    /// How the array is drawn in its graph.
+    pub style: PlotStyle,
+    /// Whether the name of the array is hidden in its graph.
+    pub hide_name: bool,
+    /// Whether the window of the graph is opened when a GUI is running.
+    pub visible: bool,
+}
+
+impl Default for ArrayOptions {
+    fn default() -> Self {
+        Self {
+            save_contents: true,
+            style: PlotStyle::default(),
+            hide_name: false,
+            visible: false,
+        }
+    }
+}
+
+impl ArrayOptions {
+    /// The flags of the `#X array` record.
+    fn flags(self) -> i32 {
+        let style = match self.style {
+            PlotStyle::Points => 0,
+            PlotStyle::Polygon => 1,
+            PlotStyle::Bezier => 2,
+        };
+        i32::from(self.save_contents) | (style << 1) | (i32::from(self.hide_name) << 3)
+    }
+}
+
+/// An array which is created from Rust in a hidden canvas of its own.
+///
+/// It dereferences to an [`Array`] so it can be used like one.
+/// The canvas is closed and the array is deleted when this is dropped,
+/// handles which are cloned from it return [`FailedToFindArray`](crate::error::ArrayError::FailedToFindArray) after that.
+///
+/// This is returned from [`Pd::create_array`](crate::Pd::create_array).
+///
+/// # Examples
+/// ```no_run
+/// use libpd_rs::{array::ArrayOptions, Pd};
+///
+/// let pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+///
+/// let wavetable = pd.create_array("wavetable", 512, &ArrayOptions::default()).unwrap();
+/// wavetable.fill(0.5).unwrap();
+///
+/// // Deletes the array and reports errors.
+/// wavetable.delete().unwrap();
+/// ```
+#[derive(Debug)]
+#[must_use = "The array is deleted when it is dropped."]
+pub struct OwnedArray {
+    array: Array,
+    patch: Patch,
+}
+
+impl OwnedArray {
+    pub(crate) const fn new(array: Array, patch: Patch) -> Self {
+        Self { array, patch }
+    }
+
+    /// Deletes the array by closing its canvas.
+    ///
+    /// # Errors
+    ///
+    /// The errors of [`Patch::close`].
+    pub fn delete(self) -> Result<(), PdError> {
+        self.patch.close()
+    }
+}
+
+impl Deref for OwnedArray {
+    type Target = Array;
+
+    fn deref(&self) -> &Self::Target {
+        &self.array
+    }
+}
+
+/// The contents of the hidden canvas of an array, a graph which holds a garray like the one of the array dialog.
+pub fn hidden_canvas(name: &str, size: usize, options: ArrayOptions) -> PatchFile {
+    let mut array = file::Array::new(name, size);
+    array.flags = options.flags();
+    let mut graph_canvas = Canvas::new(0, 50, 450, 300);
+    graph_canvas.entries = vec![
+        Entry::Array(array),
+        // The value range of the graph from 1 to -1 over the size of the array.
+        Entry::Record(Record::new(
+            format!("#X coords 0 1 {size} -1 200 140 1 0 0")
+                .split(' ')
+                .map(str::to_owned)
+                .collect(),
+        )),
+    ];
+    let mut graph = Subpatch::new("(subpatch)", 20, 20, graph_canvas);
+    graph.content = vec!["graph".to_owned()];
+    graph.open = options.visible;
+    let mut canvas = Canvas::new(0, 50, 450, 300);
+    canvas.entries.push(Entry::Subpatch(graph));
+    PatchFile::new(canvas)
+}
@@ -0,0 +1,160 @@
+use std::{ffi::CString, fmt, mem, ptr, slice};
+
+use libpd_sys::{_pdinstance, t_float, t_garray, t_word};
+
+use crate::{
+    error::{ArrayError, StringConversionError},
+    instance::ActiveInstanceGuard,
+};
+
+/// Direct access to the memory of a named array, without copying it.
+///
+/// Pd stores the values of arrays in `t_word`s which are as wide as a pointer, the values are
+/// [`t_float`](libpd_sys::t_float)s at their start. [`as_slice`](ArrayView::as_slice) and
+/// [`as_mut_slice`](ArrayView::as_mut_slice) are only available when they are as wide as each other,
+/// like in double precision builds, the other accessors step over the words in every build.
+///
+/// This is passed to the closure of [`Pd::with_array`](crate::Pd::with_array) which holds the lock of pd
+/// while it runs, the array is redrawn after the closure if it was accessed mutably.
+///
+/// # Examples
+/// ```no_run
+/// use libpd_rs::Pd;
+///
+/// let mut pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+/// let _patch = pd.open_patch("tests/patches/array_sketch_pad.pd").unwrap();
+///
+/// pd.with_array("sketch_pad", |sketch_pad| {
+///     for (index, value) in sketch_pad.iter_mut().enumerate() {
+///         *value = if index % 2 == 0 { 1.0 } else { -1.0 };
+///     }
+///     if let Some(values) = sketch_pad.as_slice() {
+///         println!("{values:?}");
+///     }
+/// })
+/// .unwrap();
+/// ```
+pub struct ArrayView<'memory> {
+    words: &'memory mut [t_word],
+    /// Whether the values were accessed mutably and the array needs to be redrawn.
+    changed: bool,
+}
+
+impl ArrayView<'_> {
+    /// Gets the number of values in the array.
+    pub const fn len(&self) -> usize {
+        self.words.len()
+    }
+
+    /// Whether the array has no values, pd keeps at least one value in arrays.
+    pub const fn is_empty(&self) -> bool {
+        self.words.is_empty()
+    }
+
+    /// Gets the value at an index.
+    pub fn get(&self, index: usize) -> Option<t_float> {
+        // The words of a float array hold floats.
+        self.words.get(index).map(|word| unsafe { word.w_float })
+    }
+
+    /// Gets a mutable reference to the value at an index.
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut t_float> {
+        self.words_mut()
+            .get_mut(index)
+            .map(|word| unsafe { &mut word.w_float })
+    }
+
+    /// Iterates over the values of the array.
+    pub fn iter(&self) -> impl Iterator<Item = t_float> + '_ {
+        self.words.iter().map(|word| unsafe { word.w_float })
+    }
+
+    /// Iterates over mutable references to the values of the array.
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut t_float> + '_ {
+        self.words_mut()
+            .iter_mut()
+            .map(|word| unsafe { &mut word.w_float })
+    }
+
+    /// Gets the values as a slice if the words of pd are as wide as its floats.
+    pub fn as_slice(&self) -> Option<&[t_float]> {
+        (mem::size_of::<t_word>() == mem::size_of::<t_float>()).then(|| unsafe {
+            slice::from_raw_parts(self.words.as_ptr().cast::<t_float>(), self.words.len())
+        })
+    }
+
+    /// Gets the values as a mutable slice if the words of pd are as wide as its floats.
+    pub fn as_mut_slice(&mut self) -> Option<&mut [t_float]> {
+        (mem::size_of::<t_word>() == mem::size_of::<t_float>()).then(|| {
+            let words = self.words_mut();
+            unsafe { slice::from_raw_parts_mut(words.as_mut_ptr().cast::<t_float>(), words.len()) }
+        })
+    }
+
+    const fn words_mut(&mut self) -> &mut [t_word] {
+        self.changed = true;
+        self.words
+    }
+}
+
+impl fmt::Debug for ArrayView<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ArrayView")
+            .field("len", &self.words.len())
+            .field("changed", &self.changed)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Holds the global lock of pd until it is dropped, also when the closure of [`with_array`] panics.
+struct Lock;
+
+impl Lock {
+    fn new() -> Self {
+        unsafe { libpd_sys::sys_lock() };
+        Self
+    }
+}
+
+impl Drop for Lock {
+    fn drop(&mut self) {
+        unsafe { libpd_sys::sys_unlock() };
+    }
+}
+
+/// Locks pd, finds the memory of an array by its name in an instance and calls `f` with it.
+pub fn with_array<R, F>(name: &str, instance: *mut _pdinstance, f: F) -> Result<R, ArrayError>
+where
+    F: FnOnce(&mut ArrayView<'_>) -> R,
+{
+    let name = CString::new(name).map_err(StringConversionError::from)?;
+    let _active = ActiveInstanceGuard::activate(instance);
+    // Dropped before the instance guard.
+    let _lock = Lock::new();
+    let symbol = unsafe { libpd_sys::gensym(name.as_ptr()) };
+    let class = unsafe { libpd_sys::garray_class };
+    let garray = unsafe { libpd_sys::pd_findbyclass(symbol, class) }.cast::<t_garray>();
+    if garray.is_null() {
+        return Err(ArrayError::FailedToFindArray);
+    }
+    let mut size = 0;
+    let mut words = ptr::null_mut();
+    // Returns 0 if the elements of the array are not floats.
+    if unsafe { libpd_sys::garray_getfloatwords(garray, &raw mut size, &raw mut words) } == 0
+        || words.is_null()
+    {
+        return Err(ArrayError::FailedToFindArray);
+    }
+    let len = usize::try_from(size).unwrap_or_default();
+    // The memory stays valid while pd is locked.
+    let words = unsafe { slice::from_raw_parts_mut(words, len) };
+    let mut view = ArrayView {
+        words,
+        changed: false,
+    };
+    let result = f(&mut view);
+    if view.changed {
+        unsafe { libpd_sys::garray_redraw(garray) };
+    }
+    Ok(result)
+}
@@ -0,0 +1,117 @@
+use std::{mem, ops::Range};
+
+use crate::{array::Array, error::ArrayError};
+
+/// Keeps a snapshot of an array and finds the ranges of it which changed since the last poll.
+///
+/// Polling reads the whole array once and compares it with the snapshot, so a visualizer can
+/// repaint only the values which changed instead of the whole array on every frame.
+/// The buffers are reused between polls, polling an array which keeps its size does not allocate.
+///
+/// # Examples
+/// ```no_run
+/// use libpd_rs::{array::ArrayWatcher, Pd};
+///
+/// let pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+/// let _patch = pd.open_patch("tests/patches/array_sketch_pad.pd").unwrap();
+///
+/// let mut watcher = ArrayWatcher::new(pd.array("sketch_pad").unwrap()).unwrap();
+///
+/// // In the render loop.
+/// if watcher.poll().unwrap() {
+///     for range in watcher.changes() {
+///         let _changed = &watcher.values()[range.clone()];
+///     }
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct ArrayWatcher {
+    array: Array,
+    /// The values of the array when it was last polled.
+    snapshot: Vec<f32>,
+    /// The values which are read on a poll, kept to reuse its allocation.
+    current: Vec<f32>,
+    /// The ranges which changed on the last poll.
+    changes: Vec<Range<usize>>,
+    version: u64,
+}
+
+impl ArrayWatcher {
+    /// Takes the first snapshot of the array and starts watching it.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`FailedToFindArray`](crate::error::ArrayError::FailedToFindArray)
+    pub fn new(array: Array) -> Result<Self, ArrayError> {
+        let snapshot = array.to_vec()?;
+        Ok(Self {
+            current: Vec::with_capacity(snapshot.len()),
+            array,
+            snapshot,
+            changes: vec![],
+            version: 0,
+        })
+    }
+
+    /// The array which is watched.
+    pub const fn array(&self) -> &Array {
+        &self.array
+    }
+
+    /// The values of the array when it was last polled.
+    pub fn values(&self) -> &[f32] {
+        &self.snapshot
+    }
+
+    /// The ranges of the values which changed on the last poll, in order and without overlaps.
+    pub fn changes(&self) -> &[Range<usize>] {
+        &self.changes
+    }
+
+    /// A counter which goes up on each poll which finds a change, starting from `0`.
+    pub const fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// Reads the array and finds the ranges which changed since the last poll, returns whether any did.
+    ///
+    /// When the array is resized all of it is reported as changed.
+    /// Values are compared by their bits so a `NaN` which stays is not a change.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`FailedToFindArray`](crate::error::ArrayError::FailedToFindArray)
+    pub fn poll(&mut self) -> Result<bool, ArrayError> {
+        let len = self.array.len()?;
+        self.current.resize(len, 0.0);
+        self.array.read_into(&mut self.current, ..len)?;
+        self.changes.clear();
+        if len == self.snapshot.len() {
+            let mut start = None;
+            for (index, (current, previous)) in self.current.iter().zip(&self.snapshot).enumerate()
+            {
+                match (current.to_bits() == previous.to_bits(), start) {
+                    (false, None) => start = Some(index),
+                    (true, Some(changed)) => {
+                        self.changes.push(changed..index);
+                        start = None;
+                    }
+                    _ => {}
+                }
+            }
+            if let Some(changed) = start {
+                self.changes.push(changed..len);
+            }
+        } else {
+            self.changes.push(0..len);
+        }
+        mem::swap(&mut self.snapshot, &mut self.current);
+        let changed = !self.changes.is_empty();
+        if changed {
+            self.version += 1;
+        }
+        Ok(changed)
+    }
+}
@@ -0,0 +1,239 @@
+use std::{f64::consts::PI, path::Path};
+
+use hound::{SampleFormat, WavReader, WavSpec, WavWriter};
+
+use crate::{
+    array::Array,
+    audio::samples,
+    error::{PdError, SizeError, WavError},
+};
+
+/// Options for loading wav files into arrays.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LoadOptions {
+    /// The sample rate to resample the file to, usually the one of the instance,
+    /// see [`Pd::sample_rate`](crate::Pd::sample_rate).
+    ///
+    /// The samples are loaded as they are when this is `None` or the file already has the sample rate.
+    pub sample_rate: Option<u32>,
+}
+
+/// Loads a channel of a wav file into an array, see [`Array::load_wav_with`].
+pub(super) fn load_channel(
+    array: &Array,
+    path: &Path,
+    channel: usize,
+    options: LoadOptions,
+) -> Result<usize, PdError> {
+    let wav = Wav::read(path, channel, 1, options)?;
+    let samples = wav.channels.first().map(Vec::as_slice).unwrap_or_default();
+    array.replace(samples)?;
+    Ok(samples.len())
+}
+
+/// Loads the channels of a wav file into arrays, the first channel into the first array and so on.
+///
+/// Each array is resized to the length of the file, the channels which are left over are not loaded.
+/// Returns the number of frames which are loaded.
+///
+/// # Examples
+/// ```no_run
+/// use libpd_rs::{array::{self, LoadOptions}, Pd};
+///
+/// let pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+/// let _patch = pd.open_patch("sampler.pd").unwrap();
+///
+/// let arrays = [pd.array("left").unwrap(), pd.array("right").unwrap()];
+/// array::load_wav("loop.wav", &arrays, &LoadOptions::default()).unwrap();
+/// ```
+///
+/// # Errors
+///
+/// The errors of [`Array::load_wav`], if the file has fewer channels than the arrays
+/// [`ChannelCount`](crate::error::WavError::ChannelCount) is returned.
+pub fn load_wav<P: AsRef<Path>>(
+    path: P,
+    arrays: &[Array],
+    options: &LoadOptions,
+) -> Result<usize, PdError> {
+    let wav = Wav::read(path.as_ref(), 0, arrays.len(), *options)?;
+    for (array, samples) in arrays.iter().zip(&wav.channels) {
+        array.replace(samples)?;
+    }
+    Ok(wav.frames())
+}
+
+/// Saves the values of arrays to a wav file of 32 bit float samples, an array for each channel.
+///
+/// Arrays which are shorter than the longest one are padded with silence.
+///
+/// # Errors
+///
+/// The errors of [`Array::save_wav`] and:
+/// - [`SizeError`](crate::error::SizeError)
+///   - [`TooLarge`](crate::error::SizeError::TooLarge)
+pub fn save_wav<P: AsRef<Path>>(
+    path: P,
+    arrays: &[Array],
+    sample_rate: u32,
+) -> Result<(), PdError> {
+    let channels = arrays
+        .iter()
+        .map(Array::to_vec)
+        .collect::<Result<Vec<_>, _>>()?;
+    let frames = channels.iter().map(Vec::len).max().unwrap_or_default();
+    let spec = WavSpec {
+        channels: u16::try_from(channels.len()).map_err(|_| SizeError::TooLarge)?,
+        sample_rate,
+        bits_per_sample: 32,
+        sample_format: SampleFormat::Float,
+    };
+    let mut writer = WavWriter::create(path, spec).map_err(WavError::from)?;
+    for frame in 0..frames {
+        for channel in &channels {
+            let sample = channel.get(frame).copied().unwrap_or_default();
+            writer.write_sample(sample).map_err(WavError::from)?;
+        }
+    }
+    writer.finalize().map_err(WavError::from)?;
+    Ok(())
+}
+
+/// Channels of a wav file in the range of `-1` to `1`.
+struct Wav {
+    channels: Vec<Vec<f32>>,
+}
+
+impl Wav {
+    /// Reads `len` channels of a file starting from the channel at `first`
+    /// and resamples them if the options ask for it.
+    fn read(path: &Path, first: usize, len: usize, options: LoadOptions) -> Result<Self, PdError> {
+        let reader = WavReader::open(path).map_err(WavError::from)?;
+        let spec = reader.spec();
+        let count = usize::from(spec.channels);
+        let needed = first.checked_add(len);
+        if needed.is_none_or(|needed| needed > count) {
+            return Err(WavError::ChannelCount {
+                channels: spec.channels,
+                expected: needed.unwrap_or(usize::MAX),
+            }
+            .into());
+        }
+        let interleaved = samples(reader).collect::<Result<Vec<_>, _>>()?;
+        let frames = interleaved.len().checked_div(count).unwrap_or_default();
+        let mut channels = vec![Vec::with_capacity(frames); len];
+        for frame in interleaved.chunks_exact(count) {
+            for (channel, sample) in channels.iter_mut().zip(frame.iter().skip(first)) {
+                channel.push(*sample);
+            }
+        }
+        if let Some(sample_rate) = options.sample_rate {
+            for channel in &mut channels {
+                *channel = resample(channel, spec.sample_rate, sample_rate);
+            }
+        }
+        Ok(Self { channels })
+    }
+
+    fn frames(&self) -> usize {
+        self.channels.first().map(Vec::len).unwrap_or_default()
+    }
+}
+
+/// Resamples with cubic hermite interpolation.
+///
+/// When downsampling the frequencies above the nyquist frequency of the new rate are filtered out first,
+/// interpolating alone would fold them back into the audible range.
+#[expect(
+    clippy::cast_possible_truncation,
+    clippy::cast_precision_loss,
+    clippy::cast_sign_loss,
+    reason = "Positions in a sample are far below the precision of the float and are not negative."
+)]
+fn resample(samples: &[f32], from: u32, to: u32) -> Vec<f32> {
+    if from == to || from == 0 || samples.is_empty() {
+        return samples.to_vec();
+    }
+    // Rounds to the nearest frame.
+    let length = (samples.len() as u64 * u64::from(to) * 2 + u64::from(from))
+        .checked_div(u64::from(from) * 2)
+        .unwrap_or_default();
+    let step = f64::from(from) / f64::from(to);
+    let filtered;
+    let samples = if to < from {
+        filtered = low_pass(samples, step);
+        &filtered
+    } else {
+        samples
+    };
+    let last = samples.len() - 1;
+    let at = |index: usize| f64::from(samples.get(index.min(last)).copied().unwrap_or_default());
+    (0..length)
+        .map(|frame| {
+            let position = frame as f64 * step;
+            let index = position.floor() as usize;
+            let fraction = position - position.floor();
+            let previous = at(index.saturating_sub(1));
+            let current = at(index);
+            let next = at(index + 1);
+            let after = at(index + 2);
+            let a = 0.5f64.mul_add(after - previous, 1.5 * (current - next));
+            let b = 2.5f64.mul_add(
+                -current,
+                2.0f64.mul_add(next, 0.5f64.mul_add(-after, previous)),
+            );
+            let c = 0.5 * (next - previous);
+            a.mul_add(fraction, b)
+                .mul_add(fraction, c)
+                .mul_add(fraction, current) as f32
+        })
+        .collect()
+}
+
+/// Filters out the frequencies above the nyquist frequency of a rate which is `step` times lower,
+/// with a sinc filter in a blackman window.
+#[expect(
+    clippy::cast_possible_truncation,
+    clippy::cast_precision_loss,
+    clippy::cast_sign_loss,
+    reason = "The length of the filter is far below the precision of the float and is not negative."
+)]
+fn low_pass(samples: &[f32], step: f64) -> Vec<f32> {
+    // The cutoff in cycles per sample and the number of taps on each side of the center,
+    // which grows with the ratio to keep the transition band as narrow.
+    let cutoff = 0.5 / step;
+    let half = (8.0 * step).ceil() as usize;
+    let width = (half * 2) as f64;
+    let mut kernel: Vec<f64> = (0..=half * 2)
+        .map(|tap| {
+            let offset = tap as f64 - half as f64;
+            let sinc = if tap == half {
+                2.0 * cutoff
+            } else {
+                (2.0 * PI * cutoff * offset).sin() / (PI * offset)
+            };
+            let phase = 2.0 * PI * tap as f64 / width;
+            let window = 0.08f64.mul_add((2.0 * phase).cos(), 0.5f64.mul_add(-phase.cos(), 0.42));
+            sinc * window
+        })
+        .collect();
+    // Passes the lower frequencies at their level.
+    let sum: f64 = kernel.iter().sum();
+    for weight in &mut kernel {
+        *weight /= sum;
+    }
+    (0..samples.len())
+        .map(|index| {
+            kernel
+                .iter()
+                .enumerate()
+                .filter_map(|(tap, weight)| {
+                    let source = (index + tap).checked_sub(half)?;
+                    samples
+                        .get(source)
+                        .map(|sample| f64::from(*sample) * weight)
+                })
+                .sum::<f64>() as f32
+        })
+        .collect()
+}
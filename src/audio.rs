@@ -0,0 +1,264 @@
+mod block;
+mod planar;
+mod render;
+mod schedule;
+
+pub use block::BlockAdapter;
+pub use planar::PlanarProcessor;
+pub(crate) use render::{render, samples};
+pub use render::{RenderOptions, WavFormat};
+pub use schedule::{ScheduleAt, Scheduler};
+
+use std::time::Duration;
+
+use crate::{
+    error::{AudioProcessingError, PdError, SizeError},
+    functions, PdAudioContext,
+};
+
+mod private {
+    use crate::PdAudioContext;
+
+    pub trait Sealed: Sized {
+        /// Processes the buffers through the instance of the context with the matching function.
+        fn process(context: &PdAudioContext, ticks: i32, input: &[Self], output: &mut [Self]);
+
+        /// Processes a tick of non-interleaved buffers with the matching raw function.
+        fn process_raw(context: &PdAudioContext, input: &[Self], output: &mut [Self]);
+    }
+}
+
+/// A sample format which pd can process, `f32`, `f64` or `i16`.
+///
+/// This trait is sealed, it is implemented for the formats which libpd has process functions for.
+pub trait Sample: Copy + Default + Send + private::Sealed + 'static {}
+
+impl Sample for f32 {}
+impl Sample for f64 {}
+impl Sample for i16 {}
+
+impl private::Sealed for f32 {
+    fn process(context: &PdAudioContext, ticks: i32, input: &[Self], output: &mut [Self]) {
+        context.process_float(ticks, input, output);
+    }
+
+    fn process_raw(context: &PdAudioContext, input: &[Self], output: &mut [Self]) {
+        context.process_raw(input, output);
+    }
+}
+
+impl private::Sealed for f64 {
+    fn process(context: &PdAudioContext, ticks: i32, input: &[Self], output: &mut [Self]) {
+        context.process_double(ticks, input, output);
+    }
+
+    fn process_raw(context: &PdAudioContext, input: &[Self], output: &mut [Self]) {
+        context.process_raw_double(input, output);
+    }
+}
+
+impl private::Sealed for i16 {
+    fn process(context: &PdAudioContext, ticks: i32, input: &[Self], output: &mut [Self]) {
+        context.process_short(ticks, input, output);
+    }
+
+    fn process_raw(context: &PdAudioContext, input: &[Self], output: &mut [Self]) {
+        context.process_raw_short(input, output);
+    }
+}
+
+/// Processes interleaved audio buffers through an instance after checking them against its channels.
+///
+/// The processor knows the input and output channel counts which the instance is configured with
+/// and the [`block_size`](crate::functions::block_size) of pd.
+/// It computes the ticks to process from the lengths of the buffers and returns an error instead of
+/// handing buffers to pd which it would read or write past the end of.
+///
+/// Both buffers should hold the same number of whole blocks,
+/// a buffer for a side without channels should be empty.
+///
+/// # Examples
+/// ```no_run
+/// use libpd_rs::Pd;
+///
+/// let pd = Pd::init_and_configure(1, 2, 44100).unwrap();
+/// let processor = pd.audio_context().processor();
+///
+/// // In the audio callback, 256 frames of one input and two output channels.
+/// let input = [0.0_f32; 256];
+/// let mut output = [0.0_f32; 512];
+/// processor.process_float(&input, &mut output).unwrap();
+///
+/// // Half a block is rejected.
+/// assert!(processor.process_float(&input[..32], &mut output[..64]).is_err());
+/// ```
+#[derive(Debug, Clone)]
+pub struct AudioProcessor {
+    context: PdAudioContext,
+    input_channels: usize,
+    output_channels: usize,
+    block_size: usize,
+}
+
+impl AudioProcessor {
+    /// Creates a processor for the instance of the context with its channel counts.
+    pub fn new(context: PdAudioContext) -> Self {
+        let input_channels = usize::try_from(context.input_channels()).unwrap_or_default();
+        let output_channels = usize::try_from(context.output_channels()).unwrap_or_default();
+        Self {
+            context,
+            input_channels,
+            output_channels,
+            block_size: usize::try_from(functions::block_size()).unwrap_or_default(),
+        }
+    }
+
+    /// Gets the audio context which the processor processes through.
+    pub const fn context(&self) -> &PdAudioContext {
+        &self.context
+    }
+
+    /// Gets the number of input channels which the input buffers are interleaved with.
+    pub const fn input_channels(&self) -> usize {
+        self.input_channels
+    }
+
+    /// Gets the number of output channels which the output buffers are interleaved with.
+    pub const fn output_channels(&self) -> usize {
+        self.output_channels
+    }
+
+    /// Gets the number of frames which pd processes in one tick.
+    pub const fn block_size(&self) -> usize {
+        self.block_size
+    }
+
+    /// Computes the ticks to process buffers of these lengths.
+    ///
+    /// The ticks are computed from the output buffer, or from the input buffer if there are no output channels.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`AudioProcessingError`](crate::error::AudioProcessingError)
+    ///   - [`NoChannels`](crate::error::AudioProcessingError::NoChannels)
+    ///   - [`PartialFrame`](crate::error::AudioProcessingError::PartialFrame)
+    ///   - [`PartialBlock`](crate::error::AudioProcessingError::PartialBlock)
+    ///   - [`MismatchedBuffers`](crate::error::AudioProcessingError::MismatchedBuffers)
+    /// - [`SizeError`](crate::error::SizeError)
+    ///   - [`TooLarge`](crate::error::SizeError::TooLarge)
+    pub fn ticks(&self, input_len: usize, output_len: usize) -> Result<i32, PdError> {
+        let frames = self.frames(input_len, output_len)?;
+        if frames.checked_rem(self.block_size) != Some(0) {
+            let channels = self.frame_channels();
+            return Err(AudioProcessingError::PartialBlock {
+                len: frames * channels,
+                block: self.block_size * channels,
+            }
+            .into());
+        }
+        let ticks = frames.checked_div(self.block_size).unwrap_or_default();
+        i32::try_from(ticks).map_err(|_| SizeError::TooLarge.into())
+    }
+
+    /// Processes interleaved buffers of any [`Sample`] format through the instance.
+    ///
+    /// # Errors
+    ///
+    /// The errors of [`ticks`](AudioProcessor::ticks), nothing is processed when one occurs.
+    pub fn process<T: Sample>(&self, input: &[T], output: &mut [T]) -> Result<(), PdError> {
+        let ticks = self.ticks(input.len(), output.len())?;
+        if ticks > 0 {
+            T::process(&self.context, ticks, input, output);
+        }
+        Ok(())
+    }
+
+    /// Processes interleaved buffers of `f32` through the instance.
+    ///
+    /// # Errors
+    ///
+    /// The errors of [`ticks`](AudioProcessor::ticks), nothing is processed when one occurs.
+    pub fn process_float(&self, input: &[f32], output: &mut [f32]) -> Result<(), PdError> {
+        self.process(input, output)
+    }
+
+    /// Processes interleaved buffers of `f64` through the instance.
+    ///
+    /// # Errors
+    ///
+    /// The errors of [`ticks`](AudioProcessor::ticks), nothing is processed when one occurs.
+    pub fn process_double(&self, input: &[f64], output: &mut [f64]) -> Result<(), PdError> {
+        self.process(input, output)
+    }
+
+    /// Processes interleaved buffers of `i16` through the instance.
+    ///
+    /// Samples are converted like [`process_short`](crate::functions::process::process_short) does.
+    ///
+    /// # Errors
+    ///
+    /// The errors of [`ticks`](AudioProcessor::ticks), nothing is processed when one occurs.
+    pub fn process_short(&self, input: &[i16], output: &mut [i16]) -> Result<(), PdError> {
+        self.process(input, output)
+    }
+
+    /// Processes a tick of non-interleaved buffers which are already checked.
+    fn process_raw<T: Sample>(&self, input: &[T], output: &mut [T]) {
+        T::process_raw(&self.context, input, output);
+    }
+
+    /// The number of channels which the frames are counted with, the output ones unless there are none.
+    const fn frame_channels(&self) -> usize {
+        if self.output_channels == 0 {
+            self.input_channels
+        } else {
+            self.output_channels
+        }
+    }
+
+    /// Computes the number of frames in buffers of these lengths, which don't need to be whole blocks.
+    fn frames(&self, input_len: usize, output_len: usize) -> Result<usize, PdError> {
+        let channels = self.frame_channels();
+        let len = if self.output_channels == 0 {
+            input_len
+        } else {
+            output_len
+        };
+        let Some(frames) = len.checked_div(channels) else {
+            return Err(AudioProcessingError::NoChannels.into());
+        };
+        if len.checked_rem(channels) != Some(0) {
+            return Err(AudioProcessingError::PartialFrame { len, channels }.into());
+        }
+        for (len, channels) in [
+            (input_len, self.input_channels),
+            (output_len, self.output_channels),
+        ] {
+            let expected = frames * channels;
+            if len != expected {
+                return Err(AudioProcessingError::MismatchedBuffers { len, expected }.into());
+            }
+        }
+        Ok(frames)
+    }
+}
+
+/// Copies `count` samples from one buffer to another.
+fn copy<'from, 'to, T: Sample>(
+    from: impl Iterator<Item = &'from T>,
+    to: impl Iterator<Item = &'to mut T>,
+    count: usize,
+) {
+    for (to, from) in to.zip(from).take(count) {
+        *to = *from;
+    }
+}
+
+/// Converts a duration to the number of frames which are played in it, rounding down.
+pub(crate) fn frames_in(duration: Duration, sample_rate: u32) -> Result<usize, PdError> {
+    (duration.as_nanos() * u128::from(sample_rate))
+        .checked_div(1_000_000_000)
+        .and_then(|frames| usize::try_from(frames).ok())
+        .ok_or_else(|| SizeError::TooLarge.into())
+}
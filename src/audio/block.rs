@@ -0,0 +1,111 @@
+use crate::{
+    audio::{copy, AudioProcessor, Sample},
+    error::PdError,
+};
+
+/// Processes interleaved buffers of any number of frames through an instance.
+///
+/// pd processes audio in blocks of [`block_size`](crate::functions::block_size) frames.
+/// The adapter collects the input of the host into a block and plays the output of the previous
+/// block meanwhile, so the output is delayed by a fixed [`latency`](BlockAdapter::latency) of one block.
+///
+/// The buffers of the blocks are allocated when the adapter is created,
+/// processing does not allocate so it is fine to be called in the audio callback.
+///
+/// # Examples
+/// ```no_run
+/// use libpd_rs::Pd;
+///
+/// let pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+/// let mut adapter = pd.audio_context().block_adapter::<f32>();
+/// assert_eq!(adapter.latency(), 64);
+///
+/// // In the audio callback, 441 frames of two output channels.
+/// let mut output = [0.0_f32; 882];
+/// adapter.process(&[], &mut output).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct BlockAdapter<T: Sample> {
+    processor: AudioProcessor,
+    /// The input which is collected for the next block.
+    input: Vec<T>,
+    /// The output of the last processed block.
+    output: Vec<T>,
+    /// The number of frames of the current block which are exchanged with the host.
+    position: usize,
+}
+
+impl<T: Sample> BlockAdapter<T> {
+    /// Creates an adapter for the processor, the first block of the output is silence.
+    pub fn new(processor: AudioProcessor) -> Self {
+        let block_size = processor.block_size();
+        Self {
+            input: vec![T::default(); block_size * processor.input_channels()],
+            output: vec![T::default(); block_size * processor.output_channels()],
+            processor,
+            position: 0,
+        }
+    }
+
+    /// Gets the processor which the blocks are processed with.
+    pub const fn processor(&self) -> &AudioProcessor {
+        &self.processor
+    }
+
+    /// Gets the number of frames which the output is delayed by, the block size of pd.
+    pub const fn latency(&self) -> usize {
+        self.processor.block_size()
+    }
+
+    /// Gets the number of frames of input which are waiting for the block to be completed.
+    pub const fn buffered_frames(&self) -> usize {
+        self.position
+    }
+
+    /// Processes interleaved buffers of the same number of frames, which can be any number.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`AudioProcessingError`](crate::error::AudioProcessingError)
+    ///   - [`NoChannels`](crate::error::AudioProcessingError::NoChannels)
+    ///   - [`PartialFrame`](crate::error::AudioProcessingError::PartialFrame)
+    ///   - [`MismatchedBuffers`](crate::error::AudioProcessingError::MismatchedBuffers)
+    ///
+    /// Nothing is processed when one occurs.
+    pub fn process(&mut self, input: &[T], output: &mut [T]) -> Result<(), PdError> {
+        let frames = self.processor.frames(input.len(), output.len())?;
+        let block_size = self.processor.block_size();
+        let input_channels = self.processor.input_channels();
+        let output_channels = self.processor.output_channels();
+
+        let mut done = 0;
+        while done < frames {
+            let count = (block_size - self.position).min(frames - done);
+            copy(
+                input.iter().skip(done * input_channels),
+                self.input.iter_mut().skip(self.position * input_channels),
+                count * input_channels,
+            );
+            copy(
+                self.output.iter().skip(self.position * output_channels),
+                output.iter_mut().skip(done * output_channels),
+                count * output_channels,
+            );
+            done += count;
+            self.position += count;
+            if self.position == block_size {
+                self.processor.process(&self.input, &mut self.output)?;
+                self.position = 0;
+            }
+        }
+        Ok(())
+    }
+
+    /// Forgets the collected input and the output which is not played yet, the next output starts with a silent block.
+    pub fn reset(&mut self) {
+        self.input.fill(T::default());
+        self.output.fill(T::default());
+        self.position = 0;
+    }
+}
@@ -0,0 +1,133 @@
+use crate::{
+    audio::{copy, AudioProcessor, Sample},
+    error::{AudioProcessingError, PdError},
+};
+
+/// Processes non-interleaved buffers, a buffer for each channel, through an instance.
+///
+/// Each tick the block of every channel is copied into the layout of
+/// [`process_raw`](crate::functions::process::process_raw), processed and copied out to the channels.
+/// The buffers of a block are allocated when the processor is created,
+/// processing does not allocate so it is fine to be called in the audio callback.
+///
+/// # Examples
+/// ```no_run
+/// use libpd_rs::Pd;
+///
+/// let pd = Pd::init_and_configure(1, 2, 44100).unwrap();
+/// let mut processor = pd.audio_context().planar_processor::<f32>();
+///
+/// // In the audio callback, 256 frames of one input and two output channels.
+/// let input = [0.0_f32; 256];
+/// let mut left = [0.0_f32; 256];
+/// let mut right = [0.0_f32; 256];
+/// processor
+///     .process(&[&input[..]], &mut [&mut left[..], &mut right[..]])
+///     .unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct PlanarProcessor<T: Sample> {
+    processor: AudioProcessor,
+    /// A block of every input channel one after another.
+    input: Vec<T>,
+    /// A block of every output channel one after another.
+    output: Vec<T>,
+}
+
+impl<T: Sample> PlanarProcessor<T> {
+    /// Creates a planar processor for the processor.
+    pub fn new(processor: AudioProcessor) -> Self {
+        let block_size = processor.block_size();
+        Self {
+            input: vec![T::default(); block_size * processor.input_channels()],
+            output: vec![T::default(); block_size * processor.output_channels()],
+            processor,
+        }
+    }
+
+    /// Gets the processor which knows the channels and the block size.
+    pub const fn processor(&self) -> &AudioProcessor {
+        &self.processor
+    }
+
+    /// Processes a buffer for each input channel into a buffer for each output channel.
+    ///
+    /// Every channel buffer should have the same number of frames, a whole number of blocks.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`AudioProcessingError`](crate::error::AudioProcessingError)
+    ///   - [`NoChannels`](crate::error::AudioProcessingError::NoChannels)
+    ///   - [`ChannelCount`](crate::error::AudioProcessingError::ChannelCount)
+    ///   - [`PartialBlock`](crate::error::AudioProcessingError::PartialBlock)
+    ///   - [`MismatchedBuffers`](crate::error::AudioProcessingError::MismatchedBuffers)
+    ///
+    /// Nothing is processed when one occurs.
+    pub fn process<I, O>(&mut self, input: &[I], output: &mut [O]) -> Result<(), PdError>
+    where
+        I: AsRef<[T]>,
+        O: AsMut<[T]>,
+    {
+        let block_size = self.processor.block_size();
+        let frames = self.frames(input, output)?;
+
+        for start in (0..frames).step_by(block_size) {
+            for (block, channel) in self.input.chunks_exact_mut(block_size).zip(input) {
+                copy(
+                    channel.as_ref().iter().skip(start),
+                    block.iter_mut(),
+                    block_size,
+                );
+            }
+            self.processor.process_raw(&self.input, &mut self.output);
+            for (block, channel) in self.output.chunks_exact(block_size).zip(&mut *output) {
+                copy(
+                    block.iter(),
+                    channel.as_mut().iter_mut().skip(start),
+                    block_size,
+                );
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks the channel buffers and gets the number of frames in them.
+    fn frames<I, O>(&self, input: &[I], output: &mut [O]) -> Result<usize, PdError>
+    where
+        I: AsRef<[T]>,
+        O: AsMut<[T]>,
+    {
+        for (len, expected) in [
+            (input.len(), self.processor.input_channels()),
+            (output.len(), self.processor.output_channels()),
+        ] {
+            if len != expected {
+                return Err(AudioProcessingError::ChannelCount { len, expected }.into());
+            }
+        }
+        let mut lengths = input
+            .iter()
+            .map(|channel| channel.as_ref().len())
+            .chain(output.iter_mut().map(|channel| channel.as_mut().len()));
+        let Some(frames) = lengths.next() else {
+            return Err(AudioProcessingError::NoChannels.into());
+        };
+        if let Some(len) = lengths.find(|len| *len != frames) {
+            return Err(AudioProcessingError::MismatchedBuffers {
+                len,
+                expected: frames,
+            }
+            .into());
+        }
+        let block_size = self.processor.block_size();
+        if frames.checked_rem(block_size) != Some(0) {
+            return Err(AudioProcessingError::PartialBlock {
+                len: frames,
+                block: block_size,
+            }
+            .into());
+        }
+        Ok(frames)
+    }
+}
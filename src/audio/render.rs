@@ -0,0 +1,184 @@
+use std::{
+    fs::File,
+    io::{BufReader, Seek, Write},
+    iter,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use hound::{SampleFormat, WavReader, WavSpec, WavWriter};
+
+use crate::{
+    audio::{frames_in, AudioProcessor, Scheduler},
+    error::{AudioProcessingError, PdError, SizeError, WavError},
+    midi::{MidiFile, MidiPlayer},
+};
+
+/// The format of the samples in a rendered wav file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WavFormat {
+    /// 32 bit float samples, which keep the output of pd as it is.
+    #[default]
+    Float32,
+    /// 16 bit integer samples, the output of pd is clipped to the range of `-1` to `1`.
+    Int16,
+    /// 24 bit integer samples, the output of pd is clipped to the range of `-1` to `1`.
+    Int24,
+}
+
+impl WavFormat {
+    const fn bits_per_sample(self) -> u16 {
+        match self {
+            Self::Float32 => 32,
+            Self::Int16 => 16,
+            Self::Int24 => 24,
+        }
+    }
+
+    const fn sample_format(self) -> SampleFormat {
+        match self {
+            Self::Float32 => SampleFormat::Float,
+            Self::Int16 | Self::Int24 => SampleFormat::Int,
+        }
+    }
+}
+
+/// Options for rendering audio offline with [`Pd::render_offline_with`](crate::Pd::render_offline_with).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RenderOptions {
+    /// The format of the samples in the rendered file.
+    pub format: WavFormat,
+    /// A wav file to play into the inputs of pd, the inputs are silent after it ends.
+    ///
+    /// The file should have the channels and the sample rate which pd is configured with.
+    pub input: Option<PathBuf>,
+    /// A MIDI file to play into pd from the start, see [`MidiPlayer`](crate::midi::MidiPlayer).
+    pub midi: Option<PathBuf>,
+}
+
+/// Renders the output of the instance of the processor to a wav file.
+pub fn render<W: Write + Seek>(
+    processor: &AudioProcessor,
+    sample_rate: i32,
+    duration: Duration,
+    options: &RenderOptions,
+    writer: W,
+) -> Result<(), PdError> {
+    let output_channels = processor.output_channels();
+    let spec = WavSpec {
+        channels: u16::try_from(output_channels).map_err(|_| SizeError::TooLarge)?,
+        sample_rate: u32::try_from(sample_rate).map_err(|_| SizeError::TooLarge)?,
+        bits_per_sample: options.format.bits_per_sample(),
+        sample_format: options.format.sample_format(),
+    };
+    if output_channels == 0 {
+        return Err(AudioProcessingError::NoChannels.into());
+    }
+    let frames = frames_in(duration, spec.sample_rate)?;
+
+    let mut input_samples = match &options.input {
+        Some(path) => input(path, processor, spec.sample_rate)?,
+        None => Box::new(iter::empty()),
+    };
+    let mut player = match &options.midi {
+        Some(path) => Some(MidiPlayer::new(&MidiFile::open(path)?, spec.sample_rate)?),
+        None => None,
+    };
+    let mut scheduler = Scheduler::new(processor.clone(), 0);
+    let mut writer = WavWriter::new(writer, spec).map_err(WavError::from)?;
+
+    let block_size = processor.block_size();
+    let mut input = vec![0.0f32; block_size * processor.input_channels()];
+    let mut output = vec![0.0f32; block_size * output_channels];
+    let mut rendered = 0;
+    while rendered < frames {
+        for sample in &mut input {
+            *sample = input_samples.next().transpose()?.unwrap_or_default();
+        }
+        if let Some(player) = &mut player {
+            player.schedule(&mut scheduler, block_size)?;
+        }
+        scheduler.process(&input, &mut output)?;
+        let count = block_size.min(frames - rendered);
+        for sample in output.iter().take(count * output_channels) {
+            write_sample(&mut writer, options.format, *sample)?;
+        }
+        rendered += count;
+    }
+    writer.finalize().map_err(WavError::from)?;
+    Ok(())
+}
+
+pub type Samples = Box<dyn Iterator<Item = Result<f32, PdError>>>;
+
+/// Opens a wav file to be played into the inputs and streams its samples in the range of `-1` to `1`.
+fn input(path: &Path, processor: &AudioProcessor, sample_rate: u32) -> Result<Samples, PdError> {
+    let reader = WavReader::open(path).map_err(WavError::from)?;
+    let spec = reader.spec();
+    let expected = processor.input_channels();
+    if usize::from(spec.channels) != expected {
+        return Err(WavError::ChannelCount {
+            channels: spec.channels,
+            expected,
+        }
+        .into());
+    }
+    if spec.sample_rate != sample_rate {
+        return Err(WavError::SampleRate {
+            sample_rate: spec.sample_rate,
+            expected: sample_rate,
+        }
+        .into());
+    }
+    Ok(samples(reader))
+}
+
+/// Streams the samples of a wav file in the range of `-1` to `1`.
+pub fn samples(reader: WavReader<BufReader<File>>) -> Samples {
+    let spec = reader.spec();
+    match spec.sample_format {
+        SampleFormat::Float => Box::new(
+            reader
+                .into_samples::<f32>()
+                .map(|sample| sample.map_err(|err| WavError::from(err).into())),
+        ),
+        SampleFormat::Int => {
+            let scale = 1.0 / f64::from(1u32 << spec.bits_per_sample.saturating_sub(1));
+            Box::new(reader.into_samples::<i32>().map(move |sample| {
+                sample
+                    .map(|sample| normalize(sample, scale))
+                    .map_err(|err| WavError::from(err).into())
+            }))
+        }
+    }
+}
+
+#[expect(
+    clippy::cast_possible_truncation,
+    reason = "Samples are in the range of `-1` to `1` which loses only precision as `f32`."
+)]
+fn normalize(sample: i32, scale: f64) -> f32 {
+    (f64::from(sample) * scale) as f32
+}
+
+#[expect(
+    clippy::cast_possible_truncation,
+    reason = "The sample is clamped to the range of the format before it is converted."
+)]
+fn write_sample<W: Write + Seek>(
+    writer: &mut WavWriter<W>,
+    format: WavFormat,
+    sample: f32,
+) -> Result<(), PdError> {
+    // The same scale as reading, so integer samples are written back as they are read.
+    let scale = f64::from(1u32 << (format.bits_per_sample() - 1));
+    let scaled = (f64::from(sample) * scale)
+        .round()
+        .clamp(-scale, scale - 1.0);
+    match format {
+        WavFormat::Float32 => writer.write_sample(sample),
+        WavFormat::Int16 => writer.write_sample(scaled as i16),
+        WavFormat::Int24 => writer.write_sample(scaled as i32),
+    }
+    .map_err(|err| WavError::from(err).into())
+}
@@ -0,0 +1,161 @@
+use std::{collections::VecDeque, time::Duration};
+
+use crate::{
+    audio::{frames_in, AudioProcessor, Sample},
+    error::{PdError, SizeError},
+    types::OutgoingMessage,
+};
+
+/// The logical time which a message is scheduled at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScheduleAt {
+    /// A frame from the start of the scheduler.
+    Frame(u64),
+    /// A time from the start of the scheduler.
+    Time(Duration),
+    /// A number of frames from the start of the next buffer which is processed.
+    Offset(usize),
+}
+
+/// Processes buffers through an instance and sends the scheduled messages right before the block they fall into.
+///
+/// Messages which are sent with the functions in [`send`](crate::functions::send) take effect at the start
+/// of the next buffer which is processed, wherever they are meant to be in it.
+/// The scheduler splits the buffers at the blocks of pd, so a message takes effect at the start of its
+/// block, which is as accurate as pd handles messages.
+///
+/// The logical time of the scheduler starts at `0` and advances with every frame it processes.
+/// Messages which are scheduled in the past are sent before the next block.
+///
+/// # Examples
+/// ```no_run
+/// use libpd_rs::{audio::ScheduleAt, types::OutgoingMessage, Pd};
+///
+/// let pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+/// let mut scheduler = pd.audio_context().scheduler(64);
+///
+/// // Sent before the third block of the next buffer.
+/// scheduler
+///     .schedule(
+///         ScheduleAt::Offset(130),
+///         OutgoingMessage::Bang {
+///             receiver: "kick".to_owned(),
+///         },
+///     )
+///     .unwrap();
+///
+/// // In the audio callback.
+/// let mut output = [0.0_f32; 1024];
+/// scheduler.process(&[], &mut output).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct Scheduler {
+    processor: AudioProcessor,
+    sample_rate: u32,
+    /// The logical time in frames, the first frame of the next buffer.
+    frame: u64,
+    /// The messages sorted by the frame they are scheduled at.
+    queue: VecDeque<(u64, OutgoingMessage)>,
+}
+
+impl Scheduler {
+    /// Creates a scheduler which has room for `capacity` messages before it allocates.
+    pub fn new(processor: AudioProcessor, capacity: usize) -> Self {
+        Self {
+            sample_rate: u32::try_from(processor.context().sample_rate()).unwrap_or_default(),
+            processor,
+            frame: 0,
+            queue: VecDeque::with_capacity(capacity),
+        }
+    }
+
+    /// Gets the processor which the blocks are processed with.
+    pub const fn processor(&self) -> &AudioProcessor {
+        &self.processor
+    }
+
+    /// Gets the logical time in frames, which is the first frame of the next buffer.
+    pub const fn frame(&self) -> u64 {
+        self.frame
+    }
+
+    /// Gets the logical time, which is the time of the first frame of the next buffer.
+    pub fn logical_time(&self) -> Duration {
+        let nanos = u128::from(self.frame) * 1_000_000_000;
+        let nanos = nanos
+            .checked_div(u128::from(self.sample_rate))
+            .unwrap_or_default();
+        Duration::from_nanos(u64::try_from(nanos).unwrap_or(u64::MAX))
+    }
+
+    /// Gets the number of messages which are waiting to be sent.
+    pub fn pending(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Schedules a message, messages which are scheduled at the same frame are sent in the order they are scheduled.
+    ///
+    /// This allocates only if there are more messages waiting than the capacity of the scheduler.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`SizeError`](crate::error::SizeError)
+    ///   - [`TooLarge`](crate::error::SizeError::TooLarge)
+    pub fn schedule(&mut self, at: ScheduleAt, message: OutgoingMessage) -> Result<(), PdError> {
+        let frame = match at {
+            ScheduleAt::Frame(frame) => Some(frame),
+            ScheduleAt::Time(time) => u64::try_from(frames_in(time, self.sample_rate)?).ok(),
+            ScheduleAt::Offset(offset) => u64::try_from(offset)
+                .ok()
+                .and_then(|offset| self.frame.checked_add(offset)),
+        }
+        .ok_or(SizeError::TooLarge)?;
+        let position = self
+            .queue
+            .partition_point(|(scheduled, _)| *scheduled <= frame);
+        self.queue.insert(position, (frame, message));
+        Ok(())
+    }
+
+    /// Removes the messages which are waiting to be sent.
+    pub fn clear(&mut self) {
+        self.queue.clear();
+    }
+
+    /// Processes interleaved buffers of whole blocks, sending the messages which are due before each block.
+    ///
+    /// A message which fails to be sent does not stop the processing,
+    /// the first error is returned after the buffers are processed.
+    ///
+    /// # Errors
+    ///
+    /// The errors of [`AudioProcessor::ticks`] and the errors of sending the messages,
+    /// see [`OutgoingMessage::send`](crate::types::OutgoingMessage::send).
+    pub fn process<T: Sample>(&mut self, input: &[T], output: &mut [T]) -> Result<(), PdError> {
+        let ticks = self.processor.ticks(input.len(), output.len())?;
+        let block_size = self.processor.block_size();
+        let input_block = block_size * self.processor.input_channels();
+        let output_block = block_size * self.processor.output_channels();
+        let block_frames = u64::try_from(block_size).map_err(|_| SizeError::TooLarge)?;
+
+        let mut result = Ok(());
+        for tick in 0..usize::try_from(ticks).unwrap_or_default() {
+            let end = self.frame + block_frames;
+            while let Some((_, message)) = self.queue.front().filter(|(frame, _)| *frame < end) {
+                let sent = self.processor.context().send(message);
+                if result.is_ok() {
+                    result = sent;
+                }
+                self.queue.pop_front();
+            }
+            let input = input.get(tick * input_block..(tick + 1) * input_block);
+            let output = output.get_mut(tick * output_block..(tick + 1) * output_block);
+            if let (Some(input), Some(output)) = (input, output) {
+                self.processor.process(input, output)?;
+            }
+            self.frame = end;
+        }
+        result
+    }
+}
@@ -0,0 +1,150 @@
+use std::{
+    collections::HashMap,
+    mem,
+    sync::{Arc, Mutex, MutexGuard, PoisonError},
+};
+
+use crate::{
+    error::SubscriptionError,
+    functions::receive::{
+        forward_messages, forward_print_and_midi, messages_are_hooked, print_or_midi_is_hooked,
+    },
+    types::{HookHandle, PdEvent, PdMessage},
+};
+
+/// A closure which is called with the messages of a single source.
+type Route = Box<dyn FnMut(&PdMessage) + Send>;
+
+/// The state which the listeners of the dispatcher feed.
+#[derive(Default)]
+struct Shared {
+    routes: HashMap<String, Route>,
+    /// The events which are not polled yet, `None` while events are not polled.
+    events: Option<Vec<PdEvent>>,
+}
+
+impl Shared {
+    fn dispatch(&mut self, event: PdEvent) {
+        if let PdEvent::Message(message) = &event {
+            if let Some(route) = self.routes.get_mut(message.source()) {
+                route(message);
+            }
+        }
+        if let Some(events) = &mut self.events {
+            events.push(event);
+        }
+    }
+}
+
+/// Feeds the closures which are registered for sources and the queue of polled events of an instance
+/// from a single set of listeners, so they do not replace each other.
+///
+/// The listeners are only registered while there is something to feed,
+/// they are not registered over listeners which are already registered on the instance
+/// like the closures which are set with the `on_*` functions.
+#[derive(Default)]
+pub struct Dispatcher {
+    shared: Arc<Mutex<Shared>>,
+    /// The listeners for messages, registered while there are routes or events are polled.
+    message_hooks: Vec<HookHandle>,
+    /// The listeners for MIDI events and console output, registered while events are polled.
+    event_hooks: Vec<HookHandle>,
+}
+
+impl Dispatcher {
+    /// Sets the closure for a source, replacing the previous one if there is any.
+    ///
+    /// The listeners are registered on the current instance if they are not registered yet,
+    /// nothing is changed if other listeners for messages are registered on it.
+    pub fn insert<F: FnMut(&PdMessage) + Send + 'static>(
+        &mut self,
+        source: &str,
+        route: F,
+    ) -> Result<(), SubscriptionError> {
+        self.register_messages()?;
+        self.lock()
+            .routes
+            .insert(source.to_owned(), Box::new(route));
+        Ok(())
+    }
+
+    /// Removes the closure for a source.
+    pub fn remove(&mut self, source: &str) {
+        self.lock().routes.remove(source);
+        self.release();
+    }
+
+    /// Removes the closures for all sources.
+    pub fn clear(&mut self) {
+        self.lock().routes.clear();
+        self.release();
+    }
+
+    /// Starts collecting events, the listeners are registered on the current instance if they are not registered yet.
+    ///
+    /// Nothing is changed if other listeners for messages, MIDI events or console output are registered on it.
+    pub fn start_polling(&mut self) -> Result<(), SubscriptionError> {
+        if self.event_hooks.is_empty() && print_or_midi_is_hooked() {
+            return Err(SubscriptionError::ListenersAlreadyRegistered);
+        }
+        self.register_messages()?;
+        self.lock().events.get_or_insert_with(Vec::new);
+        if self.event_hooks.is_empty() {
+            let shared = Arc::clone(&self.shared);
+            self.event_hooks = forward_print_and_midi(move |event| {
+                shared
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .dispatch(event);
+            });
+        }
+        Ok(())
+    }
+
+    /// Stops collecting events and drops the ones which are not polled yet.
+    pub fn stop_polling(&mut self) {
+        self.lock().events = None;
+        self.release();
+    }
+
+    /// Moves the collected events to the end of `events`.
+    pub fn drain(&self, events: &mut Vec<PdEvent>) {
+        if let Some(collected) = &mut self.lock().events {
+            events.append(collected);
+        }
+    }
+
+    fn register_messages(&mut self) -> Result<(), SubscriptionError> {
+        if self.message_hooks.is_empty() {
+            if messages_are_hooked() {
+                return Err(SubscriptionError::ListenersAlreadyRegistered);
+            }
+            let shared = Arc::clone(&self.shared);
+            self.message_hooks = forward_messages(move |message| {
+                shared
+                    .lock()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .dispatch(PdEvent::Message(message));
+            });
+        }
+        Ok(())
+    }
+
+    /// Unregisters the listeners which have nothing to feed anymore.
+    fn release(&mut self) {
+        let (routes, polling) = {
+            let shared = self.lock();
+            (!shared.routes.is_empty(), shared.events.is_some())
+        };
+        if !polling {
+            drop(mem::take(&mut self.event_hooks));
+            if !routes {
+                drop(mem::take(&mut self.message_hooks));
+            }
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Shared> {
+        self.shared.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
@@ -23,6 +23,9 @@ pub enum PdError {
     /// An error occurred during gui lifecycle.
     #[error(transparent)]
     GuiLifeCycleError(#[from] GuiLifeCycleError),
+    /// An error occurred during parsing a pd file.
+    #[error(transparent)]
+    PatchFileError(#[from] PatchFileError),
     /// An error occurred during general filesystem access.
     #[error(transparent)]
     IoError(#[from] IoError),
@@ -35,6 +38,15 @@ pub enum PdError {
     /// An error occurred related to sizes of entities.
     #[error(transparent)]
     SizeError(#[from] SizeError),
+    /// An error occurred during processing audio.
+    #[error(transparent)]
+    AudioProcessingError(#[from] AudioProcessingError),
+    /// An error occurred during reading or writing a wav file.
+    #[error(transparent)]
+    WavError(#[from] WavError),
+    /// An error occurred related to MIDI values and messages.
+    #[error(transparent)]
+    MidiError(#[from] MidiError),
     /// An error occurred related to pd arrays.
     #[error(transparent)]
     ArrayError(#[from] ArrayError),
@@ -138,6 +150,12 @@ pub enum SendError {
     /// A general error when the values which you are sending to the receiver are out of range.
     #[error("Values which are being sent are out of range.")]
     OutOfRange,
+    /// The receiving end of the channel which the message is sent through is dropped.
+    #[error("The receiving end of the channel is dropped.")]
+    Disconnected,
+    /// The queue which the message is sent through is full.
+    #[error("The queue of messages is full.")]
+    QueueFull,
     /// An error occurred related to string conversion.
     ///
     /// `CString` or `CStr` conversion error.
@@ -152,6 +170,10 @@ pub enum SubscriptionError {
     /// A failure of subscription to a sender with an unknown reason.
     #[error("Failed to subscribe to sender: `{0}` in loaded pd patch.")]
     FailedToSubscribeToSender(String),
+    /// Listeners which are registered on the instance, like the closures of the `on_*` methods,
+    /// would be replaced by the ones which are needed.
+    #[error("Listeners for the messages of pd are already registered on this instance.")]
+    ListenersAlreadyRegistered,
     /// An error occurred related to string conversion.
     ///
     /// `CString` or `CStr` conversion error.
@@ -176,6 +198,124 @@ pub enum SizeError {
     StringConversion(#[from] StringConversionError),
 }
 
+/// Errors related to the buffers which are processed.
+#[non_exhaustive]
+#[derive(Error, Debug)]
+pub enum AudioProcessingError {
+    /// There are no input or output channels to process.
+    #[error("There are no input or output channels to process.")]
+    NoChannels,
+    /// The number of channel buffers does not match the number of channels.
+    #[error("There are {len} channel buffers but the instance has {expected} channels.")]
+    ChannelCount {
+        /// The number of channel buffers.
+        len: usize,
+        /// The number of channels of the instance.
+        expected: usize,
+    },
+    /// The buffer does not hold a whole number of blocks.
+    #[error(
+        "The buffer has {len} samples which is not a whole number of blocks of {block} samples."
+    )]
+    PartialBlock {
+        /// The number of samples in the buffer.
+        len: usize,
+        /// The number of samples in a block of all the channels.
+        block: usize,
+    },
+    /// The buffer does not hold a whole number of frames.
+    #[error("The buffer has {len} samples which is not a whole number of frames of {channels} channels.")]
+    PartialFrame {
+        /// The number of samples in the buffer.
+        len: usize,
+        /// The number of channels in a frame.
+        channels: usize,
+    },
+    /// The buffer does not hold the same number of blocks or frames as the other one.
+    #[error("The buffer has {len} samples but {expected} samples are needed to process the same number of frames as the other buffer.")]
+    MismatchedBuffers {
+        /// The number of samples in the buffer.
+        len: usize,
+        /// The number of samples which are needed.
+        expected: usize,
+    },
+}
+
+/// Errors related to reading and writing wav files.
+#[non_exhaustive]
+#[derive(Error, Debug)]
+pub enum WavError {
+    /// The file could not be read or written as a wav file.
+    #[error("The wav file could not be read or written: {0}")]
+    InvalidFile(String),
+    /// The file does not have the number of channels which are needed.
+    #[error("The wav file has {channels} channels but {expected} channels are needed.")]
+    ChannelCount {
+        /// The number of channels in the file.
+        channels: u16,
+        /// The number of channels which are needed.
+        expected: usize,
+    },
+    /// The file does not have the sample rate which pd is configured with.
+    #[error("The wav file has a sample rate of {sample_rate} but pd runs at {expected}.")]
+    SampleRate {
+        /// The sample rate of the file.
+        sample_rate: u32,
+        /// The sample rate which pd is configured with.
+        expected: u32,
+    },
+}
+
+impl From<hound::Error> for WavError {
+    fn from(err: hound::Error) -> Self {
+        Self::InvalidFile(err.to_string())
+    }
+}
+
+/// Errors related to MIDI values and messages.
+#[non_exhaustive]
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum MidiError {
+    /// The value is out of the range of the MIDI type.
+    #[error("The value {value} is out of the range of 0 to {max}.")]
+    OutOfRange {
+        /// The value which is out of range.
+        value: i32,
+        /// The largest value of the type.
+        max: i32,
+    },
+    /// The bytes are not a single MIDI channel voice message.
+    #[error("The bytes are not a MIDI channel voice message: {0:02X?}")]
+    InvalidMessage(Vec<u8>),
+    /// The contents are not a valid standard MIDI file.
+    #[error("The MIDI file is invalid, {0}.")]
+    InvalidFile(String),
+    /// The MIDI file is of a format other than `0` or `1`.
+    #[error("The format {0} of the MIDI file is not supported.")]
+    UnsupportedFormat(u16),
+}
+
+/// Errors related to parsing the contents of a pd file.
+#[non_exhaustive]
+#[derive(Error, Debug)]
+pub enum PatchFileError {
+    /// The contents do not have a canvas record to be the root of the patch.
+    #[error("The contents do not have a `#N canvas` record.")]
+    MissingCanvas,
+    /// The contents end with a record which is not terminated by a semicolon.
+    #[error("The record is not terminated with a semicolon: `{0}`")]
+    UnterminatedRecord(String),
+    /// A record which the structure of the patch depends on could not be parsed.
+    #[error("The record could not be parsed: `{0}`")]
+    InvalidRecord(String),
+    /// A subpatch is not closed with a `#X restore` record.
+    #[error("The subpatch `{0}` is not closed with a `#X restore` record.")]
+    UnclosedSubpatch(String),
+    /// A `#X restore` record is found outside of a subpatch.
+    #[error("Found a `#X restore` record outside of a subpatch: `{0}`")]
+    UnexpectedRestore(String),
+}
+
 /// Errors related to pd arrays.
 #[non_exhaustive]
 #[derive(Error, Debug)]
@@ -186,6 +326,9 @@ pub enum ArrayError {
     /// The position in the array which is tried to be written is out of bounds.
     #[error("The position in array which you're trying to write is out of bounds.")]
     OutOfBounds,
+    /// An array with the name which is being tried to be created already exists.
+    #[error("An array with the name {0} already exists.")]
+    AlreadyExists(String),
     /// An error occurred related to string conversion.
     ///
     /// `CString` or `CStr` conversion error.
@@ -128,11 +128,11 @@ pub mod process;
 ///
 ///     // Register some listeners
 ///     // Print is a special one which is always listened from.
-///     on_print(|value| {
+///     let _print_hook = on_print(|value| {
 ///       println!("{value}");
 ///     });
 ///     
-///     on_float(|source, value| {
+///     let _float_hook = on_float(|source, value| {
 ///       assert_eq!(source, "float_from_pd");
 ///       assert_eq!(value, 42.0);
 ///       println!("{value} received from {source}");
@@ -240,11 +240,11 @@ pub mod send;
 ///
 ///     // Register some listeners
 ///     // Print is a special one which is always listened from.
-///     on_print(|value| {
+///     let _print_hook = on_print(|value| {
 ///       println!("{value}");
 ///     });
 ///     
-///     on_float(|source, value| {
+///     let _float_hook = on_float(|source, value| {
 ///       println!("{value} received from {source}");
 ///     });
 ///
@@ -523,6 +523,35 @@ pub fn add_to_search_paths<T: AsRef<Path>>(path: T) -> Result<(), IoError> {
 pub fn open_patch<T: AsRef<Path>>(
     path_to_patch: T,
 ) -> Result<PatchFileHandle, PatchLifeCycleError> {
+    let patch_path = resolve_patch_path(path_to_patch)?;
+    let file_name = patch_path
+        .file_name()
+        .ok_or(PatchLifeCycleError::FailedToOpenPatch)?
+        .to_string_lossy();
+    let directory = patch_path
+        .parent()
+        .unwrap_or_else(|| path::Path::new("/"))
+        .to_string_lossy();
+
+    // All good.
+    unsafe {
+        let name = CString::new(file_name.as_ref()).map_err(StringConversionError::from)?;
+        let directory = CString::new(directory.as_ref()).map_err(StringConversionError::from)?;
+
+        let file_handle =
+            libpd_sys::libpd_openfile(name.as_ptr(), directory.as_ptr()).cast::<ffi::c_void>();
+
+        if file_handle.is_null() {
+            return Err(PatchLifeCycleError::FailedToOpenPatch);
+        }
+        Ok(file_handle.into())
+    }
+}
+
+/// Finds the path of the patch file which [`open_patch`] opens.
+pub(crate) fn resolve_patch_path<T: AsRef<Path>>(
+    path_to_patch: T,
+) -> Result<PathBuf, PatchLifeCycleError> {
     let file_name = path_to_patch
         .as_ref()
         .file_name()
@@ -582,19 +611,7 @@ pub fn open_patch<T: AsRef<Path>>(
         ));
     }
 
-    // All good.
-    unsafe {
-        let name = CString::new(file_name).map_err(StringConversionError::from)?;
-        let directory = CString::new(directory).map_err(StringConversionError::from)?;
-
-        let file_handle =
-            libpd_sys::libpd_openfile(name.as_ptr(), directory.as_ptr()).cast::<ffi::c_void>();
-
-        if file_handle.is_null() {
-            return Err(PatchLifeCycleError::FailedToOpenPatch);
-        }
-        Ok(file_handle.into())
-    }
+    Ok(calculated_patch_path)
 }
 
 /// Closes a pd patch which has opened before.
@@ -5,24 +5,29 @@
     The use of expect there is ok because those strings are coming from Pd and pd uses C strings."
 )]
 
+pub(crate) mod hooks;
+
 use crate::{
     atom::{make_atom_list_from_t_atom_list, Atom},
     error::{StringConversionError, SubscriptionError, C_STR_FAILURE},
-    types::ReceiverHandle,
+    midi::{saturating_byte, Channel, U14, U7},
+    types::{EventReceiver, HookHandle, MessageReceiver, PdEvent, PdMessage, ReceiverHandle},
 };
+use hooks::{RawHook, Trampoline};
 
 use libffi::high::{
     ClosureMut1, ClosureMut2, ClosureMut3, ClosureMut4, FnPtr1, FnPtr2, FnPtr3, FnPtr4,
 };
 use libpd_sys::{
-    t_libpd_aftertouchhook, t_libpd_banghook, t_libpd_controlchangehook, t_libpd_doublehook,
-    t_libpd_floathook, t_libpd_listhook, t_libpd_messagehook, t_libpd_midibytehook,
-    t_libpd_noteonhook, t_libpd_pitchbendhook, t_libpd_polyaftertouchhook, t_libpd_printhook,
-    t_libpd_programchangehook, t_libpd_symbolhook,
+    _pdinstance, t_libpd_aftertouchhook, t_libpd_banghook, t_libpd_controlchangehook,
+    t_libpd_doublehook, t_libpd_floathook, t_libpd_listhook, t_libpd_messagehook,
+    t_libpd_midibytehook, t_libpd_noteonhook, t_libpd_pitchbendhook, t_libpd_polyaftertouchhook,
+    t_libpd_printhook, t_libpd_programchangehook, t_libpd_symbolhook,
 };
 use std::{
     ffi::{CStr, CString},
-    mem, os, slice,
+    os, slice,
+    sync::mpsc,
 };
 
 type PrintHookCodePtr = *const FnPtr1<'static, *const i8, ()>;
@@ -146,6 +151,8 @@ pub fn source_to_listen_from_exists<T: AsRef<str>>(sender: T) -> Result<bool, Su
 ///
 /// Note: Do not register this listener while pd DSP is running.
 ///
+/// The listener is unregistered when the returned [`HookHandle`] is dropped.
+///
 /// # Example
 /// ```rust
 /// use libpd_rs::functions::receive::{on_print};
@@ -153,35 +160,34 @@ pub fn source_to_listen_from_exists<T: AsRef<str>>(sender: T) -> Result<bool, Su
 ///
 /// let _main_instance = PdInstance::new().unwrap();
 ///
-/// on_print(|msg: &str| {
+/// let _hook = on_print(|msg: &str| {
 ///  println!("pd is printing: {msg}");
 /// });
 ///
 /// ```
-pub fn on_print<F: FnMut(&str) + Send + Sync + 'static>(mut user_provided_closure: F) {
-    let closure: &'static mut _ = Box::leak(Box::new(move |out: *const os::raw::c_char| {
+pub fn on_print<F: FnMut(&str) + Send + Sync + 'static>(
+    mut user_provided_closure: F,
+) -> HookHandle {
+    let state = Box::into_raw(Box::new(move |out: *const os::raw::c_char| {
         let out = unsafe { CStr::from_ptr(out).to_str().expect(C_STR_FAILURE) };
         user_provided_closure(out);
     }));
-    let callback = ClosureMut1::new(closure);
+    let callback = ClosureMut1::new(unsafe { &mut *state });
     let code = callback.code_ptr() as PrintHookCodePtr;
     let ptr = unsafe { *code.cast::<t_libpd_printhook>() };
-    mem::forget(callback);
 
-    unsafe {
-        libpd_sys::libpd_set_queued_printhook(Some(libpd_sys::libpd_print_concatenator));
-    };
-
-    // Always concatenate
-    unsafe {
-        libpd_sys::libpd_set_concatenated_printhook(ptr);
-    };
+    HookHandle::register(
+        RawHook::Print(ptr),
+        Some(Box::new(Trampoline::new(callback, state))),
+    )
 }
 
 /// Sets a closure to be called when a bang is received from a subscribed receiver
 ///
 /// Note: Do not register this listener while pd DSP is running.
 ///
+/// The listener is unregistered when the returned [`HookHandle`] is dropped.
+///
 /// # Example
 /// ```rust
 /// use libpd_rs::functions::receive::{on_bang, start_listening_from};
@@ -189,7 +195,7 @@ pub fn on_print<F: FnMut(&str) + Send + Sync + 'static>(mut user_provided_closur
 ///
 /// let _main_instance = PdInstance::new().unwrap();
 ///
-/// on_bang(|source: &str| {
+/// let _hook = on_bang(|source: &str| {
 ///   match source {
 ///     "foo" => println!("bang from foo"),   
 ///     "bar" => println!("bang from bar"),
@@ -201,19 +207,19 @@ pub fn on_print<F: FnMut(&str) + Send + Sync + 'static>(mut user_provided_closur
 /// let foo_receiver_handle = start_listening_from("foo").unwrap();
 /// let bar_receiver_handle = start_listening_from("bar").unwrap();
 /// ```
-pub fn on_bang<F: FnMut(&str) + Send + Sync + 'static>(mut user_provided_closure: F) {
-    let closure: &'static mut _ = Box::leak(Box::new(move |source: *const os::raw::c_char| {
+pub fn on_bang<F: FnMut(&str) + Send + Sync + 'static>(mut user_provided_closure: F) -> HookHandle {
+    let state = Box::into_raw(Box::new(move |source: *const os::raw::c_char| {
         let source = unsafe { CStr::from_ptr(source).to_str().expect(C_STR_FAILURE) };
         user_provided_closure(source);
     }));
-    let callback = ClosureMut1::new(closure);
+    let callback = ClosureMut1::new(unsafe { &mut *state });
     let code = callback.code_ptr() as BangHookCodePtr;
     let ptr = unsafe { *code.cast::<t_libpd_banghook>() };
-    mem::forget(callback);
 
-    unsafe {
-        libpd_sys::libpd_set_queued_banghook(ptr);
-    };
+    HookHandle::register(
+        RawHook::Bang(ptr),
+        Some(Box::new(Trampoline::new(callback, state))),
+    )
 }
 
 /// Sets a closure to be called when an `f32` is received from a subscribed receiver
@@ -224,6 +230,8 @@ pub fn on_bang<F: FnMut(&str) + Send + Sync + 'static>(mut user_provided_closure
 ///
 /// Note: Do not register this listener while pd DSP is running.
 ///
+/// The listener is unregistered when the returned [`HookHandle`] is dropped.
+///
 /// # Example
 /// ```rust
 /// use libpd_rs::functions::receive::{on_float, start_listening_from};
@@ -231,7 +239,7 @@ pub fn on_bang<F: FnMut(&str) + Send + Sync + 'static>(mut user_provided_closure
 ///
 /// let _main_instance = PdInstance::new().unwrap();
 ///
-/// on_float(|source: &str, value: f32| {
+/// let _hook = on_float(|source: &str, value: f32| {
 ///   match source {
 ///     "foo" =>  println!("Received a float from foo, value is: {value}"),  
 ///     "bar" =>  println!("Received a float from bar, value is: {value}"),
@@ -243,21 +251,23 @@ pub fn on_bang<F: FnMut(&str) + Send + Sync + 'static>(mut user_provided_closure
 /// let foo_receiver_handle = start_listening_from("foo").unwrap();
 /// let bar_receiver_handle = start_listening_from("bar").unwrap();
 /// ```
-pub fn on_float<F: FnMut(&str, f32) + Send + Sync + 'static>(mut user_provided_closure: F) {
-    let closure: &'static mut _ = Box::leak(Box::new(
+pub fn on_float<F: FnMut(&str, f32) + Send + Sync + 'static>(
+    mut user_provided_closure: F,
+) -> HookHandle {
+    let state = Box::into_raw(Box::new(
         move |source: *const os::raw::c_char, float: f32| {
             let source = unsafe { CStr::from_ptr(source).to_str().expect(C_STR_FAILURE) };
             user_provided_closure(source, float);
         },
     ));
-    let callback = ClosureMut2::new(closure);
+    let callback = ClosureMut2::new(unsafe { &mut *state });
     let code = callback.code_ptr() as FloatHookCodePtr;
     let ptr = unsafe { *code.cast::<t_libpd_floathook>() };
-    mem::forget(callback);
 
-    unsafe {
-        libpd_sys::libpd_set_queued_floathook(ptr);
-    };
+    HookHandle::register(
+        RawHook::Float(ptr),
+        Some(Box::new(Trampoline::new(callback, state))),
+    )
 }
 
 /// Sets a closure to be called when an `f64` is received from a subscribed receiver
@@ -268,6 +278,8 @@ pub fn on_float<F: FnMut(&str, f32) + Send + Sync + 'static>(mut user_provided_c
 ///
 /// Note: Do not register this listener while pd DSP is running.
 ///
+/// The listener is unregistered when the returned [`HookHandle`] is dropped.
+///
 /// # Example
 /// ```rust
 /// use libpd_rs::functions::receive::{on_double, start_listening_from};
@@ -275,7 +287,7 @@ pub fn on_float<F: FnMut(&str, f32) + Send + Sync + 'static>(mut user_provided_c
 ///
 /// let _main_instance = PdInstance::new().unwrap();
 ///
-/// on_double(|source: &str, value: f64| {
+/// let _hook = on_double(|source: &str, value: f64| {
 ///   match source {
 ///     "foo" =>  println!("Received a float from foo, value is: {value}"),  
 ///     "bar" =>  println!("Received a float from bar, value is: {value}"),
@@ -287,27 +299,31 @@ pub fn on_float<F: FnMut(&str, f32) + Send + Sync + 'static>(mut user_provided_c
 /// let foo_receiver_handle = start_listening_from("foo").unwrap();
 /// let bar_receiver_handle = start_listening_from("bar").unwrap();
 /// ```
-pub fn on_double<F: FnMut(&str, f64) + Send + Sync + 'static>(mut user_provided_closure: F) {
-    let closure: &'static mut _ = Box::leak(Box::new(
+pub fn on_double<F: FnMut(&str, f64) + Send + Sync + 'static>(
+    mut user_provided_closure: F,
+) -> HookHandle {
+    let state = Box::into_raw(Box::new(
         move |source: *const os::raw::c_char, double: f64| {
             let source = unsafe { CStr::from_ptr(source).to_str().expect(C_STR_FAILURE) };
             user_provided_closure(source, double);
         },
     ));
-    let callback = ClosureMut2::new(closure);
+    let callback = ClosureMut2::new(unsafe { &mut *state });
     let code = callback.code_ptr() as DoubleHookCodePtr;
     let ptr = unsafe { *code.cast::<t_libpd_doublehook>() };
-    mem::forget(callback);
 
-    unsafe {
-        libpd_sys::libpd_set_queued_doublehook(ptr);
-    };
+    HookHandle::register(
+        RawHook::Double(ptr),
+        Some(Box::new(Trampoline::new(callback, state))),
+    )
 }
 
 /// Sets a closure to be called when a symbol is received from a subscribed receiver
 ///
 /// Note: Do not register this listener while pd DSP is running.
 ///
+/// The listener is unregistered when the returned [`HookHandle`] is dropped.
+///
 /// # Example
 /// ```rust
 /// use libpd_rs::functions::receive::{on_symbol, start_listening_from};
@@ -315,7 +331,7 @@ pub fn on_double<F: FnMut(&str, f64) + Send + Sync + 'static>(mut user_provided_
 ///
 /// let _main_instance = PdInstance::new().unwrap();
 ///
-/// on_symbol(|source: &str, symbol: &str| {
+/// let _hook = on_symbol(|source: &str, symbol: &str| {
 ///   match source {
 ///     "foo" =>  println!("Received a float from foo, value is: {symbol}"),  
 ///     "bar" =>  println!("Received a float from bar, value is: {symbol}"),
@@ -327,28 +343,32 @@ pub fn on_double<F: FnMut(&str, f64) + Send + Sync + 'static>(mut user_provided_
 /// let foo_receiver_handle = start_listening_from("foo").unwrap();
 /// let bar_receiver_handle = start_listening_from("bar").unwrap();
 /// ```
-pub fn on_symbol<F: FnMut(&str, &str) + Send + Sync + 'static>(mut user_provided_closure: F) {
-    let closure: &'static mut _ = Box::leak(Box::new(
+pub fn on_symbol<F: FnMut(&str, &str) + Send + Sync + 'static>(
+    mut user_provided_closure: F,
+) -> HookHandle {
+    let state = Box::into_raw(Box::new(
         move |source: *const os::raw::c_char, symbol: *const os::raw::c_char| {
             let source = unsafe { CStr::from_ptr(source).to_str().expect(C_STR_FAILURE) };
             let symbol = unsafe { CStr::from_ptr(symbol).to_str().expect(C_STR_FAILURE) };
             user_provided_closure(source, symbol);
         },
     ));
-    let callback = ClosureMut2::new(closure);
+    let callback = ClosureMut2::new(unsafe { &mut *state });
     let code = callback.code_ptr() as SymbolHookCodePtr;
     let ptr = unsafe { *code.cast::<t_libpd_symbolhook>() };
 
-    mem::forget(callback);
-    unsafe {
-        libpd_sys::libpd_set_queued_symbolhook(ptr);
-    };
+    HookHandle::register(
+        RawHook::Symbol(ptr),
+        Some(Box::new(Trampoline::new(callback, state))),
+    )
 }
 
 /// Sets a closure to be called when a list is received from a subscribed receiver
 ///
 /// Note: Do not register this listener while pd DSP is running.
 ///
+/// The listener is unregistered when the returned [`HookHandle`] is dropped.
+///
 /// # Example
 /// ```rust
 /// use libpd_rs::functions::receive::{on_list, start_listening_from};
@@ -357,7 +377,7 @@ pub fn on_symbol<F: FnMut(&str, &str) + Send + Sync + 'static>(mut user_provided
 ///
 /// let _main_instance = PdInstance::new().unwrap();
 ///
-/// on_list(|source: &str, list: &[Atom]| match source {
+/// let _hook = on_list(|source: &str, list: &[Atom]| match source {
 ///     "foo" => {
 ///         for atom in list {
 ///             match atom {
@@ -391,8 +411,10 @@ pub fn on_symbol<F: FnMut(&str, &str) + Send + Sync + 'static>(mut user_provided
 /// let foo_receiver_handle = start_listening_from("foo").unwrap();
 /// let bar_receiver_handle = start_listening_from("bar").unwrap();
 /// ```
-pub fn on_list<F: FnMut(&str, &[Atom]) + Send + Sync + 'static>(mut user_provided_closure: F) {
-    let closure: &'static mut _ = Box::leak(Box::new(
+pub fn on_list<F: FnMut(&str, &[Atom]) + Send + Sync + 'static>(
+    mut user_provided_closure: F,
+) -> HookHandle {
+    let state = Box::into_raw(Box::new(
         move |source: *const os::raw::c_char,
               list_length: i32,
               atom_list: *mut libpd_sys::t_atom| {
@@ -407,14 +429,14 @@ pub fn on_list<F: FnMut(&str, &[Atom]) + Send + Sync + 'static>(mut user_provide
             user_provided_closure(source, &atoms);
         },
     ));
-    let callback = ClosureMut3::new(closure);
+    let callback = ClosureMut3::new(unsafe { &mut *state });
     let code = callback.code_ptr() as ListHookCodePtr;
     let ptr = unsafe { *code.cast::<t_libpd_listhook>() };
-    mem::forget(callback);
 
-    unsafe {
-        libpd_sys::libpd_set_queued_listhook(ptr);
-    };
+    HookHandle::register(
+        RawHook::List(ptr),
+        Some(Box::new(Trampoline::new(callback, state))),
+    )
 }
 
 /// Sets a closure to be called when a typed message is received from a subscribed receiver
@@ -433,6 +455,8 @@ pub fn on_list<F: FnMut(&str, &[Atom]) + Send + Sync + 'static>(mut user_provide
 ///
 /// Note: Do not register this listener while pd DSP is running.
 ///
+/// The listener is unregistered when the returned [`HookHandle`] is dropped.
+///
 /// # Example
 /// ```rust
 /// use libpd_rs::functions::receive::{on_message, start_listening_from};
@@ -441,7 +465,7 @@ pub fn on_list<F: FnMut(&str, &[Atom]) + Send + Sync + 'static>(mut user_provide
 ///
 /// let _main_instance = PdInstance::new().unwrap();
 ///
-/// on_message(|source: &str, message: &str, values: &[Atom]| match source {
+/// let _hook = on_message(|source: &str, message: &str, values: &[Atom]| match source {
 ///     "foo" => {
 ///         println!("Received a message from foo, message is: {message}");
 ///         for atom in values {
@@ -464,8 +488,8 @@ pub fn on_list<F: FnMut(&str, &[Atom]) + Send + Sync + 'static>(mut user_provide
 /// ```
 pub fn on_message<F: FnMut(&str, &str, &[Atom]) + Send + Sync + 'static>(
     mut user_provided_closure: F,
-) {
-    let closure: &'static mut _ = Box::leak(Box::new(
+) -> HookHandle {
+    let state = Box::into_raw(Box::new(
         move |source: *const os::raw::c_char,
               message: *const os::raw::c_char,
               list_length: i32,
@@ -482,20 +506,254 @@ pub fn on_message<F: FnMut(&str, &str, &[Atom]) + Send + Sync + 'static>(
             user_provided_closure(source, message, &atoms);
         },
     ));
-    let callback = ClosureMut4::new(closure);
+    let callback = ClosureMut4::new(unsafe { &mut *state });
     let code = callback.code_ptr() as MessageHookCodePtr;
     let ptr = unsafe { *code.cast::<t_libpd_messagehook>() };
-    mem::forget(callback);
 
-    unsafe {
-        libpd_sys::libpd_set_queued_messagehook(ptr);
-    };
+    HookHandle::register(
+        RawHook::Message(ptr),
+        Some(Box::new(Trampoline::new(callback, state))),
+    )
+}
+
+/// Registers listeners for all message types and returns a channel which receives them as [`PdMessage`]s.
+///
+/// This replaces the closures which are set with [`on_bang`], [`on_double`], [`on_symbol`], [`on_list`] and [`on_message`]
+/// with ones which forward every message to the returned [`MessageReceiver`].
+///
+/// The previous closures are restored when the [`MessageReceiver`] is dropped.
+///
+/// Messages arrive in the channel when [`receive_messages_from_pd`] is called,
+/// so the receiver can be consumed from any thread after the queue is drained.
+///
+/// Floats are received with [`on_double`] so they arrive without loss of precision.
+///
+/// Note: Do not register this listener while pd DSP is running.
+///
+/// # Example
+/// ```rust
+/// use libpd_rs::functions::receive::{message_receiver, receive_messages_from_pd, start_listening_from};
+/// use libpd_rs::instance::PdInstance;
+/// use libpd_rs::types::PdMessage;
+///
+/// let _main_instance = PdInstance::new().unwrap();
+///
+/// let messages = message_receiver();
+/// let foo_receiver_handle = start_listening_from("foo").unwrap();
+///
+/// receive_messages_from_pd();
+///
+/// for message in messages.try_iter() {
+///     match message {
+///         PdMessage::Float { source, value } => println!("Received a float from {source}, value is: {value}"),
+///         other => println!("Received {other:?}"),
+///     }
+/// }
+/// ```
+#[expect(
+    clippy::let_underscore_must_use,
+    clippy::let_underscore_untyped,
+    reason = "If the receiver is dropped there is no one to deliver the message to, so it is fine to drop the message."
+)]
+pub fn message_receiver() -> MessageReceiver {
+    let (sender, receiver) = mpsc::channel();
+    let hooks = forward_messages(move |message| {
+        let _ = sender.send(message);
+    });
+    MessageReceiver::new(receiver, hooks)
+}
+
+/// Registers listeners for messages, MIDI events and console output and returns a channel which receives them as [`PdEvent`]s.
+///
+/// This replaces all the closures which are set with the `on_*` functions in this module
+/// with ones which forward everything to the returned [`EventReceiver`].
+///
+/// The previous closures are restored when the [`EventReceiver`] is dropped.
+///
+/// Events arrive in the channel when [`receive_messages_from_pd`] and [`receive_midi_messages_from_pd`] are called.
+///
+/// Note: Do not register this listener while pd DSP is running.
+///
+/// # Example
+/// ```rust
+/// use libpd_rs::functions::receive::{event_receiver, receive_messages_from_pd, receive_midi_messages_from_pd};
+/// use libpd_rs::instance::PdInstance;
+/// use libpd_rs::types::PdEvent;
+///
+/// let _main_instance = PdInstance::new().unwrap();
+///
+/// let events = event_receiver();
+///
+/// receive_messages_from_pd();
+/// receive_midi_messages_from_pd();
+///
+/// for event in events.try_iter() {
+///     match event {
+///         PdEvent::Print(line) => println!("pd is printing: {line}"),
+///         other => println!("Received {other:?}"),
+///     }
+/// }
+/// ```
+#[expect(
+    clippy::let_underscore_must_use,
+    clippy::let_underscore_untyped,
+    reason = "If the receiver is dropped there is no one to deliver the event to, so it is fine to drop the event."
+)]
+pub fn event_receiver() -> EventReceiver {
+    let (sender, receiver) = mpsc::channel();
+    let hooks = forward_events(move |event| {
+        let _ = sender.send(event);
+    });
+    EventReceiver::new(receiver, hooks)
+}
+
+/// Registers listeners for messages, MIDI events and console output which pass them to `forward` as [`PdEvent`]s.
+pub(crate) fn forward_events<S: Fn(PdEvent) + Clone + Send + Sync + 'static>(
+    forward: S,
+) -> Vec<HookHandle> {
+    let message_forward = forward.clone();
+    let mut hooks = forward_messages(move |message| message_forward(message.into()));
+    hooks.extend(forward_print_and_midi(forward));
+    hooks
+}
+
+/// Registers listeners for MIDI events and console output which pass them to `forward` as [`PdEvent`]s.
+pub(crate) fn forward_print_and_midi<S: Fn(PdEvent) + Clone + Send + Sync + 'static>(
+    forward: S,
+) -> Vec<HookHandle> {
+    let print_forward = forward.clone();
+    let mut hooks = vec![on_print(move |line| {
+        print_forward(PdEvent::Print(line.to_owned()));
+    })];
+
+    let note_on_forward = forward.clone();
+    hooks.push(on_midi_note_on(move |channel, pitch, velocity| {
+        note_on_forward(PdEvent::NoteOn {
+            channel,
+            pitch,
+            velocity,
+        });
+    }));
+
+    let control_change_forward = forward.clone();
+    hooks.push(on_midi_control_change(move |channel, controller, value| {
+        control_change_forward(PdEvent::ControlChange {
+            channel,
+            controller,
+            value,
+        });
+    }));
+
+    let program_change_forward = forward.clone();
+    hooks.push(on_midi_program_change(move |channel, value| {
+        program_change_forward(PdEvent::ProgramChange { channel, value });
+    }));
+
+    let pitch_bend_forward = forward.clone();
+    hooks.push(on_midi_pitch_bend(move |channel, value| {
+        pitch_bend_forward(PdEvent::PitchBend { channel, value });
+    }));
+
+    let after_touch_forward = forward.clone();
+    hooks.push(on_midi_after_touch(move |channel, value| {
+        after_touch_forward(PdEvent::AfterTouch { channel, value });
+    }));
+
+    let poly_after_touch_forward = forward.clone();
+    hooks.push(on_midi_poly_after_touch(move |channel, pitch, value| {
+        poly_after_touch_forward(PdEvent::PolyAfterTouch {
+            channel,
+            pitch,
+            value,
+        });
+    }));
+
+    hooks.push(on_midi_byte(move |port, byte| {
+        forward(PdEvent::MidiByte { port, byte });
+    }));
+
+    hooks
+}
+
+/// Checks if any of the slots which [`forward_print_and_midi`] registers has a listener on the current instance.
+pub(crate) fn print_or_midi_is_hooked() -> bool {
+    hooks::any_registered(&[
+        RawHook::Print(None),
+        RawHook::NoteOn(None),
+        RawHook::ControlChange(None),
+        RawHook::ProgramChange(None),
+        RawHook::PitchBend(None),
+        RawHook::AfterTouch(None),
+        RawHook::PolyAfterTouch(None),
+        RawHook::MidiByte(None),
+    ])
+}
+
+/// Checks if any of the slots which [`forward_messages`] registers has a listener on the current instance.
+///
+/// Float and double listeners share a slot.
+pub(crate) fn messages_are_hooked() -> bool {
+    hooks::any_registered(&[
+        RawHook::Bang(None),
+        RawHook::Double(None),
+        RawHook::Symbol(None),
+        RawHook::List(None),
+        RawHook::Message(None),
+    ])
+}
+
+/// Registers listeners for all message types which pass them to `forward` as [`PdMessage`]s.
+pub(crate) fn forward_messages<S: Fn(PdMessage) + Clone + Send + Sync + 'static>(
+    forward: S,
+) -> Vec<HookHandle> {
+    let bang_forward = forward.clone();
+    let bang = on_bang(move |source| {
+        bang_forward(PdMessage::Bang {
+            source: source.to_owned(),
+        });
+    });
+
+    let double_forward = forward.clone();
+    let double = on_double(move |source, value| {
+        double_forward(PdMessage::Float {
+            source: source.to_owned(),
+            value,
+        });
+    });
+
+    let symbol_forward = forward.clone();
+    let symbol = on_symbol(move |source, value| {
+        symbol_forward(PdMessage::Symbol {
+            source: source.to_owned(),
+            value: value.to_owned(),
+        });
+    });
+
+    let list_forward = forward.clone();
+    let list = on_list(move |source, values| {
+        list_forward(PdMessage::List {
+            source: source.to_owned(),
+            values: values.to_vec(),
+        });
+    });
+
+    let message = on_message(move |source, message, values| {
+        forward(PdMessage::Message {
+            source: source.to_owned(),
+            message: message.to_owned(),
+            values: values.to_vec(),
+        });
+    });
+
+    vec![bang, double, symbol, list, message]
 }
 
 /// Receives messages from pd message queue.
 ///
 /// This should be called repeatedly in the **application's main loop** or the **audio callback** to fetch messages from pd.
 ///
+/// The closures of the listeners of this queue whose [`HookHandle`]s are dropped are freed after it.
+///
 /// # Example
 /// ```no_run
 /// use libpd_rs::functions::receive::{start_listening_from, on_symbol, receive_messages_from_pd};
@@ -503,7 +761,7 @@ pub fn on_message<F: FnMut(&str, &str, &[Atom]) + Send + Sync + 'static>(
 ///
 /// let _main_instance = PdInstance::new().unwrap();
 ///
-/// on_symbol(|source: &str, value: &str| {
+/// let _hook = on_symbol(|source: &str, value: &str| {
 ///   match source {
 ///     "foo" => println!("Received a float from foo, value is: {value}"),   
 ///     "bar" => println!("Received a float from bar, value is: {value}"),
@@ -522,248 +780,270 @@ pub fn receive_messages_from_pd() {
     unsafe {
         libpd_sys::libpd_queued_receive_pd_messages();
     };
+    hooks::free_retired(hooks::Queue::Messages);
 }
 
 /// Sets a closure to be called when a MIDI note on event is received.
 ///
 /// You do not need to register this listener explicitly.
 ///
-/// Channel is zero-indexed, pitch is `0-127` and velocity is `0-127`.
-///
-/// Channels encode MIDI ports via: `libpd_channel = pd_channel + 16 * pd_port`
+/// The port of the channel is decoded from the way libpd encodes it, see [`Channel`].
 ///
 /// Note:
 ///  - There is no note off message, a note on message with velocity = 0 is used instead.
 ///  - Out of range values which are sent from the patch are clamped.
 ///
+/// The listener is unregistered when the returned [`HookHandle`] is dropped.
+///
 /// # Example
 /// ```rust
 /// use libpd_rs::functions::receive::{on_midi_note_on};
 /// use libpd_rs::instance::PdInstance;
+/// use libpd_rs::midi::{Channel, U7};
 ///
 /// let _main_instance = PdInstance::new().unwrap();
 ///
 /// libpd_rs::functions::init();
 ///
-/// on_midi_note_on(|channel: i32, pitch: i32, velocity: i32| {
+/// let _hook = on_midi_note_on(|channel: Channel, pitch: U7, velocity: U7| {
 ///   println!("Note On: channel {channel}, pitch {pitch}, velocity {velocity}");
 /// });
 /// ```
-pub fn on_midi_note_on<F: FnMut(i32, i32, i32) + Send + Sync + 'static>(
+pub fn on_midi_note_on<F: FnMut(Channel, U7, U7) + Send + Sync + 'static>(
     mut user_provided_closure: F,
-) {
-    let closure: &'static mut _ =
-        Box::leak(Box::new(move |channel: i32, pitch: i32, velocity: i32| {
-            user_provided_closure(channel, pitch, velocity);
-        }));
-    let callback = ClosureMut3::new(closure);
+) -> HookHandle {
+    let state = Box::into_raw(Box::new(move |channel: i32, pitch: i32, velocity: i32| {
+        user_provided_closure(
+            Channel::saturating_from_raw(channel),
+            U7::saturating(pitch),
+            U7::saturating(velocity),
+        );
+    }));
+    let callback = ClosureMut3::new(unsafe { &mut *state });
     let code = callback.code_ptr() as MidiNoteOnCodePtr;
     let ptr = unsafe { *code.cast::<t_libpd_noteonhook>() };
-    mem::forget(callback);
 
-    unsafe {
-        libpd_sys::libpd_set_queued_noteonhook(ptr);
-    };
+    HookHandle::register(
+        RawHook::NoteOn(ptr),
+        Some(Box::new(Trampoline::new(callback, state))),
+    )
 }
 
 /// Sets a closure to be called when a MIDI control change event is received.
 ///
 /// You do not need to register this listener explicitly.
 ///
-/// Channel is zero-indexed, controller is `0-127` and value is `0-127`.
-///
-/// Channels encode MIDI ports via: `libpd_channel = pd_channel + 16 * pd_port`
+/// The port of the channel is decoded from the way libpd encodes it, see [`Channel`].
 ///
 /// Note: Out of range values which are sent from the patch are clamped.
 ///
+/// The listener is unregistered when the returned [`HookHandle`] is dropped.
+///
 /// # Example
 /// ```rust
 /// use libpd_rs::functions::receive::{on_midi_control_change};
 /// use libpd_rs::instance::PdInstance;
+/// use libpd_rs::midi::{Channel, U7};
 ///
 /// let _main_instance = PdInstance::new().unwrap();
 ///
-/// on_midi_control_change(|channel: i32, controller: i32, value: i32| {
+/// let _hook = on_midi_control_change(|channel: Channel, controller: U7, value: U7| {
 ///   println!("Control Change: channel {channel}, controller number {controller}, value {value}");
 /// });
 /// ```
-pub fn on_midi_control_change<F: FnMut(i32, i32, i32) + Send + Sync + 'static>(
+pub fn on_midi_control_change<F: FnMut(Channel, U7, U7) + Send + Sync + 'static>(
     mut user_provided_closure: F,
-) {
-    let closure: &'static mut _ = Box::leak(Box::new(
+) -> HookHandle {
+    let state = Box::into_raw(Box::new(
         move |channel: i32, controller: i32, value: i32| {
-            user_provided_closure(channel, controller, value);
+            user_provided_closure(
+                Channel::saturating_from_raw(channel),
+                U7::saturating(controller),
+                U7::saturating(value),
+            );
         },
     ));
-    let callback = ClosureMut3::new(closure);
+    let callback = ClosureMut3::new(unsafe { &mut *state });
     let code = callback.code_ptr() as MidiControlChangeCodePtr;
     let ptr = unsafe { *code.cast::<t_libpd_controlchangehook>() };
-    mem::forget(callback);
 
-    unsafe {
-        libpd_sys::libpd_set_queued_controlchangehook(ptr);
-    };
+    HookHandle::register(
+        RawHook::ControlChange(ptr),
+        Some(Box::new(Trampoline::new(callback, state))),
+    )
 }
 
 /// Sets a closure to be called when a MIDI program change event is received.
 ///
 /// You do not need to register this listener explicitly.
 ///
-/// Channel is zero-indexed, value is `0-127`.
-///
-/// Channels encode MIDI ports via: `libpd_channel = pd_channel + 16 * pd_port`
+/// The port of the channel is decoded from the way libpd encodes it, see [`Channel`].
 ///
 /// Note: Out of range values which are sent from the patch are clamped.
 ///
+/// The listener is unregistered when the returned [`HookHandle`] is dropped.
+///
 /// # Example
 /// ```rust
 /// use libpd_rs::functions::receive::{on_midi_program_change};
 /// use libpd_rs::instance::PdInstance;
+/// use libpd_rs::midi::{Channel, U7};
 ///
 /// let _main_instance = PdInstance::new().unwrap();
 ///
-/// on_midi_program_change(|channel: i32, value: i32| {
+/// let _hook = on_midi_program_change(|channel: Channel, value: U7| {
 ///   println!("Program Change: channel {channel}, program number {value}");
 /// });
 /// ```
-pub fn on_midi_program_change<F: FnMut(i32, i32) + Send + Sync + 'static>(
+pub fn on_midi_program_change<F: FnMut(Channel, U7) + Send + Sync + 'static>(
     mut user_provided_closure: F,
-) {
-    let closure: &'static mut _ = Box::leak(Box::new(move |channel: i32, value: i32| {
-        user_provided_closure(channel, value);
+) -> HookHandle {
+    let state = Box::into_raw(Box::new(move |channel: i32, value: i32| {
+        user_provided_closure(Channel::saturating_from_raw(channel), U7::saturating(value));
     }));
-    let callback = ClosureMut2::new(closure);
+    let callback = ClosureMut2::new(unsafe { &mut *state });
     let code = callback.code_ptr() as MidiProgramChangeCodePtr;
     let ptr = unsafe { *code.cast::<t_libpd_programchangehook>() };
-    mem::forget(callback);
 
-    unsafe {
-        libpd_sys::libpd_set_queued_programchangehook(ptr);
-    };
+    HookHandle::register(
+        RawHook::ProgramChange(ptr),
+        Some(Box::new(Trampoline::new(callback, state))),
+    )
 }
 
 /// Sets a closure to be called when a MIDI pitch bend event is received.
 ///
 /// You do not need to register this listener explicitly.
 ///
-/// Channel is zero-indexed, value is `-8192 to 8192`.
+/// The port of the channel is decoded from the way libpd encodes it, see [`Channel`].
 ///
-/// Channels encode MIDI ports via: `libpd_channel = pd_channel + 16 * pd_port`
+/// The value is `0-16383` like `|bendin|` outputs, [`U14::CENTER`] is no bend.
 ///
-/// Note:
-///  - `|bendin|` object in pd outputs 0-16383 while `|bendout|` accepts `-8192 to +8192`.
-///  - Out of range values which are sent from the patch are clamped.
+/// Note: Out of range values which are sent from the patch are clamped.
+///
+/// The listener is unregistered when the returned [`HookHandle`] is dropped.
 ///
 /// # Example
 /// ```rust
 /// use libpd_rs::functions::receive::{on_midi_pitch_bend};
 /// use libpd_rs::instance::PdInstance;
+/// use libpd_rs::midi::{Channel, U14};
 ///
 /// let _main_instance = PdInstance::new().unwrap();
 ///
-/// on_midi_pitch_bend(|channel: i32, value: i32| {
+/// let _hook = on_midi_pitch_bend(|channel: Channel, value: U14| {
 ///   println!("Pitch Bend: channel {channel}, bend amount {value}");
 /// });
 /// ```
-pub fn on_midi_pitch_bend<F: FnMut(i32, i32) + Send + Sync + 'static>(
+pub fn on_midi_pitch_bend<F: FnMut(Channel, U14) + Send + Sync + 'static>(
     mut user_provided_closure: F,
-) {
-    let closure: &'static mut _ = Box::leak(Box::new(move |channel: i32, value: i32| {
-        user_provided_closure(channel, value);
+) -> HookHandle {
+    let state = Box::into_raw(Box::new(move |channel: i32, value: i32| {
+        user_provided_closure(
+            Channel::saturating_from_raw(channel),
+            U14::saturating_from_bend(value),
+        );
     }));
-    let callback = ClosureMut2::new(closure);
+    let callback = ClosureMut2::new(unsafe { &mut *state });
     let code = callback.code_ptr() as MidiPitchBendCodePtr;
     let ptr = unsafe { *code.cast::<t_libpd_pitchbendhook>() };
-    mem::forget(callback);
 
-    unsafe {
-        libpd_sys::libpd_set_queued_pitchbendhook(ptr);
-    };
+    HookHandle::register(
+        RawHook::PitchBend(ptr),
+        Some(Box::new(Trampoline::new(callback, state))),
+    )
 }
 
 /// Sets a closure to be called when a MIDI after touch event is received.
 ///
 /// You do not need to register this listener explicitly.
 ///
-/// Channel is zero-indexed, value is `0-127`.
-///
-/// Channels encode MIDI ports via: `libpd_channel = pd_channel + 16 * pd_port`
+/// The port of the channel is decoded from the way libpd encodes it, see [`Channel`].
 ///
 /// Note: Out of range values which are sent from the patch are clamped.
 ///
+/// The listener is unregistered when the returned [`HookHandle`] is dropped.
+///
 /// # Example
 /// ```rust
 /// use libpd_rs::functions::receive::{on_midi_after_touch};
 /// use libpd_rs::instance::PdInstance;
+/// use libpd_rs::midi::{Channel, U7};
 ///
 /// let _main_instance = PdInstance::new().unwrap();
 ///
-/// on_midi_after_touch(|channel: i32, value: i32| {
+/// let _hook = on_midi_after_touch(|channel: Channel, value: U7| {
 ///   println!("After Touch: channel {channel}, after touch amount {value}");
 /// });
 /// ```
-pub fn on_midi_after_touch<F: FnMut(i32, i32) + Send + Sync + 'static>(
+pub fn on_midi_after_touch<F: FnMut(Channel, U7) + Send + Sync + 'static>(
     mut user_provided_closure: F,
-) {
-    let closure: &'static mut _ = Box::leak(Box::new(move |channel: i32, value: i32| {
-        user_provided_closure(channel, value);
+) -> HookHandle {
+    let state = Box::into_raw(Box::new(move |channel: i32, value: i32| {
+        user_provided_closure(Channel::saturating_from_raw(channel), U7::saturating(value));
     }));
-    let callback = ClosureMut2::new(closure);
+    let callback = ClosureMut2::new(unsafe { &mut *state });
     let code = callback.code_ptr() as MidiAfterTouchCodePtr;
     let ptr = unsafe { *code.cast::<t_libpd_aftertouchhook>() };
-    mem::forget(callback);
 
-    unsafe {
-        libpd_sys::libpd_set_queued_aftertouchhook(ptr);
-    };
+    HookHandle::register(
+        RawHook::AfterTouch(ptr),
+        Some(Box::new(Trampoline::new(callback, state))),
+    )
 }
 
 /// Sets a closure to be called when a MIDI poly after touch event is received.
 ///
 /// You do not need to register this listener explicitly.
 ///
-/// Channel is zero-indexed, pitch is `0-127` and value is `0-127`.
-///
-/// Channels encode MIDI ports via: `libpd_channel = pd_channel + 16 * pd_port`
+/// The port of the channel is decoded from the way libpd encodes it, see [`Channel`].
 ///
 /// Note: Out of range values which are sent from the patch are clamped.
 ///
+/// The listener is unregistered when the returned [`HookHandle`] is dropped.
+///
 /// # Example
 /// ```rust
 /// use libpd_rs::functions::receive::{on_midi_poly_after_touch};
 /// use libpd_rs::instance::PdInstance;
+/// use libpd_rs::midi::{Channel, U7};
 ///
 /// let _main_instance = PdInstance::new().unwrap();
 ///
-/// on_midi_poly_after_touch(|channel: i32, pitch: i32, value: i32| {
+/// let _hook = on_midi_poly_after_touch(|channel: Channel, pitch: U7, value: U7| {
 ///   println!("Poly After Touch: channel {channel}, pitch {pitch}, after touch amount {value}");
 /// });
 /// ```
-pub fn on_midi_poly_after_touch<F: FnMut(i32, i32, i32) + Send + Sync + 'static>(
+pub fn on_midi_poly_after_touch<F: FnMut(Channel, U7, U7) + Send + Sync + 'static>(
     mut user_provided_closure: F,
-) {
-    let closure: &'static mut _ =
-        Box::leak(Box::new(move |channel: i32, pitch: i32, value: i32| {
-            user_provided_closure(channel, pitch, value);
-        }));
-    let callback = ClosureMut3::new(closure);
+) -> HookHandle {
+    let state = Box::into_raw(Box::new(move |channel: i32, pitch: i32, value: i32| {
+        user_provided_closure(
+            Channel::saturating_from_raw(channel),
+            U7::saturating(pitch),
+            U7::saturating(value),
+        );
+    }));
+    let callback = ClosureMut3::new(unsafe { &mut *state });
     let code = callback.code_ptr() as MidiPolyAfterTouchCodePtr;
     let ptr = unsafe { *code.cast::<t_libpd_polyaftertouchhook>() };
-    mem::forget(callback);
 
-    unsafe {
-        libpd_sys::libpd_set_queued_polyaftertouchhook(ptr);
-    };
+    HookHandle::register(
+        RawHook::PolyAfterTouch(ptr),
+        Some(Box::new(Trampoline::new(callback, state))),
+    )
 }
 
 /// Sets a closure to be called when a single raw MIDI byte is received.
 ///
 /// You do not need to register this listener explicitly.
 ///
-/// Port is zero-indexed and byte is `0-255`
+/// Port is zero-indexed.
 ///
 /// Note: Out of range values which are sent from the patch are clamped.
 ///
+/// The listener is unregistered when the returned [`HookHandle`] is dropped.
+///
 /// # Example
 /// ```rust
 /// use libpd_rs::functions::receive::{on_midi_byte};
@@ -771,26 +1051,30 @@ pub fn on_midi_poly_after_touch<F: FnMut(i32, i32, i32) + Send + Sync + 'static>
 ///
 /// let _main_instance = PdInstance::new().unwrap();
 ///
-/// on_midi_byte(|port: i32, byte: i32| {
+/// let _hook = on_midi_byte(|port: u8, byte: u8| {
 ///   println!("Raw MIDI Byte: port {port}, byte {byte}");
 /// });
 /// ```
-pub fn on_midi_byte<F: FnMut(i32, i32) + Send + Sync + 'static>(mut user_provided_closure: F) {
-    let closure: &'static mut _ = Box::leak(Box::new(move |port: i32, byte: i32| {
-        user_provided_closure(port, byte);
+pub fn on_midi_byte<F: FnMut(u8, u8) + Send + Sync + 'static>(
+    mut user_provided_closure: F,
+) -> HookHandle {
+    let state = Box::into_raw(Box::new(move |port: i32, byte: i32| {
+        user_provided_closure(saturating_byte(port), saturating_byte(byte));
     }));
-    let callback = ClosureMut2::new(closure);
+    let callback = ClosureMut2::new(unsafe { &mut *state });
     let code = callback.code_ptr() as MidiByteCodePtr;
     let ptr = unsafe { *code.cast::<t_libpd_midibytehook>() };
-    mem::forget(callback);
 
-    unsafe {
-        libpd_sys::libpd_set_queued_midibytehook(ptr);
-    };
+    HookHandle::register(
+        RawHook::MidiByte(ptr),
+        Some(Box::new(Trampoline::new(callback, state))),
+    )
 }
 
 /// Receives messages from pd midi message queue.
 ///
+/// The closures of the MIDI listeners whose [`HookHandle`]s are dropped are freed after it.
+///
 /// This should be called repeatedly in the **application's main loop** or the **audio callback** to fetch MIDI messages from pd.
 ///
 /// # Example
@@ -800,7 +1084,7 @@ pub fn on_midi_byte<F: FnMut(i32, i32) + Send + Sync + 'static>(mut user_provide
 ///
 /// let _main_instance = PdInstance::new().unwrap();
 ///
-/// on_midi_byte(|port: i32, byte: i32| {
+/// let _hook = on_midi_byte(|port: u8, byte: u8| {
 ///     println!("{port}, {byte}");
 /// });
 ///
@@ -812,4 +1096,12 @@ pub fn receive_midi_messages_from_pd() {
     unsafe {
         libpd_sys::libpd_queued_receive_midi_messages();
     };
+    hooks::free_retired(hooks::Queue::Midi);
+}
+
+/// Forgets the listeners of an instance which is about to be freed.
+///
+/// Their handles become no-ops when they are dropped.
+pub(crate) fn forget_hooks_of_instance(instance: *mut _pdinstance) {
+    hooks::forget_instance(instance);
 }
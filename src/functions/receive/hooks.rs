@@ -0,0 +1,286 @@
+use libpd_sys::{
+    _pdinstance, t_libpd_aftertouchhook, t_libpd_banghook, t_libpd_controlchangehook,
+    t_libpd_doublehook, t_libpd_floathook, t_libpd_listhook, t_libpd_messagehook,
+    t_libpd_midibytehook, t_libpd_noteonhook, t_libpd_pitchbendhook, t_libpd_polyaftertouchhook,
+    t_libpd_printhook, t_libpd_programchangehook, t_libpd_symbolhook,
+};
+use std::{
+    mem::{self, ManuallyDrop},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Mutex, PoisonError,
+    },
+};
+
+use crate::instance::ActiveInstanceGuard;
+
+/// A hook function pointer for one of the hook slots of libpd.
+///
+/// `None` clears the slot.
+#[derive(Debug, Clone, Copy)]
+pub enum RawHook {
+    Print(t_libpd_printhook),
+    Bang(t_libpd_banghook),
+    Float(t_libpd_floathook),
+    Double(t_libpd_doublehook),
+    Symbol(t_libpd_symbolhook),
+    List(t_libpd_listhook),
+    Message(t_libpd_messagehook),
+    NoteOn(t_libpd_noteonhook),
+    ControlChange(t_libpd_controlchangehook),
+    ProgramChange(t_libpd_programchangehook),
+    PitchBend(t_libpd_pitchbendhook),
+    AfterTouch(t_libpd_aftertouchhook),
+    PolyAfterTouch(t_libpd_polyaftertouchhook),
+    MidiByte(t_libpd_midibytehook),
+}
+
+impl RawHook {
+    /// Checks if both hooks are for the same slot.
+    ///
+    /// Float and double hooks share a slot, libpd clears one when the other is set.
+    fn same_slot(&self, other: &Self) -> bool {
+        let numeric = |hook: &Self| matches!(hook, Self::Float(_) | Self::Double(_));
+        (numeric(self) && numeric(other)) || mem::discriminant(self) == mem::discriminant(other)
+    }
+
+    /// An empty hook for the same slot.
+    const fn cleared(self) -> Self {
+        match self {
+            Self::Print(_) => Self::Print(None),
+            Self::Bang(_) => Self::Bang(None),
+            Self::Float(_) => Self::Float(None),
+            Self::Double(_) => Self::Double(None),
+            Self::Symbol(_) => Self::Symbol(None),
+            Self::List(_) => Self::List(None),
+            Self::Message(_) => Self::Message(None),
+            Self::NoteOn(_) => Self::NoteOn(None),
+            Self::ControlChange(_) => Self::ControlChange(None),
+            Self::ProgramChange(_) => Self::ProgramChange(None),
+            Self::PitchBend(_) => Self::PitchBend(None),
+            Self::AfterTouch(_) => Self::AfterTouch(None),
+            Self::PolyAfterTouch(_) => Self::PolyAfterTouch(None),
+            Self::MidiByte(_) => Self::MidiByte(None),
+        }
+    }
+
+    /// The queue of libpd which calls the hook when it is drained.
+    const fn queue(&self) -> Queue {
+        match self {
+            Self::Print(_)
+            | Self::Bang(_)
+            | Self::Float(_)
+            | Self::Double(_)
+            | Self::Symbol(_)
+            | Self::List(_)
+            | Self::Message(_) => Queue::Messages,
+            Self::NoteOn(_)
+            | Self::ControlChange(_)
+            | Self::ProgramChange(_)
+            | Self::PitchBend(_)
+            | Self::AfterTouch(_)
+            | Self::PolyAfterTouch(_)
+            | Self::MidiByte(_) => Queue::Midi,
+        }
+    }
+
+    /// Sets the hook in its slot for the current instance.
+    fn install(self) {
+        match self {
+            Self::Print(hook) => {
+                // Always concatenate
+                let concatenator: t_libpd_printhook =
+                    hook.and(Some(libpd_sys::libpd_print_concatenator));
+                unsafe { libpd_sys::libpd_set_queued_printhook(concatenator) };
+                unsafe { libpd_sys::libpd_set_concatenated_printhook(hook) };
+            }
+            Self::Bang(hook) => unsafe { libpd_sys::libpd_set_queued_banghook(hook) },
+            Self::Float(hook) => unsafe { libpd_sys::libpd_set_queued_floathook(hook) },
+            Self::Double(hook) => unsafe { libpd_sys::libpd_set_queued_doublehook(hook) },
+            Self::Symbol(hook) => unsafe { libpd_sys::libpd_set_queued_symbolhook(hook) },
+            Self::List(hook) => unsafe { libpd_sys::libpd_set_queued_listhook(hook) },
+            Self::Message(hook) => unsafe { libpd_sys::libpd_set_queued_messagehook(hook) },
+            Self::NoteOn(hook) => unsafe { libpd_sys::libpd_set_queued_noteonhook(hook) },
+            Self::ControlChange(hook) => unsafe {
+                libpd_sys::libpd_set_queued_controlchangehook(hook);
+            },
+            Self::ProgramChange(hook) => unsafe {
+                libpd_sys::libpd_set_queued_programchangehook(hook);
+            },
+            Self::PitchBend(hook) => unsafe { libpd_sys::libpd_set_queued_pitchbendhook(hook) },
+            Self::AfterTouch(hook) => unsafe { libpd_sys::libpd_set_queued_aftertouchhook(hook) },
+            Self::PolyAfterTouch(hook) => unsafe {
+                libpd_sys::libpd_set_queued_polyaftertouchhook(hook);
+            },
+            Self::MidiByte(hook) => unsafe { libpd_sys::libpd_set_queued_midibytehook(hook) },
+        }
+    }
+}
+
+/// The queues of libpd which call the hooks when they are drained.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Queue {
+    /// The queue which [`receive_messages_from_pd`](crate::functions::receive::receive_messages_from_pd) drains.
+    Messages,
+    /// The queue which [`receive_midi_messages_from_pd`](crate::functions::receive::receive_midi_messages_from_pd) drains.
+    Midi,
+}
+
+/// The closures of an unregistered hook which may still be called by a drain which is in progress in another thread.
+struct Retired {
+    instance: usize,
+    queue: Queue,
+    _trampoline: Box<dyn Send>,
+}
+
+/// A registered hook.
+///
+/// Registrations of the same slot of the same instance form a stack in the registry,
+/// the last one is the one which is installed in libpd.
+struct Registration {
+    id: u64,
+    instance: usize,
+    hook: RawHook,
+}
+
+impl Registration {
+    fn same_slot(&self, other: &Self) -> bool {
+        self.instance == other.instance && self.hook.same_slot(&other.hook)
+    }
+}
+
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+static REGISTRY: Mutex<Vec<Registration>> = Mutex::new(Vec::new());
+static RETIRED: Mutex<Vec<Retired>> = Mutex::new(Vec::new());
+
+/// Installs the hook for the current instance and records it, returns the id of the registration.
+pub fn register(hook: RawHook) -> u64 {
+    let id = NEXT_ID.fetch_add(1, Ordering::Relaxed);
+    let instance = unsafe { libpd_sys::libpd_this_instance() } as usize;
+    let mut registry = REGISTRY.lock().unwrap_or_else(PoisonError::into_inner);
+    hook.install();
+    registry.push(Registration { id, instance, hook });
+    id
+}
+
+/// Checks if any of the slots of the hooks has a registration for the current instance.
+pub fn any_registered(hooks: &[RawHook]) -> bool {
+    let instance = unsafe { libpd_sys::libpd_this_instance() } as usize;
+    REGISTRY
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .iter()
+        .any(|registration| {
+            registration.instance == instance
+                && hooks.iter().any(|hook| registration.hook.same_slot(hook))
+        })
+}
+
+/// Removes the registration, if it was the installed one the previous registration of the same slot
+/// is installed back or the slot is cleared if there is none.
+///
+/// A drain of the queue of the hook may have loaded it before it is replaced, so its closures are kept
+/// until the next drain of that queue is finished, see [`free_retired`].
+#[expect(
+    clippy::significant_drop_tightening,
+    reason = "The registry stays locked until the restored hook is installed so a concurrent registration can not be overwritten."
+)]
+pub fn unregister(id: u64, trampoline: Option<Box<dyn Send>>) {
+    let mut registry = REGISTRY.lock().unwrap_or_else(PoisonError::into_inner);
+    let Some(position) = registry
+        .iter()
+        .position(|registration| registration.id == id)
+    else {
+        // The instance is already freed, nothing can call the closures.
+        return;
+    };
+    let removed = registry.remove(position);
+    if let Some(trampoline) = trampoline {
+        RETIRED
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push(Retired {
+                instance: removed.instance,
+                queue: removed.hook.queue(),
+                _trampoline: trampoline,
+            });
+    }
+    if registry
+        .iter()
+        .skip(position)
+        .any(|registration| registration.same_slot(&removed))
+    {
+        // A later registration is installed, nothing to restore.
+        return;
+    }
+    let previous = registry
+        .iter()
+        .take(position)
+        .rev()
+        .find(|registration| registration.same_slot(&removed))
+        .map_or_else(|| removed.hook.cleared(), |registration| registration.hook);
+
+    let _guard = ActiveInstanceGuard::activate(removed.instance as *mut _pdinstance);
+    previous.install();
+}
+
+/// Frees the closures of the hooks of a queue of the current instance which were unregistered
+/// before the drain of the queue which has just finished.
+pub fn free_retired(queue: Queue) {
+    let instance = unsafe { libpd_sys::libpd_this_instance() } as usize;
+    free_retired_where(|retired| retired.instance == instance && retired.queue == queue);
+}
+
+fn free_retired_where<P: FnMut(&Retired) -> bool>(mut predicate: P) {
+    let mut retired = RETIRED.lock().unwrap_or_else(PoisonError::into_inner);
+    let (freed, kept): (Vec<Retired>, Vec<Retired>) = mem::take(&mut *retired)
+        .into_iter()
+        .partition(|retired| predicate(retired));
+    *retired = kept;
+    drop(retired);
+    // The closures are dropped after the lock is released.
+    drop(freed);
+}
+
+/// Drops all registrations of an instance which is about to be freed.
+pub fn forget_instance(instance: *mut _pdinstance) {
+    REGISTRY
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .retain(|registration| registration.instance != instance as usize);
+    free_retired_where(|retired| retired.instance == instance as usize);
+}
+
+/// Owns a boxed closure and the libffi closure which is created from it.
+///
+/// Both are freed together when this is dropped.
+pub struct Trampoline<C, F> {
+    closure: ManuallyDrop<C>,
+    state: *mut F,
+}
+
+impl<C, F> Trampoline<C, F> {
+    /// Wraps the libffi closure and the closure state it points to which is obtained by [`Box::into_raw`].
+    pub const fn new(closure: C, state: *mut F) -> Self {
+        Self {
+            closure: ManuallyDrop::new(closure),
+            state,
+        }
+    }
+}
+
+impl<C, F> Drop for Trampoline<C, F> {
+    fn drop(&mut self) {
+        // The libffi closure points to the state, so it needs to go first.
+        unsafe { ManuallyDrop::drop(&mut self.closure) };
+        drop(unsafe { Box::from_raw(self.state) });
+    }
+}
+
+// The state is only ever touched by pd through the libffi closure
+// and the user provided closures are required to be `Send`.
+#[expect(
+    clippy::non_send_fields_in_send_ty,
+    reason = "The libffi closure only holds pointers to its own allocation and to the state which is owned here."
+)]
+unsafe impl<C, F: Send> Send for Trampoline<C, F> {}
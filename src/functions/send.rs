@@ -1,6 +1,7 @@
 use crate::{
     atom::{make_t_atom_list_from_atom_list, Atom},
     error::{PdError, SendError, SizeError, StringConversionError},
+    midi::{Channel, MidiMessage, U14, U7},
 };
 
 use std::ffi::CString;
@@ -462,9 +463,7 @@ pub fn send_message_to<T: AsRef<str>>(
 
 /// Sends a MIDI note on message to `|notein|` objects in pd.
 ///
-/// Channel is zero-indexed, pitch is `0-127` and velocity is `0-127`.
-///
-/// Channels encode MIDI ports via: `libpd_channel = pd_channel + 16 * pd_port`
+/// The port of the channel is encoded in the way libpd expects, see [`Channel`].
 ///
 /// Note: There is no note off message, send a note on with velocity = 0 instead.
 ///
@@ -472,25 +471,29 @@ pub fn send_message_to<T: AsRef<str>>(
 /// ```rust
 /// use libpd_rs::functions::send::{send_note_on};
 /// use libpd_rs::instance::PdInstance;
+/// use libpd_rs::midi::{Channel, U7};
 ///
 /// let _main_instance = PdInstance::new().unwrap();
+/// let channel = Channel::new(0).unwrap();
+/// let pitch = U7::new(48).unwrap();
+/// let velocity = U7::new(64).unwrap();
 ///
 /// // Handle the error if the receiver object is not found
-/// send_note_on(0, 48, 64).unwrap_or_else(|err| {
+/// send_note_on(channel, pitch, velocity).unwrap_or_else(|err| {
 ///   dbg!("{err}");
 /// });
 /// // or don't care..
-/// let _ = send_note_on(0, 48, 64);
+/// let _ = send_note_on(channel, pitch, velocity);
 /// ```
 ///
 /// # Errors
 ///
 /// A list of errors that can occur:
 /// - [`OutOfRange`](crate::error::SendError::OutOfRange)
-pub fn send_note_on(channel: i32, pitch: i32, velocity: i32) -> Result<(), SendError> {
+pub fn send_note_on(channel: Channel, pitch: U7, velocity: U7) -> Result<(), SendError> {
     unsafe {
         // Returns 0 on success or -1 if an argument is out of range
-        match libpd_sys::libpd_noteon(channel, pitch, velocity) {
+        match libpd_sys::libpd_noteon(channel.raw(), pitch.into(), velocity.into()) {
             0 => Ok(()),
             _ => Err(SendError::OutOfRange),
         }
@@ -499,33 +502,35 @@ pub fn send_note_on(channel: i32, pitch: i32, velocity: i32) -> Result<(), SendE
 
 /// Sends a MIDI control change message to `ctlin` objects in pd.
 ///
-/// Channel is zero-indexed, controller is `0-127` and value is `0-127`.
-///
-/// Channels encode MIDI ports via: `libpd_channel = pd_channel + 16 * pd_port`
+/// The port of the channel is encoded in the way libpd expects, see [`Channel`].
 ///
 /// # Example
 /// ```rust
 /// use libpd_rs::functions::send::{send_control_change};
 /// use libpd_rs::instance::PdInstance;
+/// use libpd_rs::midi::{Channel, U7};
 ///
 /// let _main_instance = PdInstance::new().unwrap();
+/// let channel = Channel::new(0).unwrap();
+/// let controller = U7::new(0).unwrap();
+/// let value = U7::new(64).unwrap();
 ///
 /// // Handle the error if the receiver object is not found
-/// send_control_change(0, 0, 64).unwrap_or_else(|err| {
+/// send_control_change(channel, controller, value).unwrap_or_else(|err| {
 ///   dbg!("{err}");
 /// });
 /// // or don't care..
-/// let _ = send_control_change(0, 0, 64);
+/// let _ = send_control_change(channel, controller, value);
 /// ```
 ///
 /// # Errors
 ///
 /// A list of errors that can occur:
 /// - [`OutOfRange`](crate::error::SendError::OutOfRange)
-pub fn send_control_change(channel: i32, controller: i32, value: i32) -> Result<(), SendError> {
+pub fn send_control_change(channel: Channel, controller: U7, value: U7) -> Result<(), SendError> {
     unsafe {
         // Returns 0 on success or -1 if an argument is out of range
-        match libpd_sys::libpd_controlchange(channel, controller, value) {
+        match libpd_sys::libpd_controlchange(channel.raw(), controller.into(), value.into()) {
             0 => Ok(()),
             _ => Err(SendError::OutOfRange),
         }
@@ -534,33 +539,34 @@ pub fn send_control_change(channel: i32, controller: i32, value: i32) -> Result<
 
 /// Sends a MIDI program change message to `pgmin` objects in pd.
 ///
-/// Channel is zero-indexed, value is `0-127`.
-///
-/// Channels encode MIDI ports via: `libpd_channel = pd_channel + 16 * pd_port`
+/// The port of the channel is encoded in the way libpd expects, see [`Channel`].
 ///
 /// # Example
 /// ```rust
 /// use libpd_rs::functions::send::{send_program_change};
 /// use libpd_rs::instance::PdInstance;
+/// use libpd_rs::midi::{Channel, U7};
 ///
 /// let _main_instance = PdInstance::new().unwrap();
+/// let channel = Channel::new(0).unwrap();
+/// let program = U7::new(42).unwrap();
 ///
 /// // Handle the error if the receiver object is not found
-/// send_program_change(0, 42).unwrap_or_else(|err| {
+/// send_program_change(channel, program).unwrap_or_else(|err| {
 ///   dbg!("{err}");
 /// });
 /// // or don't care..
-/// let _ = send_program_change(0, 42);
+/// let _ = send_program_change(channel, program);
 /// ```
 ///
 /// # Errors
 ///
 /// A list of errors that can occur:
 /// - [`OutOfRange`](crate::error::SendError::OutOfRange)
-pub fn send_program_change(channel: i32, value: i32) -> Result<(), SendError> {
+pub fn send_program_change(channel: Channel, program: U7) -> Result<(), SendError> {
     unsafe {
         // Returns 0 on success or -1 if an argument is out of range
-        match libpd_sys::libpd_programchange(channel, value) {
+        match libpd_sys::libpd_programchange(channel.raw(), program.into()) {
             0 => Ok(()),
             _ => Err(SendError::OutOfRange),
         }
@@ -569,35 +575,35 @@ pub fn send_program_change(channel: i32, value: i32) -> Result<(), SendError> {
 
 /// Sends a MIDI pitch bend message to `|bendin|` objects in pd.
 ///
-/// Channel is zero-indexed, value is `-8192 to 8192`.
+/// The port of the channel is encoded in the way libpd expects, see [`Channel`].
 ///
-/// Channels encode MIDI ports via: `libpd_channel = pd_channel + 16 * pd_port`
-///
-/// Note: `|bendin|` outputs 0-16383 while `|bendout|` accepts `-8192 to 8192`
+/// The value is `0-16383` like `|bendin|` outputs, [`U14::CENTER`] is no bend.
 ///
 /// # Example
 /// ```rust
 /// use libpd_rs::functions::send::{send_pitch_bend};
 /// use libpd_rs::instance::PdInstance;
+/// use libpd_rs::midi::{Channel, U14};
 ///
 /// let _main_instance = PdInstance::new().unwrap();
+/// let channel = Channel::new(0).unwrap();
 ///
 /// // Handle the error if the receiver object is not found
-/// send_pitch_bend(0, 8192).unwrap_or_else(|err| {
+/// send_pitch_bend(channel, U14::CENTER).unwrap_or_else(|err| {
 ///   dbg!("{err}");
 /// });
 /// // or don't care..
-/// let _ = send_pitch_bend(0, 8192);
+/// let _ = send_pitch_bend(channel, U14::CENTER);
 /// ```
 ///
 /// # Errors
 ///
 /// A list of errors that can occur:
 /// - [`OutOfRange`](crate::error::SendError::OutOfRange)
-pub fn send_pitch_bend(channel: i32, value: i32) -> Result<(), SendError> {
+pub fn send_pitch_bend(channel: Channel, value: U14) -> Result<(), SendError> {
     unsafe {
         // Returns 0 on success or -1 if an argument is out of range
-        match libpd_sys::libpd_pitchbend(channel, value) {
+        match libpd_sys::libpd_pitchbend(channel.raw(), value.bend()) {
             0 => Ok(()),
             _ => Err(SendError::OutOfRange),
         }
@@ -606,33 +612,34 @@ pub fn send_pitch_bend(channel: i32, value: i32) -> Result<(), SendError> {
 
 /// Sends a MIDI after touch message to `|touchin|` objects in pd.
 ///
-/// Channel is zero-indexed, value is `0-127`.
-///
-/// Channels encode MIDI ports via: `libpd_channel = pd_channel + 16 * pd_port`
+/// The port of the channel is encoded in the way libpd expects, see [`Channel`].
 ///
 /// # Example
 /// ```rust
 /// use libpd_rs::functions::send::{send_after_touch};
 /// use libpd_rs::instance::PdInstance;
+/// use libpd_rs::midi::{Channel, U7};
 ///
 /// let _main_instance = PdInstance::new().unwrap();
+/// let channel = Channel::new(0).unwrap();
+/// let value = U7::new(42).unwrap();
 ///
 /// // Handle the error if the receiver object is not found
-/// send_after_touch(0, 42).unwrap_or_else(|err| {
+/// send_after_touch(channel, value).unwrap_or_else(|err| {
 ///   dbg!("{err}");
 /// });
 /// // or don't care..
-/// let _ = send_after_touch(0, 42);
+/// let _ = send_after_touch(channel, value);
 /// ```
 ///
 /// # Errors
 ///
 /// A list of errors that can occur:
 /// - [`OutOfRange`](crate::error::SendError::OutOfRange)
-pub fn send_after_touch(channel: i32, value: i32) -> Result<(), SendError> {
+pub fn send_after_touch(channel: Channel, value: U7) -> Result<(), SendError> {
     unsafe {
         // Returns 0 on success or -1 if an argument is out of range
-        match libpd_sys::libpd_aftertouch(channel, value) {
+        match libpd_sys::libpd_aftertouch(channel.raw(), value.into()) {
             0 => Ok(()),
             _ => Err(SendError::OutOfRange),
         }
@@ -641,42 +648,88 @@ pub fn send_after_touch(channel: i32, value: i32) -> Result<(), SendError> {
 
 /// Sends a MIDI poly after touch message to `|polytouchin|` objects in pd.
 ///
-/// Channel is zero-indexed, pitch is `0-127` and value is `0-127`.
-///
-/// Channels encode MIDI ports via: `libpd_channel = pd_channel + 16 * pd_port`
+/// The port of the channel is encoded in the way libpd expects, see [`Channel`].
 ///
 /// # Example
 /// ```rust
 /// use libpd_rs::functions::send::{send_poly_after_touch};
 /// use libpd_rs::instance::PdInstance;
+/// use libpd_rs::midi::{Channel, U7};
 ///
 /// let _main_instance = PdInstance::new().unwrap();
+/// let channel = Channel::new(0).unwrap();
+/// let pitch = U7::new(48).unwrap();
+/// let value = U7::new(64).unwrap();
 ///
 /// // Handle the error if the receiver object is not found
-/// send_poly_after_touch(0, 48, 64).unwrap_or_else(|err| {
+/// send_poly_after_touch(channel, pitch, value).unwrap_or_else(|err| {
 ///   dbg!("{err}");
 /// });
 /// // or don't care..
-/// let _ = send_poly_after_touch(0, 48, 64);
+/// let _ = send_poly_after_touch(channel, pitch, value);
 /// ```
 ///
 /// # Errors
 ///
 /// A list of errors that can occur:
 /// - [`OutOfRange`](crate::error::SendError::OutOfRange)
-pub fn send_poly_after_touch(channel: i32, pitch: i32, value: i32) -> Result<(), SendError> {
+pub fn send_poly_after_touch(channel: Channel, pitch: U7, value: U7) -> Result<(), SendError> {
     unsafe {
         // Returns 0 on success or -1 if an argument is out of range
-        match libpd_sys::libpd_polyaftertouch(channel, pitch, value) {
+        match libpd_sys::libpd_polyaftertouch(channel.raw(), pitch.into(), value.into()) {
             0 => Ok(()),
             _ => Err(SendError::OutOfRange),
         }
     }
 }
 
+/// Sends a MIDI channel voice message to the objects in pd which receive its kind.
+///
+/// A [`NoteOff`](MidiMessage::NoteOff) is sent as a note on with velocity = 0, its release velocity is dropped.
+///
+/// # Example
+/// ```rust
+/// use libpd_rs::functions::send::send_midi;
+/// use libpd_rs::instance::PdInstance;
+/// use libpd_rs::midi::MidiMessage;
+///
+/// let _main_instance = PdInstance::new().unwrap();
+///
+/// let message = MidiMessage::try_from(&[0x90, 48, 64][..]).unwrap();
+/// send_midi(message).unwrap();
+/// ```
+///
+/// # Errors
+///
+/// A list of errors that can occur:
+/// - [`OutOfRange`](crate::error::SendError::OutOfRange)
+pub fn send_midi(message: MidiMessage) -> Result<(), SendError> {
+    match message {
+        MidiMessage::NoteOff { channel, pitch, .. } => send_note_on(channel, pitch, U7::MIN),
+        MidiMessage::NoteOn {
+            channel,
+            pitch,
+            velocity,
+        } => send_note_on(channel, pitch, velocity),
+        MidiMessage::PolyAfterTouch {
+            channel,
+            pitch,
+            value,
+        } => send_poly_after_touch(channel, pitch, value),
+        MidiMessage::ControlChange {
+            channel,
+            controller,
+            value,
+        } => send_control_change(channel, controller, value),
+        MidiMessage::ProgramChange { channel, program } => send_program_change(channel, program),
+        MidiMessage::AfterTouch { channel, value } => send_after_touch(channel, value),
+        MidiMessage::PitchBend { channel, value } => send_pitch_bend(channel, value),
+    }
+}
+
 /// Sends a raw MIDI byte to `|midiin|` objects in pd.
 ///
-/// Port is zero-indexed and byte is `0-255`
+/// Port is zero-indexed.
 ///
 /// # Example
 /// ```rust
@@ -697,10 +750,10 @@ pub fn send_poly_after_touch(channel: i32, pitch: i32, value: i32) -> Result<(),
 ///
 /// A list of errors that can occur:
 /// - [`OutOfRange`](crate::error::SendError::OutOfRange)
-pub fn send_midi_byte(port: i32, byte: i32) -> Result<(), SendError> {
+pub fn send_midi_byte(port: u8, byte: u8) -> Result<(), SendError> {
     unsafe {
         // Returns 0 on success or -1 if an argument is out of range
-        match libpd_sys::libpd_midibyte(port, byte) {
+        match libpd_sys::libpd_midibyte(port.into(), byte.into()) {
             0 => Ok(()),
             _ => Err(SendError::OutOfRange),
         }
@@ -709,7 +762,7 @@ pub fn send_midi_byte(port: i32, byte: i32) -> Result<(), SendError> {
 
 /// Sends a raw MIDI byte to `|sysexin|` objects in pd.
 ///
-/// Port is zero-indexed and byte is `0-255`
+/// Port is zero-indexed.
 ///
 /// # Example
 /// ```rust
@@ -730,10 +783,10 @@ pub fn send_midi_byte(port: i32, byte: i32) -> Result<(), SendError> {
 ///
 /// A list of errors that can occur:
 /// - [`OutOfRange`](crate::error::SendError::OutOfRange)
-pub fn send_sysex(port: i32, byte: i32) -> Result<(), SendError> {
+pub fn send_sysex(port: u8, byte: u8) -> Result<(), SendError> {
     unsafe {
         // Returns 0 on success or -1 if an argument is out of range
-        match libpd_sys::libpd_sysex(port, byte) {
+        match libpd_sys::libpd_sysex(port.into(), byte.into()) {
             0 => Ok(()),
             _ => Err(SendError::OutOfRange),
         }
@@ -742,7 +795,7 @@ pub fn send_sysex(port: i32, byte: i32) -> Result<(), SendError> {
 
 /// Sends a raw MIDI byte to `|midirealtimein|` objects in pd.
 ///
-/// Port is zero-indexed and byte is `0-255`
+/// Port is zero-indexed.
 ///
 /// # Example
 /// ```rust
@@ -763,10 +816,10 @@ pub fn send_sysex(port: i32, byte: i32) -> Result<(), SendError> {
 ///
 /// A list of errors that can occur:
 /// - [`OutOfRange`](crate::error::SendError::OutOfRange)
-pub fn send_sys_realtime(port: i32, byte: i32) -> Result<(), SendError> {
+pub fn send_sys_realtime(port: u8, byte: u8) -> Result<(), SendError> {
     unsafe {
         // Returns 0 on success or -1 if an argument is out of range
-        match libpd_sys::libpd_sysrealtime(port, byte) {
+        match libpd_sys::libpd_sysrealtime(port.into(), byte.into()) {
             0 => Ok(()),
             _ => Err(SendError::OutOfRange),
         }
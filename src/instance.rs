@@ -7,9 +7,24 @@ use libpd_sys::{
     libpd_new_instance, libpd_num_instances, libpd_set_instance, libpd_set_instancedata,
     libpd_this_instance, t_libpd_freehook,
 };
-use std::{any::TypeId, ffi::c_void, mem};
+use std::{
+    any::TypeId,
+    ffi::c_void,
+    mem, ptr,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        PoisonError, RwLock, RwLockReadGuard,
+    },
+};
 
-use crate::{error::InstanceError, functions};
+use crate::{
+    atom::Atom,
+    error::InstanceError,
+    functions,
+    midi::{Channel, U14, U7},
+    patch,
+    types::HookHandle,
+};
 
 type FreeHookCodePtr = *const FnPtr1<'static, *mut c_void, ()>;
 
@@ -49,6 +64,8 @@ unsafe impl Send for PdInstance {}
 // and our public API ensures thread-safe access to the instance pointer
 unsafe impl Sync for PdInstance {}
 
+/// The `on_*` methods register their closures on this instance regardless of which instance is the current one,
+/// so they do not receive anything from other instances.
 impl PdInstance {
     /// Create a new instance of Pd.
     ///
@@ -121,6 +138,7 @@ impl PdInstance {
             }
         }
 
+        register_instance(new_instance_ptr);
         Ok(Self {
             inner: new_instance_ptr,
             // Since we've just successfully created the main instance, it's safe to dereference here.
@@ -198,6 +216,170 @@ impl PdInstance {
         current_instance.pd_instanceno == self.number
     }
 
+    /// Sets this instance as the active instance for the thread until the returned guard is dropped.
+    ///
+    /// If the guard is dropped, the previously active instance will be set as the active instance.
+    ///
+    /// If the previous instance is null this guard will set the main instance as the active instance since that is always valid.
+    pub(crate) fn set_as_active_instance(&self) -> ActiveInstanceGuard {
+        ActiveInstanceGuard::activate(self.inner)
+    }
+
+    /// Sets a closure to be called when a message is written to the pd console in this instance.
+    ///
+    /// See [`on_print`](crate::functions::receive::on_print) for more details.
+    pub fn on_print<F: FnMut(&str) + Send + Sync + 'static>(&self, closure: F) -> HookHandle {
+        let _guard = self.set_as_active_instance();
+        functions::receive::on_print(closure)
+    }
+
+    /// Sets a closure to be called when a bang is received from a subscribed receiver in this instance.
+    ///
+    /// See [`on_bang`](crate::functions::receive::on_bang) for more details.
+    pub fn on_bang<F: FnMut(&str) + Send + Sync + 'static>(&self, closure: F) -> HookHandle {
+        let _guard = self.set_as_active_instance();
+        functions::receive::on_bang(closure)
+    }
+
+    /// Sets a closure to be called when an `f32` is received from a subscribed receiver in this instance.
+    ///
+    /// See [`on_float`](crate::functions::receive::on_float) for more details.
+    pub fn on_float<F: FnMut(&str, f32) + Send + Sync + 'static>(&self, closure: F) -> HookHandle {
+        let _guard = self.set_as_active_instance();
+        functions::receive::on_float(closure)
+    }
+
+    /// Sets a closure to be called when an `f64` is received from a subscribed receiver in this instance.
+    ///
+    /// See [`on_double`](crate::functions::receive::on_double) for more details.
+    pub fn on_double<F: FnMut(&str, f64) + Send + Sync + 'static>(&self, closure: F) -> HookHandle {
+        let _guard = self.set_as_active_instance();
+        functions::receive::on_double(closure)
+    }
+
+    /// Sets a closure to be called when a symbol is received from a subscribed receiver in this instance.
+    ///
+    /// See [`on_symbol`](crate::functions::receive::on_symbol) for more details.
+    pub fn on_symbol<F: FnMut(&str, &str) + Send + Sync + 'static>(
+        &self,
+        closure: F,
+    ) -> HookHandle {
+        let _guard = self.set_as_active_instance();
+        functions::receive::on_symbol(closure)
+    }
+
+    /// Sets a closure to be called when a list is received from a subscribed receiver in this instance.
+    ///
+    /// See [`on_list`](crate::functions::receive::on_list) for more details.
+    pub fn on_list<F: FnMut(&str, &[Atom]) + Send + Sync + 'static>(
+        &self,
+        closure: F,
+    ) -> HookHandle {
+        let _guard = self.set_as_active_instance();
+        functions::receive::on_list(closure)
+    }
+
+    /// Sets a closure to be called when a typed message is received from a subscribed receiver in this instance.
+    ///
+    /// See [`on_message`](crate::functions::receive::on_message) for more details.
+    pub fn on_message<F: FnMut(&str, &str, &[Atom]) + Send + Sync + 'static>(
+        &self,
+        closure: F,
+    ) -> HookHandle {
+        let _guard = self.set_as_active_instance();
+        functions::receive::on_message(closure)
+    }
+
+    /// Sets a closure to be called when a MIDI note on event is received in this instance.
+    ///
+    /// See [`on_midi_note_on`](crate::functions::receive::on_midi_note_on) for more details.
+    pub fn on_midi_note_on<F: FnMut(Channel, U7, U7) + Send + Sync + 'static>(
+        &self,
+        closure: F,
+    ) -> HookHandle {
+        let _guard = self.set_as_active_instance();
+        functions::receive::on_midi_note_on(closure)
+    }
+
+    /// Sets a closure to be called when a MIDI control change event is received in this instance.
+    ///
+    /// See [`on_midi_control_change`](crate::functions::receive::on_midi_control_change) for more details.
+    pub fn on_midi_control_change<F: FnMut(Channel, U7, U7) + Send + Sync + 'static>(
+        &self,
+        closure: F,
+    ) -> HookHandle {
+        let _guard = self.set_as_active_instance();
+        functions::receive::on_midi_control_change(closure)
+    }
+
+    /// Sets a closure to be called when a MIDI program change event is received in this instance.
+    ///
+    /// See [`on_midi_program_change`](crate::functions::receive::on_midi_program_change) for more details.
+    pub fn on_midi_program_change<F: FnMut(Channel, U7) + Send + Sync + 'static>(
+        &self,
+        closure: F,
+    ) -> HookHandle {
+        let _guard = self.set_as_active_instance();
+        functions::receive::on_midi_program_change(closure)
+    }
+
+    /// Sets a closure to be called when a MIDI pitch bend event is received in this instance.
+    ///
+    /// See [`on_midi_pitch_bend`](crate::functions::receive::on_midi_pitch_bend) for more details.
+    pub fn on_midi_pitch_bend<F: FnMut(Channel, U14) + Send + Sync + 'static>(
+        &self,
+        closure: F,
+    ) -> HookHandle {
+        let _guard = self.set_as_active_instance();
+        functions::receive::on_midi_pitch_bend(closure)
+    }
+
+    /// Sets a closure to be called when a MIDI after touch event is received in this instance.
+    ///
+    /// See [`on_midi_after_touch`](crate::functions::receive::on_midi_after_touch) for more details.
+    pub fn on_midi_after_touch<F: FnMut(Channel, U7) + Send + Sync + 'static>(
+        &self,
+        closure: F,
+    ) -> HookHandle {
+        let _guard = self.set_as_active_instance();
+        functions::receive::on_midi_after_touch(closure)
+    }
+
+    /// Sets a closure to be called when a MIDI poly after touch event is received in this instance.
+    ///
+    /// See [`on_midi_poly_after_touch`](crate::functions::receive::on_midi_poly_after_touch) for more details.
+    pub fn on_midi_poly_after_touch<F: FnMut(Channel, U7, U7) + Send + Sync + 'static>(
+        &self,
+        closure: F,
+    ) -> HookHandle {
+        let _guard = self.set_as_active_instance();
+        functions::receive::on_midi_poly_after_touch(closure)
+    }
+
+    /// Sets a closure to be called when a single raw MIDI byte is received in this instance.
+    ///
+    /// See [`on_midi_byte`](crate::functions::receive::on_midi_byte) for more details.
+    pub fn on_midi_byte<F: FnMut(u8, u8) + Send + Sync + 'static>(&self, closure: F) -> HookHandle {
+        let _guard = self.set_as_active_instance();
+        functions::receive::on_midi_byte(closure)
+    }
+
+    /// Receives messages from the message queue of this instance.
+    ///
+    /// See [`receive_messages_from_pd`](crate::functions::receive::receive_messages_from_pd) for more details.
+    pub fn receive_messages_from_pd(&self) {
+        let _guard = self.set_as_active_instance();
+        functions::receive::receive_messages_from_pd();
+    }
+
+    /// Receives messages from the MIDI message queue of this instance.
+    ///
+    /// See [`receive_midi_messages_from_pd`](crate::functions::receive::receive_midi_messages_from_pd) for more details.
+    pub fn receive_midi_messages_from_pd(&self) {
+        let _guard = self.set_as_active_instance();
+        functions::receive::receive_midi_messages_from_pd();
+    }
+
     /// Set custom instance data with an optional free hook
     ///
     /// We expose this since it is a library function but I'm not sure if it is useful.
@@ -273,9 +455,18 @@ impl Drop for PdInstance {
         //     libpd_queued_release();
         //     libpd_free_instance(pd1);
 
+        // Waits for the handles which are using the instance and keeps them from using it again.
+        let mut live = LIVE_INSTANCES
+            .write()
+            .unwrap_or_else(PoisonError::into_inner);
+        live.retain(|(instance, _)| *instance != self.inner as usize);
+
         self.set_as_current();
+        functions::receive::forget_hooks_of_instance(self.inner);
+        patch::forget_patches_of_instance(self.inner);
         functions::release_internal_queues();
-        unsafe { libpd_free_instance(self.inner) }
+        unsafe { libpd_free_instance(self.inner) };
+        drop(live);
     }
 }
 
@@ -289,3 +480,106 @@ impl Drop for PdInstance {
 pub fn instance_count() -> usize {
     unsafe { libpd_num_instances() as usize }
 }
+
+/// When an instance is set as the active instance for the thread, this guard is returned.
+///
+/// When the guard is dropped, the previously active instance will be set as the active instance.
+pub(crate) struct ActiveInstanceGuard {
+    /// `None` if the instance was already the active one, then dropping the guard is a no-op.
+    previous_instance: Option<*mut _pdinstance>,
+}
+
+impl ActiveInstanceGuard {
+    /// Sets the instance which the pointer points to as the active instance for the thread.
+    pub(crate) fn activate(instance: *mut _pdinstance) -> Self {
+        let previous_instance = unsafe { libpd_this_instance() };
+        if ptr::eq(previous_instance, instance) {
+            return Self {
+                previous_instance: None,
+            };
+        }
+        unsafe {
+            libpd_set_instance(instance);
+        }
+        Self {
+            previous_instance: Some(previous_instance),
+        }
+    }
+}
+
+impl Drop for ActiveInstanceGuard {
+    fn drop(&mut self) {
+        match self.previous_instance {
+            None => {}
+            Some(previous_instance) if previous_instance.is_null() => {
+                // Main instance is always valid.
+                let main_instance = unsafe { libpd_main_instance() };
+                unsafe {
+                    libpd_set_instance(main_instance);
+                }
+            }
+            Some(previous_instance) => unsafe {
+                libpd_set_instance(previous_instance);
+            },
+        }
+    }
+}
+
+static NEXT_INSTANCE_ID: AtomicU64 = AtomicU64::new(0);
+/// The instances other than the main instance which are not freed, with ids which are not reused
+/// when pd reuses the memory of a freed instance.
+static LIVE_INSTANCES: RwLock<Vec<(usize, u64)>> = RwLock::new(Vec::new());
+
+fn register_instance(instance: *mut _pdinstance) {
+    let id = NEXT_INSTANCE_ID.fetch_add(1, Ordering::Relaxed);
+    LIVE_INSTANCES
+        .write()
+        .unwrap_or_else(PoisonError::into_inner)
+        .push((instance as usize, id));
+}
+
+/// Refers to an instance from a handle which does not borrow it, like an [`Array`](crate::array::Array).
+///
+/// The instance may be freed while the handle is alive, it is checked before each use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct InstanceRef {
+    instance: *mut _pdinstance,
+    /// `None` for the main instance which is never freed.
+    id: Option<u64>,
+}
+
+impl InstanceRef {
+    /// Refers to an instance which is alive.
+    pub(crate) fn new(instance: *mut _pdinstance) -> Self {
+        let id = LIVE_INSTANCES
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .find(|(live, _)| *live == instance as usize)
+            .map(|(_, id)| *id);
+        Self { instance, id }
+    }
+
+    /// Sets the instance as the active instance for the thread if it is not freed,
+    /// it is not freed until the guard is dropped.
+    pub(crate) fn activate(&self) -> Option<LiveInstanceGuard> {
+        let live = LIVE_INSTANCES
+            .read()
+            .unwrap_or_else(PoisonError::into_inner);
+        let alive = self.id.map_or_else(
+            || ptr::eq(self.instance, unsafe { libpd_main_instance() }),
+            |id| live.contains(&(self.instance as usize, id)),
+        );
+        alive.then(|| LiveInstanceGuard {
+            _active: ActiveInstanceGuard::activate(self.instance),
+            _live: live,
+        })
+    }
+}
+
+/// Keeps an instance active and alive, returned from [`InstanceRef::activate`].
+pub(crate) struct LiveInstanceGuard {
+    // Restores the previous instance before the instance can be freed.
+    _active: ActiveInstanceGuard,
+    _live: RwLockReadGuard<'static, Vec<(usize, u64)>>,
+}
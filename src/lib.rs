@@ -39,6 +39,7 @@
     clippy::missing_trait_methods,
     clippy::mem_forget,
     clippy::pub_use,
+    clippy::single_call_fn,
 
     // Expect is fine in relevant cases
     // clippy::expect_used,
@@ -155,7 +156,7 @@ doc = ::embed_doc_image::embed_image!("phasor_patch", "assets/phasor_patch.png")
 //!     // Let's evaluate a pd patch.
 //!     // We could have opened a `.pd` file also.
 //!     // This patch would play a sine wave at 440hz.
-//!     pd.eval_patch(
+//!     let patch = pd.eval_patch(
 //!         r#"
 //!     #N canvas 577 549 158 168 12;
 //!     #X obj 23 116 dac~;
@@ -205,7 +206,7 @@ doc = ::embed_doc_image::embed_image!("phasor_patch", "assets/phasor_patch.png")
 //!     output_stream.pause()?;
 //!
 //!     // Close the patch
-//!     pd.close_patch()?;
+//!     patch.close()?;
 //!
 //!     // Leave
 //!     Ok(())
@@ -267,7 +268,7 @@ doc = ::embed_doc_image::embed_image!("phasor_patch", "assets/phasor_patch.png")
 //!
 //!     // Let's evaluate another pd patch.
 //!     // We could have opened a `.pd` file also.
-//!     pd.eval_patch(
+//!     let _patch = pd.eval_patch(
 //!         r#"
 //!     #N canvas 832 310 625 448 12;
 //!     #X obj 18 27 r cpu_load;
@@ -317,7 +318,7 @@ doc = ::embed_doc_image::embed_image!("phasor_patch", "assets/phasor_patch.png")
 //!
 //!     // Here we are registering a listener (hook in libpd lingo) for
 //!     // float values which are received from the pd patch.
-//!     on_float(|source, value| {
+//!     let _float_hook = on_float(|source, value| {
 //!         if source == "response" {
 //!             print!("\r");
 //!             print!("Pd says that the q value of the vcf~ is: {value}");
@@ -528,17 +529,91 @@ pub mod error;
 /// The atom module contains the Atom enum which is used to represent pd's atom type in Rust.
 pub mod atom;
 
-use error::PdError;
-use libpd_sys::_pdinstance;
+/// Typed MIDI values and messages.
+///
+/// [`Channel`](crate::midi::Channel), [`U7`](crate::midi::U7) and [`U14`](crate::midi::U14) can only hold values in their range,
+/// [`MidiMessage`](crate::midi::MidiMessage) converts from and to the bytes of a MIDI message.
+///
+/// [`MidiFile`](crate::midi::MidiFile) reads standard MIDI files and [`MidiPlayer`](crate::midi::MidiPlayer)
+/// plays them into pd through a [`Scheduler`](crate::audio::Scheduler).
+///
+/// The MIDI functions in [`send`](crate::functions::send) and [`receive`](crate::functions::receive) use these types.
+pub mod midi;
+
+/// Async integration, available with the `async` feature.
+///
+/// Exposes the events of an instance as a [`Stream`](futures_core::Stream) of [`PdEvent`](crate::types::PdEvent)s
+/// together with a sender handle which can be used from async code.
+///
+/// With the `tokio` feature the bridge can be driven by a tokio task.
+#[cfg(feature = "async")]
+pub mod stream;
+
+/// Patches which are open in pd instances.
+///
+/// [`Patch`](crate::patch::Patch) closes the patch it holds when it is dropped.
+pub mod patch;
+
+/// Named arrays of pd instances.
+///
+/// [`Array`](crate::array::Array) reads and writes an array with `usize` positions and ranges,
+/// the functions in [`array`](crate::functions::array) are its lower level counterparts.
+///
+/// [`Array::load_wav`](crate::array::Array::load_wav) and [`Array::save_wav`](crate::array::Array::save_wav)
+/// move the contents of arrays from and to wav files.
+///
+/// [`ArrayWatcher`](crate::array::ArrayWatcher) finds the ranges of an array which changed since it was last polled.
+///
+/// [`Pd::create_array`](crate::Pd::create_array) creates an [`OwnedArray`](crate::array::OwnedArray)
+/// which is deleted when it is dropped.
+///
+/// [`Pd::with_array`](crate::Pd::with_array) gives a closure direct access to the memory of an array through an
+/// [`ArrayView`](crate::array::ArrayView).
+pub mod array;
+
+/// Safe audio processing.
+///
+/// [`AudioProcessor`](crate::audio::AudioProcessor) checks the buffers which are processed
+/// against the channels of the instance and computes the ticks to process.
+///
+/// [`BlockAdapter`](crate::audio::BlockAdapter) processes buffers which are not a multiple of the block size of pd.
+///
+/// [`PlanarProcessor`](crate::audio::PlanarProcessor) processes a buffer for each channel instead of interleaved ones.
+///
+/// [`Scheduler`](crate::audio::Scheduler) sends messages at the blocks of the buffer which they are scheduled in.
+///
+/// [`Pd::render_offline`](crate::Pd::render_offline) renders the output of pd to a wav file without an audio device.
+pub mod audio;
+
+/// Testing patches against reference recordings of their output.
+///
+/// [`GoldenTest`](crate::testing::GoldenTest) renders a patch offline while sending it messages at logical times
+/// and compares the output with a reference wav file within a tolerance.
+pub mod testing;
+
+mod dispatcher;
+
+use array::{Array, ArrayOptions, ArrayView, OwnedArray};
+use audio::{AudioProcessor, BlockAdapter, PlanarProcessor, RenderOptions, Sample, Scheduler};
+use error::{ArrayError, PdError};
 use std::collections::HashMap;
+use std::fs;
+use std::io::{Seek, Write};
 use std::path::{Path, PathBuf};
-use std::{fs, ptr};
+use std::sync::Arc;
+use std::time::Duration;
 use tempfile::NamedTempFile;
 
-use crate::instance::PdInstance;
+use crate::dispatcher::Dispatcher;
+use crate::instance::{ActiveInstanceGuard, PdInstance};
+use crate::midi::{Channel, U14, U7};
+use crate::patch::{file::PatchFile, Patch, Watcher};
 use crate::{
     error::PatchLifeCycleError,
-    types::{PatchFileHandle, ReceiverHandle},
+    types::{
+        HookHandle, MessageQueue, MessageReceiver, OutgoingMessage, PdEvent, PdMessage, PdSender,
+        ReceiverHandle,
+    },
 };
 
 pub use atom::Atom;
@@ -599,8 +674,9 @@ pub struct Pd {
     input_channels: i32,
     output_channels: i32,
     sample_rate: i32,
-    running_patch: Option<PatchFileHandle>,
-    temporary_evaluated_patch: Option<NamedTempFile>,
+    /// Feeds the routes of [`on`](Pd::on) and the events of [`poll_events`](Pd::poll_events).
+    dispatcher: Dispatcher,
+    watched_patches: Vec<Watcher>,
     /// A store to keep track of subscriptions which are made to senders in pd through the app lifecycle.
     pub subscriptions: HashMap<String, ReceiverHandle>,
     /// A store to keep track of paths which are added to pd search paths through the app lifecycle.
@@ -648,8 +724,8 @@ impl Pd {
             input_channels,
             output_channels,
             sample_rate,
-            running_patch: None,
-            temporary_evaluated_patch: None,
+            dispatcher: Dispatcher::default(),
+            watched_patches: vec![],
             subscriptions: HashMap::default(),
             search_paths: vec![],
         })
@@ -669,9 +745,26 @@ impl Pd {
     pub fn audio_context(&self) -> PdAudioContext {
         PdAudioContext {
             instance: self.inner.clone(),
+            input_channels: self.input_channels,
+            output_channels: self.output_channels,
+            sample_rate: self.sample_rate,
+            queue: None,
         }
     }
 
+    /// Creates an audio context which sends the messages of a new [`PdSender`](crate::types::PdSender)
+    /// before it processes audio.
+    ///
+    /// The queue of the sender has room for `capacity` messages, the clones of the context share it.
+    pub fn audio_context_with_sender(&self, capacity: usize) -> (PdAudioContext, PdSender) {
+        let sender = PdSender::new(capacity);
+        let ctx = PdAudioContext {
+            queue: Some(sender.queue()),
+            ..self.audio_context()
+        };
+        (ctx, sender)
+    }
+
     /// Set this instance as the current active instance for the thread.
     pub fn set_as_current(&self) {
         self.inner.set_as_current();
@@ -700,13 +793,7 @@ impl Pd {
     ///
     /// If the previous instance is null this guard will set the main instance as the active instance since that is always valid.
     pub(crate) fn set_as_active_instance(&self) -> ActiveInstanceGuard {
-        if self.inner.is_current_instance() {
-            // This would render the guard useless and as a no-op on drop which is what we want.
-            return ActiveInstanceGuard::wrap(ptr::null_mut::<_pdinstance>());
-        }
-        let previous_instance = unsafe { libpd_sys::libpd_this_instance() };
-        self.inner.set_as_current();
-        ActiveInstanceGuard::wrap(previous_instance)
+        self.inner.set_as_active_instance()
     }
 
     /// Adds a path to the list of paths where this instance searches in.
@@ -760,20 +847,26 @@ impl Pd {
         self.search_paths.clear();
     }
 
-    /// Closes a pd patch for this instance.
+    /// Closes a pd patch of this instance.
+    ///
+    /// This is the same as calling [`close`](crate::patch::Patch::close) on the patch.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use libpd_rs::Pd;
+    ///
+    /// let pd = Pd::init_and_configure(1, 2, 44100).unwrap();
+    /// let patch = pd.open_patch("tests/patches/sine.pd").unwrap();
+    /// assert!(pd.close_patch(patch).is_ok());
+    /// ```
     ///
     /// # Errors
     ///
     /// A list of errors that can occur:
     /// - [`PatchLifeCycleError`](crate::error::PatchLifeCycleError)
     ///   - [`FailedToClosePatch`](crate::error::PatchLifeCycleError::FailedToClosePatch)
-    pub fn close_patch(&mut self) -> Result<(), PdError> {
-        let _guard = self.set_as_active_instance();
-        if let Some(handle) = self.running_patch.take() {
-            functions::close_patch(handle)?;
-        }
-        self.temporary_evaluated_patch.take();
-        Ok(())
+    pub fn close_patch(&self, patch: Patch) -> Result<(), PdError> {
+        patch.close()
     }
 
     /// Opens a pd patch for this instance.
@@ -784,42 +877,40 @@ impl Pd {
     ///
     /// Tha function **first** checks the executable directory and **then** the manifest directory.
     ///
+    /// The returned [`Patch`] closes the patch when it is dropped, so it needs to be kept alive as long as the patch is used.
+    /// Any number of patches can be open at the same time.
+    ///
     /// # Examples
     /// ```no_run
     /// use libpd_rs::Pd;
     ///
-    /// let mut pd = Pd::init_and_configure(1, 2, 44100).unwrap();
-    /// assert!(pd.open_patch("tests/patches/sine.pd").is_ok());
+    /// let pd = Pd::init_and_configure(1, 2, 44100).unwrap();
+    /// let synth = pd.open_patch("tests/patches/sine.pd").unwrap();
+    /// let echo = pd.open_patch("tests/patches/echo.pd").unwrap();
+    /// println!("$0 of the synth is {}", synth.dollar_zero());
     /// ```
     ///
     /// # Errors
     ///
     /// A list of errors that can occur:
     /// - [`PatchLifeCycleError`](crate::error::PatchLifeCycleError)
-    ///   - [`FailedToClosePatch`](crate::error::PatchLifeCycleError::FailedToClosePatch)
     ///   - [`FailedToOpenPatch`](crate::error::PatchLifeCycleError::FailedToOpenPatch)
     ///   - [`PathDoesNotExist`](crate::error::PatchLifeCycleError::PathDoesNotExist)
-    pub fn open_patch<T: AsRef<Path>>(&mut self, path: T) -> Result<(), PdError> {
+    pub fn open_patch<T: AsRef<Path>>(&self, path: T) -> Result<Patch, PdError> {
         let _guard = self.set_as_active_instance();
-        if self.running_patch.is_some() {
-            self.close_patch()?;
-        }
-        self.running_patch = Some(functions::open_patch(path)?);
-        Ok(())
+        Patch::open(path, None)
     }
 
     /// Evaluate a string as a pd patch for this instance.
     ///
     /// This function creates a temporary file with the contents passed behind the scenes.
-    /// and saves it into the [`Pd`] struct holding onto it until the patch is closed or the instantiated [`Pd`] is dropped.
-    ///
-    /// Note: The patch opened after this evaluation could be closed safely with [`close_patch`](Pd::close_patch).
+    /// and saves it into the returned [`Patch`] holding onto it until the patch is closed.
     ///
     /// # Examples
     /// ```rust
     /// use libpd_rs::Pd;
     ///
-    /// let mut pd = Pd::init_and_configure(1, 2, 44100).unwrap();
+    /// let pd = Pd::init_and_configure(1, 2, 44100).unwrap();
     ///     
     /// assert!(pd.eval_patch(
     /// r#"
@@ -841,14 +932,10 @@ impl Pd {
     /// A list of errors that can occur:
     /// - [`PatchLifeCycleError`](crate::error::PatchLifeCycleError)
     ///   - [`FailedToEvaluateAsPatch`](crate::error::PatchLifeCycleError::FailedToEvaluateAsPatch)
-    ///   - [`FailedToClosePatch`](crate::error::PatchLifeCycleError::FailedToClosePatch)
     ///   - [`FailedToOpenPatch`](crate::error::PatchLifeCycleError::FailedToOpenPatch)
     ///   - [`PathDoesNotExist`](crate::error::PatchLifeCycleError::PathDoesNotExist)
-    pub fn eval_patch<T: AsRef<str>>(&mut self, contents: T) -> Result<(), PdError> {
+    pub fn eval_patch<T: AsRef<str>>(&self, contents: T) -> Result<Patch, PdError> {
         let _guard = self.set_as_active_instance();
-        if self.running_patch.is_some() {
-            self.close_patch()?;
-        }
         let temp_file =
             NamedTempFile::new().map_err(|err| PatchLifeCycleError::FailedToEvaluateAsPatch {
                 content: contents.as_ref().to_owned(),
@@ -860,9 +947,146 @@ impl Pd {
                 msg: err.to_string(),
             }
         })?;
-        self.running_patch = Some(functions::open_patch(temp_file.path())?);
-        self.temporary_evaluated_patch = Some(temp_file);
-        Ok(())
+        let path = temp_file.path().to_path_buf();
+        Patch::open(path, Some(temp_file))
+    }
+
+    /// Loads the contents of a patch which is built or edited in code for this instance.
+    ///
+    /// Accepts a [`PatchBuilder`](crate::patch::PatchBuilder) or a [`PatchFile`](crate::patch::file::PatchFile),
+    /// the contents are written and evaluated like [`eval_patch`](Pd::eval_patch) does.
+    ///
+    /// # Examples
+    /// ```rust
+    /// use libpd_rs::{patch::PatchBuilder, Pd};
+    ///
+    /// let pd = Pd::init_and_configure(1, 2, 44100).unwrap();
+    ///
+    /// let mut builder = PatchBuilder::new();
+    /// let receive = builder.obj("r float_from_rust");
+    /// let send = builder.obj("s float_from_pd");
+    /// builder.connect(receive, 0, send, 0);
+    ///
+    /// let _patch = pd.load_patch(&builder).unwrap();
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`PatchLifeCycleError`](crate::error::PatchLifeCycleError)
+    ///   - [`FailedToEvaluateAsPatch`](crate::error::PatchLifeCycleError::FailedToEvaluateAsPatch)
+    ///   - [`FailedToOpenPatch`](crate::error::PatchLifeCycleError::FailedToOpenPatch)
+    ///   - [`PathDoesNotExist`](crate::error::PatchLifeCycleError::PathDoesNotExist)
+    pub fn load_patch<T: AsRef<PatchFile>>(&self, patch: T) -> Result<Patch, PdError> {
+        self.eval_patch(patch.as_ref().to_string())
+    }
+
+    /// Gets a handle to a named array of this instance, like the one of a `|table|` or an `|array define|`.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use libpd_rs::Pd;
+    ///
+    /// let pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+    /// let _patch = pd.open_patch("tests/patches/array_sketch_pad.pd").unwrap();
+    ///
+    /// let sketch_pad = pd.array("sketch_pad").unwrap();
+    /// sketch_pad.fill(0.5).unwrap();
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`ArrayError`](crate::error::ArrayError)
+    ///   - [`FailedToFindArray`](crate::error::ArrayError::FailedToFindArray)
+    ///   - [`StringConversion`](crate::error::ArrayError::StringConversion)
+    pub fn array<T: AsRef<str>>(&self, name: T) -> Result<Array, ArrayError> {
+        Array::find(name.as_ref(), self.inner.as_ptr())
+    }
+
+    /// Creates a named array in a hidden canvas of its own, without a patch which defines it.
+    ///
+    /// The array is deleted when the returned [`OwnedArray`] is dropped.
+    /// Pd clips sizes of `0` to `1`.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use libpd_rs::{array::{ArrayOptions, PlotStyle}, Pd};
+    ///
+    /// let pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+    ///
+    /// let options = ArrayOptions {
+    ///     save_contents: false,
+    ///     style: PlotStyle::Points,
+    ///     ..ArrayOptions::default()
+    /// };
+    /// let delay_buffer = pd.create_array("delay_buffer", 44100, &options).unwrap();
+    /// assert_eq!(pd.array("delay_buffer").unwrap().len().unwrap(), 44100);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`ArrayError`](crate::error::ArrayError)
+    ///   - [`AlreadyExists`](crate::error::ArrayError::AlreadyExists)
+    ///   - [`FailedToFindArray`](crate::error::ArrayError::FailedToFindArray)
+    ///   - [`StringConversion`](crate::error::ArrayError::StringConversion)
+    /// - [`PatchLifeCycleError`](crate::error::PatchLifeCycleError)
+    ///   - [`FailedToEvaluateAsPatch`](crate::error::PatchLifeCycleError::FailedToEvaluateAsPatch)
+    ///   - [`FailedToOpenPatch`](crate::error::PatchLifeCycleError::FailedToOpenPatch)
+    pub fn create_array<T: AsRef<str>>(
+        &self,
+        name: T,
+        size: usize,
+        options: &ArrayOptions,
+    ) -> Result<OwnedArray, PdError> {
+        let name = name.as_ref();
+        match self.array(name) {
+            Ok(_) => return Err(ArrayError::AlreadyExists(name.to_owned()).into()),
+            Err(ArrayError::FailedToFindArray) => {}
+            Err(err) => return Err(err.into()),
+        }
+        let patch = self.load_patch(array::hidden_canvas(name, size, *options))?;
+        Ok(OwnedArray::new(self.array(name)?, patch))
+    }
+
+    /// Locks pd and calls a closure with direct access to the memory of a named array.
+    ///
+    /// This avoids copying large arrays. The lock is held only while the closure runs,
+    /// processing and every other libpd call in other threads waits for it, so the closure should be brief.
+    /// The closure must not call into libpd through [`Array`]s or the functions in
+    /// [`functions`](crate::functions), locking pd again from the same thread deadlocks.
+    /// The array is redrawn after the closure if it was accessed mutably.
+    ///
+    /// [`as_slice`](ArrayView::as_slice) and [`as_mut_slice`](ArrayView::as_mut_slice) return `None`
+    /// in single precision builds on 64 bit platforms, where pd spaces its floats out to the width of a pointer.
+    /// [`iter`](ArrayView::iter) and [`iter_mut`](ArrayView::iter_mut) work in every build.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use libpd_rs::Pd;
+    ///
+    /// let mut pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+    /// let _patch = pd.open_patch("tests/patches/array_sketch_pad.pd").unwrap();
+    ///
+    /// let peak = pd
+    ///     .with_array("sketch_pad", |sketch_pad| {
+    ///         sketch_pad.iter().fold(0.0, |peak, value| value.abs().max(peak))
+    ///     })
+    ///     .unwrap();
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`FailedToFindArray`](crate::error::ArrayError::FailedToFindArray)
+    /// - [`StringConversion`](crate::error::ArrayError::StringConversion)
+    pub fn with_array<T, R, F>(&mut self, name: T, f: F) -> Result<R, ArrayError>
+    where
+        T: AsRef<str>,
+        F: FnOnce(&mut ArrayView<'_>) -> R,
+    {
+        array::with_array(name.as_ref(), self.inner.as_ptr(), f)
     }
 
     /// Starts listening messages from a source.
@@ -874,7 +1098,7 @@ impl Pd {
     /// use libpd_rs::Pd;
     ///
     /// let mut pd = Pd::init_and_configure(1, 2, 44100).unwrap();
-    /// pd.open_patch("tests/patches/sine.pd").unwrap();
+    /// let _patch = pd.open_patch("tests/patches/sine.pd").unwrap();
     /// pd.subscribe_to("sender").unwrap();
     /// ```
     ///
@@ -904,7 +1128,7 @@ impl Pd {
     /// use libpd_rs::Pd;
     ///
     /// let mut pd = Pd::init_and_configure(1, 2, 44100).unwrap();
-    /// pd.open_patch("tests/patches/sine.pd").unwrap();
+    /// let _patch = pd.open_patch("tests/patches/sine.pd").unwrap();
     /// pd.subscribe_to_many(&["sender", "other_sender"]).unwrap();
     /// ```
     ///
@@ -927,6 +1151,59 @@ impl Pd {
         Ok(())
     }
 
+    /// Subscribes to a source and routes the messages which are sent to it to the closure.
+    ///
+    /// Every source has its own closure so there is no need to match on the source in a single listener.
+    /// Registering a closure for a source which already has one replaces it.
+    ///
+    /// The closure is removed together with the subscription by [`unsubscribe_from`](Pd::unsubscribe_from),
+    /// [`unsubscribe_from_many`](Pd::unsubscribe_from_many) or [`unsubscribe_from_all`](Pd::unsubscribe_from_all).
+    ///
+    /// Messages are dispatched when [`receive_messages_from_pd`](PdAudioContext::receive_messages_from_pd) is called.
+    ///
+    /// The closures share their listeners with [`poll_events`](Pd::poll_events) so both can be used together.
+    /// The listeners are not registered over other listeners for messages on this instance,
+    /// like the closures of the `on_*` methods or a [`message_receiver`](Pd::message_receiver),
+    /// drop their handles first.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use libpd_rs::{Pd, types::PdMessage};
+    ///
+    /// let mut pd = Pd::init_and_configure(1, 2, 44100).unwrap();
+    /// let _patch = pd.open_patch("tests/patches/echo.pd").unwrap();
+    ///
+    /// pd.on("float_from_pd", |message| {
+    ///     if let PdMessage::Float { value, .. } = message {
+    ///         println!("Received a float: {value}");
+    ///     }
+    /// })
+    /// .unwrap();
+    /// pd.on("symbol_from_pd", |message| println!("Received: {message:?}"))
+    ///     .unwrap();
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`SubscriptionError`](crate::error::SubscriptionError)
+    ///   - [`FailedToSubscribeToSender`](crate::error::SubscriptionError::FailedToSubscribeToSender)
+    ///   - [`ListenersAlreadyRegistered`](crate::error::SubscriptionError::ListenersAlreadyRegistered)
+    pub fn on<T: AsRef<str>, F: FnMut(&PdMessage) + Send + 'static>(
+        &mut self,
+        source: T,
+        closure: F,
+    ) -> Result<(), PdError> {
+        {
+            let _guard = self.set_as_active_instance();
+            self.dispatcher.insert(source.as_ref(), closure)?;
+        }
+        self.subscribe_to(source.as_ref()).inspect_err(|_| {
+            let _guard = self.set_as_active_instance();
+            self.dispatcher.remove(source.as_ref());
+        })
+    }
+
     /// Stops listening messages from a source.
     ///
     /// # Examples
@@ -934,7 +1211,7 @@ impl Pd {
     /// use libpd_rs::Pd;
     ///
     /// let mut pd = Pd::init_and_configure(1, 2, 44100).unwrap();
-    /// pd.open_patch("tests/patches/sine.pd").unwrap();
+    /// let _patch = pd.open_patch("tests/patches/sine.pd").unwrap();
     /// pd.subscribe_to("sender").unwrap();
     /// pd.unsubscribe_from("sender");
     /// ```
@@ -943,6 +1220,7 @@ impl Pd {
         if let Some(handle) = self.subscriptions.remove(source.as_ref()) {
             functions::receive::stop_listening_from(handle);
         }
+        self.dispatcher.remove(source.as_ref());
     }
 
     /// Stops listening messages from many sources.
@@ -952,7 +1230,7 @@ impl Pd {
     /// use libpd_rs::Pd;
     ///
     /// let mut pd = Pd::init_and_configure(1, 2, 44100).unwrap();
-    /// pd.open_patch("tests/patches/sine.pd").unwrap();
+    /// let _patch = pd.open_patch("tests/patches/sine.pd").unwrap();
     /// pd.subscribe_to_many(&["sender", "other_sender"]).unwrap();
     ///
     /// pd.unsubscribe_from_many(&["sender", "other_sender"]);
@@ -963,6 +1241,7 @@ impl Pd {
             if let Some(handle) = self.subscriptions.remove(source.as_ref()) {
                 functions::receive::stop_listening_from(handle);
             }
+            self.dispatcher.remove(source.as_ref());
         }
     }
 
@@ -973,7 +1252,7 @@ impl Pd {
     /// use libpd_rs::Pd;
     ///
     /// let mut pd = Pd::init_and_configure(1, 2, 44100).unwrap();
-    /// pd.open_patch("tests/patches/sine.pd").unwrap();
+    /// let _patch = pd.open_patch("tests/patches/sine.pd").unwrap();
     /// pd.subscribe_to_many(&["sender", "other_sender"]).unwrap();
     ///
     /// pd.unsubscribe_from_all();
@@ -986,24 +1265,466 @@ impl Pd {
                 functions::receive::stop_listening_from(handle);
             }
         }
+        self.dispatcher.clear();
+    }
+
+    /// Registers listeners for all message types on this instance and returns a channel which receives them.
+    ///
+    /// The listeners are unregistered when the returned [`MessageReceiver`] is dropped.
+    ///
+    /// Messages which are sent from the patch to the receivers in [`subscriptions`](Pd::subscriptions)
+    /// arrive in the channel after [`receive_messages_from_pd`](PdAudioContext::receive_messages_from_pd) is called.
+    ///
+    /// See [`message_receiver`](crate::functions::receive::message_receiver) for more details.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use libpd_rs::{Pd, types::PdMessage};
+    ///
+    /// let mut pd = Pd::init_and_configure(1, 2, 44100).unwrap();
+    /// let _patch = pd.open_patch("tests/patches/echo.pd").unwrap();
+    ///
+    /// let messages = pd.message_receiver();
+    /// pd.subscribe_to("float_from_pd").unwrap();
+    ///
+    /// let ctx = pd.audio_context();
+    /// std::thread::spawn(move || {
+    ///     for message in messages.iter() {
+    ///         if let PdMessage::Float { value, .. } = message {
+    ///             println!("{value}");
+    ///         }
+    ///     }
+    /// });
+    ///
+    /// loop {
+    ///     ctx.receive_messages_from_pd();
+    /// }
+    /// ```
+    pub fn message_receiver(&self) -> MessageReceiver {
+        let _guard = self.set_as_active_instance();
+        functions::receive::message_receiver()
+    }
+
+    /// Drains the message and MIDI queues of this instance and appends everything pd sent to `events`.
+    ///
+    /// This calls both [`receive_messages_from_pd`](crate::functions::receive::receive_messages_from_pd)
+    /// and [`receive_midi_messages_from_pd`](crate::functions::receive::receive_midi_messages_from_pd),
+    /// so it is meant to be called once per frame from the main loop of the application instead of the audio callback.
+    ///
+    /// The first call registers listeners for messages, MIDI events and console output on this instance
+    /// until [`stop_polling_events`](Pd::stop_polling_events) is called.
+    /// They are not registered over other listeners on this instance, like the closures of the `on_*` methods,
+    /// a [`message_receiver`](Pd::message_receiver) or an [`EventReceiver`](crate::types::EventReceiver),
+    /// drop their handles first.
+    /// The closures of [`on`](Pd::on) keep being called since they share the listeners.
+    ///
+    /// Messages are only received from the sources in [`subscriptions`](Pd::subscriptions).
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use libpd_rs::{Pd, types::{PdEvent, PdMessage}};
+    ///
+    /// let mut pd = Pd::init_and_configure(1, 2, 44100).unwrap();
+    /// let _patch = pd.open_patch("tests/patches/echo.pd").unwrap();
+    /// pd.subscribe_to("float_from_pd").unwrap();
+    ///
+    /// let mut events = Vec::new();
+    /// loop {
+    ///     pd.poll_events(&mut events).unwrap();
+    ///     for event in events.drain(..) {
+    ///         match event {
+    ///             PdEvent::Message(PdMessage::Float { value, .. }) => println!("{value}"),
+    ///             PdEvent::NoteOn { pitch, .. } => println!("Note on: {pitch}"),
+    ///             PdEvent::Print(line) => println!("{line}"),
+    ///             _ => {}
+    ///         }
+    ///     }
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`SubscriptionError`](crate::error::SubscriptionError)
+    ///   - [`ListenersAlreadyRegistered`](crate::error::SubscriptionError::ListenersAlreadyRegistered)
+    pub fn poll_events(&mut self, events: &mut Vec<PdEvent>) -> Result<(), PdError> {
+        let _guard = self.set_as_active_instance();
+        self.dispatcher.start_polling()?;
+        functions::receive::receive_messages_from_pd();
+        functions::receive::receive_midi_messages_from_pd();
+        self.dispatcher.drain(events);
+        self.check_watched_patches(events);
+        Ok(())
+    }
+
+    /// Stops collecting events for [`poll_events`](Pd::poll_events) and drops the ones which are not polled yet.
+    ///
+    /// The listeners which [`poll_events`](Pd::poll_events) registered are unregistered
+    /// unless the closures of [`on`](Pd::on) still use them, so other listeners can be registered again.
+    /// Calling [`poll_events`](Pd::poll_events) again starts collecting events again.
+    pub fn stop_polling_events(&mut self) {
+        let _guard = self.set_as_active_instance();
+        self.dispatcher.stop_polling();
     }
 
-    /// Gets the `$0` of the running patch.
+    /// Opens a patch and reopens it whenever its file changes.
+    ///
+    /// The abstractions which the patch uses are watched too,
+    /// they are looked for in the directory of the patch and in [`search_paths`](Pd::search_paths).
     ///
-    /// `$0` id in pd could be thought as a auto generated unique identifier for the patch.
+    /// Changes are checked by polling the modification times of the files in
+    /// [`check_watched_patches`](Pd::check_watched_patches) which [`poll_events`](Pd::poll_events) calls.
+    /// Watching a patch which is already watched reopens it.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use libpd_rs::{types::PdEvent, Pd};
+    ///
+    /// let mut pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+    /// pd.watch_patch("tests/patches/sine.pd").unwrap();
+    ///
+    /// let mut events = Vec::new();
+    /// loop {
+    ///     pd.poll_events(&mut events).unwrap();
+    ///     for event in events.drain(..) {
+    ///         match event {
+    ///             PdEvent::PatchReloaded { path } => println!("Reloaded {}", path.display()),
+    ///             PdEvent::PatchReloadFailed { path, error } => {
+    ///                 println!("Failed to reload {}: {error}", path.display());
+    ///             }
+    ///             _ => {}
+    ///         }
+    ///     }
+    /// }
+    /// ```
     ///
     /// # Errors
     ///
     /// A list of errors that can occur:
     /// - [`PatchLifeCycleError`](crate::error::PatchLifeCycleError)
-    ///   - [`PatchIsNotOpen`](crate::error::PatchLifeCycleError::PatchIsNotOpen)
-    pub fn dollar_zero(&mut self) -> Result<i32, PdError> {
+    ///   - [`FailedToOpenPatch`](crate::error::PatchLifeCycleError::FailedToOpenPatch)
+    ///   - [`PathDoesNotExist`](crate::error::PatchLifeCycleError::PathDoesNotExist)
+    pub fn watch_patch<T: AsRef<Path>>(&mut self, path: T) -> Result<(), PdError> {
+        let _guard = self.set_as_active_instance();
+        let watcher = Watcher::new(path.as_ref(), &self.search_paths)?;
+        self.watched_patches
+            .retain(|watched| watched.path() != path.as_ref());
+        self.watched_patches.push(watcher);
+        Ok(())
+    }
+
+    /// Stops watching a patch and returns it, the patch is closed when it is dropped.
+    pub fn unwatch_patch<T: AsRef<Path>>(&mut self, path: T) -> Option<Patch> {
+        let position = self
+            .watched_patches
+            .iter()
+            .position(|watched| watched.path() == path.as_ref())?;
+        Some(self.watched_patches.swap_remove(position).into_patch())
+    }
+
+    /// Gets the currently open version of a watched patch.
+    pub fn watched_patch<T: AsRef<Path>>(&self, path: T) -> Option<&Patch> {
+        self.watched_patches
+            .iter()
+            .find(|watched| watched.path() == path.as_ref())
+            .map(Watcher::patch)
+    }
+
+    /// Reopens the watched patches which changed since the last check.
+    ///
+    /// A [`PatchReloaded`](PdEvent::PatchReloaded) or [`PatchReloadFailed`](PdEvent::PatchReloadFailed)
+    /// event is pushed for every patch which changed.
+    /// The previous version of a patch keeps running if the new version fails to open.
+    ///
+    /// After a reload audio processing is turned on again if it is active
+    /// and the sources in [`subscriptions`](Pd::subscriptions) are listened to again.
+    pub fn check_watched_patches(&mut self, events: &mut Vec<PdEvent>) {
         let _guard = self.set_as_active_instance();
-        if let Some(ref patch) = self.running_patch {
-            let dollar_zero = functions::get_dollar_zero(patch)?;
-            return Ok(dollar_zero);
+        let mut reloads = vec![];
+        for watcher in &mut self.watched_patches {
+            if watcher.is_changed() {
+                let result = watcher.reload(&self.search_paths);
+                reloads.push((watcher.path().to_path_buf(), result));
+            }
+        }
+        if reloads.is_empty() {
+            return;
+        }
+
+        let restored = if reloads.iter().any(|(_, result)| result.is_ok()) {
+            self.restore_state().map_err(|err| {
+                format!("The patch is reopened but the state could not be restored: {err}")
+            })
+        } else {
+            Ok(())
+        };
+        events.extend(reloads.into_iter().map(|(path, result)| {
+            match result
+                .map_err(|err| err.to_string())
+                .and_then(|()| restored.clone())
+            {
+                Ok(()) => PdEvent::PatchReloaded { path },
+                Err(error) => PdEvent::PatchReloadFailed { path, error },
+            }
+        }));
+    }
+
+    /// Turns audio processing on again if it is active and listens to the subscribed sources again.
+    fn restore_state(&mut self) -> Result<(), PdError> {
+        if self.audio_active {
+            functions::util::dsp_on()?;
         }
-        Err(PatchLifeCycleError::PatchIsNotOpen.into())
+        let sources: Vec<String> = self.subscriptions.keys().cloned().collect();
+        for source in sources {
+            let handle = functions::receive::start_listening_from(&source)?;
+            if let Some(previous) = self.subscriptions.insert(source, handle) {
+                functions::receive::stop_listening_from(previous);
+            }
+        }
+        Ok(())
+    }
+
+    /// Sets a closure to be called when a message is written to the pd console in this instance.
+    ///
+    /// See [`PdInstance::on_print`](crate::instance::PdInstance::on_print) for more details.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use libpd_rs::Pd;
+    ///
+    /// let pd = Pd::init_and_configure(1, 2, 44100).unwrap();
+    /// let _hook = pd.on_print(|msg: &str| println!("pd is printing: {msg}"));
+    /// ```
+    pub fn on_print<F: FnMut(&str) + Send + Sync + 'static>(&self, closure: F) -> HookHandle {
+        self.inner.on_print(closure)
+    }
+
+    /// Sets a closure to be called when a bang is received from a subscribed receiver in this instance.
+    ///
+    /// See [`PdInstance::on_bang`](crate::instance::PdInstance::on_bang) for more details.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use libpd_rs::Pd;
+    ///
+    /// let pd = Pd::init_and_configure(1, 2, 44100).unwrap();
+    /// let _hook = pd.on_bang(|source: &str| println!("bang from {source}"));
+    /// ```
+    pub fn on_bang<F: FnMut(&str) + Send + Sync + 'static>(&self, closure: F) -> HookHandle {
+        self.inner.on_bang(closure)
+    }
+
+    /// Sets a closure to be called when an `f32` is received from a subscribed receiver in this instance.
+    ///
+    /// See [`PdInstance::on_float`](crate::instance::PdInstance::on_float) for more details.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use libpd_rs::Pd;
+    ///
+    /// let pd = Pd::init_and_configure(1, 2, 44100).unwrap();
+    /// let _hook = pd.on_float(|source: &str, value: f32| println!("{value} from {source}"));
+    /// ```
+    pub fn on_float<F: FnMut(&str, f32) + Send + Sync + 'static>(&self, closure: F) -> HookHandle {
+        self.inner.on_float(closure)
+    }
+
+    /// Sets a closure to be called when an `f64` is received from a subscribed receiver in this instance.
+    ///
+    /// See [`PdInstance::on_double`](crate::instance::PdInstance::on_double) for more details.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use libpd_rs::Pd;
+    ///
+    /// let pd = Pd::init_and_configure(1, 2, 44100).unwrap();
+    /// let _hook = pd.on_double(|source: &str, value: f64| println!("{value} from {source}"));
+    /// ```
+    pub fn on_double<F: FnMut(&str, f64) + Send + Sync + 'static>(&self, closure: F) -> HookHandle {
+        self.inner.on_double(closure)
+    }
+
+    /// Sets a closure to be called when a symbol is received from a subscribed receiver in this instance.
+    ///
+    /// See [`PdInstance::on_symbol`](crate::instance::PdInstance::on_symbol) for more details.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use libpd_rs::Pd;
+    ///
+    /// let pd = Pd::init_and_configure(1, 2, 44100).unwrap();
+    /// let _hook = pd.on_symbol(|source: &str, symbol: &str| println!("{symbol} from {source}"));
+    /// ```
+    pub fn on_symbol<F: FnMut(&str, &str) + Send + Sync + 'static>(
+        &self,
+        closure: F,
+    ) -> HookHandle {
+        self.inner.on_symbol(closure)
+    }
+
+    /// Sets a closure to be called when a list is received from a subscribed receiver in this instance.
+    ///
+    /// See [`PdInstance::on_list`](crate::instance::PdInstance::on_list) for more details.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use libpd_rs::{Atom, Pd};
+    ///
+    /// let pd = Pd::init_and_configure(1, 2, 44100).unwrap();
+    /// let _hook = pd.on_list(|source: &str, list: &[Atom]| println!("{list:?} from {source}"));
+    /// ```
+    pub fn on_list<F: FnMut(&str, &[Atom]) + Send + Sync + 'static>(
+        &self,
+        closure: F,
+    ) -> HookHandle {
+        self.inner.on_list(closure)
+    }
+
+    /// Sets a closure to be called when a typed message is received from a subscribed receiver in this instance.
+    ///
+    /// See [`PdInstance::on_message`](crate::instance::PdInstance::on_message) for more details.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use libpd_rs::{Atom, Pd};
+    ///
+    /// let pd = Pd::init_and_configure(1, 2, 44100).unwrap();
+    /// let _hook = pd.on_message(|source: &str, message: &str, values: &[Atom]| println!("{message} {values:?} from {source}"));
+    /// ```
+    pub fn on_message<F: FnMut(&str, &str, &[Atom]) + Send + Sync + 'static>(
+        &self,
+        closure: F,
+    ) -> HookHandle {
+        self.inner.on_message(closure)
+    }
+
+    /// Sets a closure to be called when a MIDI note on event is received in this instance.
+    ///
+    /// See [`PdInstance::on_midi_note_on`](crate::instance::PdInstance::on_midi_note_on) for more details.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use libpd_rs::midi::{Channel, U7};
+    /// use libpd_rs::Pd;
+    ///
+    /// let pd = Pd::init_and_configure(1, 2, 44100).unwrap();
+    /// let _hook = pd.on_midi_note_on(|channel: Channel, pitch: U7, velocity: U7| println!("{channel} {pitch} {velocity}"));
+    /// ```
+    pub fn on_midi_note_on<F: FnMut(Channel, U7, U7) + Send + Sync + 'static>(
+        &self,
+        closure: F,
+    ) -> HookHandle {
+        self.inner.on_midi_note_on(closure)
+    }
+
+    /// Sets a closure to be called when a MIDI control change event is received in this instance.
+    ///
+    /// See [`PdInstance::on_midi_control_change`](crate::instance::PdInstance::on_midi_control_change) for more details.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use libpd_rs::midi::{Channel, U7};
+    /// use libpd_rs::Pd;
+    ///
+    /// let pd = Pd::init_and_configure(1, 2, 44100).unwrap();
+    /// let _hook = pd.on_midi_control_change(|channel: Channel, controller: U7, value: U7| println!("{channel} {controller} {value}"));
+    /// ```
+    pub fn on_midi_control_change<F: FnMut(Channel, U7, U7) + Send + Sync + 'static>(
+        &self,
+        closure: F,
+    ) -> HookHandle {
+        self.inner.on_midi_control_change(closure)
+    }
+
+    /// Sets a closure to be called when a MIDI program change event is received in this instance.
+    ///
+    /// See [`PdInstance::on_midi_program_change`](crate::instance::PdInstance::on_midi_program_change) for more details.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use libpd_rs::midi::{Channel, U7};
+    /// use libpd_rs::Pd;
+    ///
+    /// let pd = Pd::init_and_configure(1, 2, 44100).unwrap();
+    /// let _hook = pd.on_midi_program_change(|channel: Channel, value: U7| println!("{channel} {value}"));
+    /// ```
+    pub fn on_midi_program_change<F: FnMut(Channel, U7) + Send + Sync + 'static>(
+        &self,
+        closure: F,
+    ) -> HookHandle {
+        self.inner.on_midi_program_change(closure)
+    }
+
+    /// Sets a closure to be called when a MIDI pitch bend event is received in this instance.
+    ///
+    /// See [`PdInstance::on_midi_pitch_bend`](crate::instance::PdInstance::on_midi_pitch_bend) for more details.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use libpd_rs::midi::{Channel, U14};
+    /// use libpd_rs::Pd;
+    ///
+    /// let pd = Pd::init_and_configure(1, 2, 44100).unwrap();
+    /// let _hook = pd.on_midi_pitch_bend(|channel: Channel, value: U14| println!("{channel} {value}"));
+    /// ```
+    pub fn on_midi_pitch_bend<F: FnMut(Channel, U14) + Send + Sync + 'static>(
+        &self,
+        closure: F,
+    ) -> HookHandle {
+        self.inner.on_midi_pitch_bend(closure)
+    }
+
+    /// Sets a closure to be called when a MIDI after touch event is received in this instance.
+    ///
+    /// See [`PdInstance::on_midi_after_touch`](crate::instance::PdInstance::on_midi_after_touch) for more details.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use libpd_rs::midi::{Channel, U7};
+    /// use libpd_rs::Pd;
+    ///
+    /// let pd = Pd::init_and_configure(1, 2, 44100).unwrap();
+    /// let _hook = pd.on_midi_after_touch(|channel: Channel, value: U7| println!("{channel} {value}"));
+    /// ```
+    pub fn on_midi_after_touch<F: FnMut(Channel, U7) + Send + Sync + 'static>(
+        &self,
+        closure: F,
+    ) -> HookHandle {
+        self.inner.on_midi_after_touch(closure)
+    }
+
+    /// Sets a closure to be called when a MIDI poly after touch event is received in this instance.
+    ///
+    /// See [`PdInstance::on_midi_poly_after_touch`](crate::instance::PdInstance::on_midi_poly_after_touch) for more details.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use libpd_rs::midi::{Channel, U7};
+    /// use libpd_rs::Pd;
+    ///
+    /// let pd = Pd::init_and_configure(1, 2, 44100).unwrap();
+    /// let _hook = pd.on_midi_poly_after_touch(|channel: Channel, pitch: U7, value: U7| println!("{channel} {pitch} {value}"));
+    /// ```
+    pub fn on_midi_poly_after_touch<F: FnMut(Channel, U7, U7) + Send + Sync + 'static>(
+        &self,
+        closure: F,
+    ) -> HookHandle {
+        self.inner.on_midi_poly_after_touch(closure)
+    }
+
+    /// Sets a closure to be called when a single raw MIDI byte is received in this instance.
+    ///
+    /// See [`PdInstance::on_midi_byte`](crate::instance::PdInstance::on_midi_byte) for more details.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use libpd_rs::Pd;
+    ///
+    /// let pd = Pd::init_and_configure(1, 2, 44100).unwrap();
+    /// let _hook = pd.on_midi_byte(|port: u8, byte: u8| println!("{port} {byte}"));
+    /// ```
+    pub fn on_midi_byte<F: FnMut(u8, u8) + Send + Sync + 'static>(&self, closure: F) -> HookHandle {
+        self.inner.on_midi_byte(closure)
     }
 
     /// Checks if the audio is active.
@@ -1040,6 +1761,100 @@ impl Pd {
         Ok(())
     }
 
+    /// Renders the output of pd for a duration to a 32 bit float wav file as fast as it can be processed.
+    ///
+    /// No audio device is used, so this runs headless, for example to render sounds in batch or to test patches on CI.
+    /// Audio is activated for rendering and deactivated again if it was not active before.
+    ///
+    /// The inputs of pd are silent, use [`render_offline_with`](Pd::render_offline_with) to play a wav file into them
+    /// or to write integer samples.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use std::{fs::File, time::Duration};
+    /// use libpd_rs::Pd;
+    ///
+    /// let mut pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+    /// let _patch = pd.open_patch("tests/patches/sine.pd").unwrap();
+    ///
+    /// let file = File::create("sine.wav").unwrap();
+    /// pd.render_offline(Duration::from_secs(10), file).unwrap();
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`WavError`](crate::error::WavError)
+    ///   - [`InvalidFile`](crate::error::WavError::InvalidFile)
+    /// - [`AudioProcessingError`](crate::error::AudioProcessingError)
+    ///   - [`NoChannels`](crate::error::AudioProcessingError::NoChannels)
+    /// - [`SendError`](crate::error::SendError)
+    ///   - [`MissingDestination`](crate::error::SendError::MissingDestination)
+    /// - [`SizeError`](crate::error::SizeError)
+    ///   - [`TooLarge`](crate::error::SizeError::TooLarge)
+    pub fn render_offline<W: Write + Seek>(
+        &mut self,
+        duration: Duration,
+        writer: W,
+    ) -> Result<(), PdError> {
+        self.render_offline_with(duration, &RenderOptions::default(), writer)
+    }
+
+    /// Renders the output of pd for a duration to a wav file with the options.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use std::{fs::File, time::Duration};
+    /// use libpd_rs::{
+    ///     audio::{RenderOptions, WavFormat},
+    ///     Pd,
+    /// };
+    ///
+    /// let mut pd = Pd::init_and_configure(1, 1, 44100).unwrap();
+    /// let _patch = pd.open_patch("tests/patches/echo.pd").unwrap();
+    ///
+    /// let options = RenderOptions {
+    ///     format: WavFormat::Int16,
+    ///     input: Some("voice.wav".into()),
+    ///     ..RenderOptions::default()
+    /// };
+    /// let file = File::create("echo.wav").unwrap();
+    /// pd.render_offline_with(Duration::from_secs(10), &options, file)
+    ///     .unwrap();
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`WavError`](crate::error::WavError)
+    ///   - [`InvalidFile`](crate::error::WavError::InvalidFile)
+    ///   - [`ChannelCount`](crate::error::WavError::ChannelCount)
+    ///   - [`SampleRate`](crate::error::WavError::SampleRate)
+    /// - [`IoError`](crate::error::IoError)
+    ///   - [`PathDoesNotExist`](crate::error::IoError::PathDoesNotExist)
+    /// - [`MidiError`](crate::error::MidiError)
+    ///   - [`InvalidFile`](crate::error::MidiError::InvalidFile)
+    ///   - [`UnsupportedFormat`](crate::error::MidiError::UnsupportedFormat)
+    /// - [`AudioProcessingError`](crate::error::AudioProcessingError)
+    ///   - [`NoChannels`](crate::error::AudioProcessingError::NoChannels)
+    /// - [`SendError`](crate::error::SendError)
+    ///   - [`MissingDestination`](crate::error::SendError::MissingDestination)
+    /// - [`SizeError`](crate::error::SizeError)
+    ///   - [`TooLarge`](crate::error::SizeError::TooLarge)
+    pub fn render_offline_with<W: Write + Seek>(
+        &mut self,
+        duration: Duration,
+        options: &RenderOptions,
+        writer: W,
+    ) -> Result<(), PdError> {
+        let audio_active = self.audio_active;
+        self.activate_audio(true)?;
+        let processor = self.audio_context().processor();
+        let rendered = audio::render(&processor, self.sample_rate, duration, options, writer);
+        self.activate_audio(audio_active)?;
+        rendered
+    }
+
     /// Gets the sample rate which pd is configured with.
     ///
     /// # Important
@@ -1082,12 +1897,81 @@ impl Pd {
 /// Since the instances are thread local, this is just a convenience struct to ensure that the instance is set as the current one before calling any functions.
 ///
 /// If you don't set at least one instance as the current one, the functions in the library will panic.
+///
+/// A context which is created with [`audio_context_with_sender`](Pd::audio_context_with_sender) sends the messages
+/// which are queued by its [`PdSender`](crate::types::PdSender) before processing audio.
 #[derive(Debug, Clone)]
 pub struct PdAudioContext {
     instance: PdInstance,
+    input_channels: i32,
+    output_channels: i32,
+    sample_rate: i32,
+    /// The messages which are sent before processing, see [`Pd::audio_context_with_sender`].
+    queue: Option<Arc<MessageQueue>>,
 }
 
 impl PdAudioContext {
+    /// Gets the number of input channels which the instance was configured with when the context was created.
+    #[must_use]
+    pub const fn input_channels(&self) -> i32 {
+        self.input_channels
+    }
+
+    /// Gets the number of output channels which the instance was configured with when the context was created.
+    #[must_use]
+    pub const fn output_channels(&self) -> i32 {
+        self.output_channels
+    }
+
+    /// Gets the sample rate which the instance was configured with when the context was created.
+    #[must_use]
+    pub const fn sample_rate(&self) -> i32 {
+        self.sample_rate
+    }
+
+    /// Sets the instance as the current one and sends the message with [`OutgoingMessage::send`](crate::types::OutgoingMessage::send).
+    ///
+    /// # Errors
+    ///
+    /// The errors of the corresponding function in [`send`](crate::functions::send) are returned.
+    pub fn send(&self, message: &OutgoingMessage) -> Result<(), PdError> {
+        self.instance.set_as_current();
+        message.send()
+    }
+
+    /// Sends the messages of the [`PdSender`](crate::types::PdSender) of the context to the current instance.
+    fn send_queued_messages(&self) {
+        if let Some(queue) = &self.queue {
+            queue.send_all();
+        }
+    }
+
+    /// Creates an [`AudioProcessor`](crate::audio::AudioProcessor) which checks the buffers before processing them.
+    #[must_use]
+    pub fn processor(&self) -> AudioProcessor {
+        AudioProcessor::new(self.clone())
+    }
+
+    /// Creates a [`BlockAdapter`](crate::audio::BlockAdapter) which processes buffers of any number of frames.
+    #[must_use]
+    pub fn block_adapter<T: Sample>(&self) -> BlockAdapter<T> {
+        BlockAdapter::new(self.processor())
+    }
+
+    /// Creates a [`PlanarProcessor`](crate::audio::PlanarProcessor) which processes a buffer for each channel.
+    #[must_use]
+    pub fn planar_processor<T: Sample>(&self) -> PlanarProcessor<T> {
+        PlanarProcessor::new(self.processor())
+    }
+
+    /// Creates a [`Scheduler`](crate::audio::Scheduler) which sends messages right before the blocks they are scheduled in.
+    ///
+    /// The queue of the scheduler has room for `capacity` messages before it allocates.
+    #[must_use]
+    pub fn scheduler(&self, capacity: usize) -> Scheduler {
+        Scheduler::new(self.processor(), capacity)
+    }
+
     /// Sets the instance as the current one and calls [`receive_messages_from_pd`](crate::functions::receive::receive_messages_from_pd).
     pub fn receive_messages_from_pd(&self) {
         self.instance.set_as_current();
@@ -1100,68 +1984,45 @@ impl PdAudioContext {
         functions::receive::receive_midi_messages_from_pd();
     }
 
-    /// Sets the instance as the current one and calls [`process_float`](crate::functions::process::process_float).
+    /// Sets the instance as the current one, sends the messages of its [`PdSender`](crate::types::PdSender) and calls [`process_float`](crate::functions::process::process_float).
     pub fn process_float(&self, ticks: i32, input: &[f32], output: &mut [f32]) {
         self.instance.set_as_current();
+        self.send_queued_messages();
         functions::process::process_float(ticks, input, output);
     }
 
-    /// Sets the instance as the current one and calls [`process_double`](crate::functions::process::process_double).
+    /// Sets the instance as the current one, sends the messages of its [`PdSender`](crate::types::PdSender) and calls [`process_double`](crate::functions::process::process_double).
     pub fn process_double(&self, ticks: i32, input: &[f64], output: &mut [f64]) {
         self.instance.set_as_current();
+        self.send_queued_messages();
         functions::process::process_double(ticks, input, output);
     }
 
-    /// Sets the instance as the current one and calls [`process_short`](crate::functions::process::process_short).
+    /// Sets the instance as the current one, sends the messages of its [`PdSender`](crate::types::PdSender) and calls [`process_short`](crate::functions::process::process_short).
     pub fn process_short(&self, ticks: i32, input: &[i16], output: &mut [i16]) {
         self.instance.set_as_current();
+        self.send_queued_messages();
         functions::process::process_short(ticks, input, output);
     }
 
-    /// Sets the instance as the current one and calls [`process_raw`](crate::functions::process::process_raw).
+    /// Sets the instance as the current one, sends the messages of its [`PdSender`](crate::types::PdSender) and calls [`process_raw`](crate::functions::process::process_raw).
     pub fn process_raw(&self, input: &[f32], output: &mut [f32]) {
         self.instance.set_as_current();
+        self.send_queued_messages();
         functions::process::process_raw(input, output);
     }
 
-    /// Sets the instance as the current one and calls [`process_raw_short`](crate::functions::process::process_raw_short).
+    /// Sets the instance as the current one, sends the messages of its [`PdSender`](crate::types::PdSender) and calls [`process_raw_short`](crate::functions::process::process_raw_short).
     pub fn process_raw_short(&self, input: &[i16], output: &mut [i16]) {
         self.instance.set_as_current();
+        self.send_queued_messages();
         functions::process::process_raw_short(input, output);
     }
 
-    /// Sets the instance as the current one and calls [`process_raw_double`](crate::functions::process::process_raw_double).
+    /// Sets the instance as the current one, sends the messages of its [`PdSender`](crate::types::PdSender) and calls [`process_raw_double`](crate::functions::process::process_raw_double).
     pub fn process_raw_double(&self, input: &[f64], output: &mut [f64]) {
         self.instance.set_as_current();
+        self.send_queued_messages();
         functions::process::process_raw_double(input, output);
     }
 }
-
-/// When an instance is set as the active instance for the thread, this guard is returned.
-///
-/// When the guard is dropped, the previously active instance will be set as the active instance.
-struct ActiveInstanceGuard {
-    previous_instance: *mut _pdinstance,
-}
-
-impl ActiveInstanceGuard {
-    const fn wrap(previous_instance: *mut _pdinstance) -> Self {
-        Self { previous_instance }
-    }
-}
-
-impl Drop for ActiveInstanceGuard {
-    fn drop(&mut self) {
-        if self.previous_instance.is_null() {
-            // Main instance is always valid.
-            let main_instance = unsafe { libpd_sys::libpd_main_instance() };
-            unsafe {
-                libpd_sys::libpd_set_instance(main_instance);
-            }
-            return;
-        }
-        unsafe {
-            libpd_sys::libpd_set_instance(self.previous_instance);
-        }
-    }
-}
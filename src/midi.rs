@@ -0,0 +1,478 @@
+mod file;
+mod player;
+
+pub use file::{MidiEvent, MidiFile};
+pub use player::MidiPlayer;
+
+use std::fmt;
+
+use crate::error::MidiError;
+
+/// The number of channels on a MIDI port.
+const CHANNELS_PER_PORT: u8 = 16;
+
+/// Converts a raw MIDI byte or port which is received from libpd, clamping it to the range of a byte.
+pub(crate) fn saturating_byte(value: i32) -> u8 {
+    u8::try_from(value.clamp(0, i32::from(u8::MAX))).unwrap_or_default()
+}
+
+/// A zero-indexed MIDI channel on a zero-indexed port.
+///
+/// libpd encodes ports in channels as `channel + 16 * port`, which is what [`raw`](Channel::raw) returns
+/// and what `TryFrom<i32>` accepts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct Channel {
+    port: u8,
+    channel: u8,
+}
+
+impl Channel {
+    /// Creates a channel `0-15` on the first port.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`OutOfRange`](crate::error::MidiError::OutOfRange)
+    pub const fn new(channel: u8) -> Result<Self, MidiError> {
+        Self::on_port(0, channel)
+    }
+
+    /// Creates a channel `0-15` on a port.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`OutOfRange`](crate::error::MidiError::OutOfRange)
+    pub const fn on_port(port: u8, channel: u8) -> Result<Self, MidiError> {
+        if channel >= CHANNELS_PER_PORT {
+            return Err(MidiError::OutOfRange {
+                value: channel as i32,
+                max: CHANNELS_PER_PORT as i32 - 1,
+            });
+        }
+        Ok(Self { port, channel })
+    }
+
+    /// Gets the channel on its port, `0-15`.
+    pub const fn channel(self) -> u8 {
+        self.channel
+    }
+
+    /// Gets the port of the channel.
+    pub const fn port(self) -> u8 {
+        self.port
+    }
+
+    /// Gets the channel in the way libpd encodes it, `channel + 16 * port`.
+    pub const fn raw(self) -> i32 {
+        self.port as i32 * CHANNELS_PER_PORT as i32 + self.channel as i32
+    }
+
+    /// Converts a channel which is received from libpd, clamping it to the range of the type.
+    pub(crate) fn saturating_from_raw(raw: i32) -> Self {
+        let max =
+            i32::from(u8::MAX) * i32::from(CHANNELS_PER_PORT) + i32::from(CHANNELS_PER_PORT) - 1;
+        Self::try_from(raw.clamp(0, max)).unwrap_or_default()
+    }
+}
+
+impl TryFrom<i32> for Channel {
+    type Error = MidiError;
+
+    /// Converts a channel which is encoded the way libpd does, `channel + 16 * port`.
+    fn try_from(raw: i32) -> Result<Self, Self::Error> {
+        let channels = i32::from(CHANNELS_PER_PORT);
+        let port = raw
+            .checked_div(channels)
+            .and_then(|port| u8::try_from(port).ok());
+        let channel = raw
+            .checked_rem(channels)
+            .and_then(|channel| u8::try_from(channel).ok());
+        match (port, channel) {
+            (Some(port), Some(channel)) => Ok(Self { port, channel }),
+            _ => Err(MidiError::OutOfRange {
+                value: raw,
+                max: i32::from(u8::MAX) * channels + channels - 1,
+            }),
+        }
+    }
+}
+
+impl From<Channel> for i32 {
+    fn from(channel: Channel) -> Self {
+        channel.raw()
+    }
+}
+
+impl fmt::Display for Channel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.raw())
+    }
+}
+
+/// A 7 bit MIDI value `0-127`, like a pitch, a velocity or a controller value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct U7(u8);
+
+impl U7 {
+    /// The smallest value, `0`.
+    pub const MIN: Self = Self(0);
+    /// The largest value, `127`.
+    pub const MAX: Self = Self(127);
+
+    /// Creates a value `0-127`.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`OutOfRange`](crate::error::MidiError::OutOfRange)
+    pub const fn new(value: u8) -> Result<Self, MidiError> {
+        if value > Self::MAX.0 {
+            return Err(MidiError::OutOfRange {
+                value: value as i32,
+                max: Self::MAX.0 as i32,
+            });
+        }
+        Ok(Self(value))
+    }
+
+    /// Gets the value.
+    pub const fn value(self) -> u8 {
+        self.0
+    }
+
+    /// Converts a value which is received from libpd, clamping it to the range of the type.
+    pub(crate) fn saturating(value: i32) -> Self {
+        u8::try_from(value.clamp(0, i32::from(Self::MAX.0))).map_or(Self::MIN, Self)
+    }
+}
+
+impl TryFrom<u8> for U7 {
+    type Error = MidiError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        Self::new(value)
+    }
+}
+
+impl TryFrom<i32> for U7 {
+    type Error = MidiError;
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        u8::try_from(value)
+            .ok()
+            .and_then(|value| Self::new(value).ok())
+            .ok_or_else(|| MidiError::OutOfRange {
+                value,
+                max: i32::from(Self::MAX.0),
+            })
+    }
+}
+
+impl From<U7> for u8 {
+    fn from(value: U7) -> Self {
+        value.0
+    }
+}
+
+impl From<U7> for i32 {
+    fn from(value: U7) -> Self {
+        Self::from(value.0)
+    }
+}
+
+impl fmt::Display for U7 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// A 14 bit MIDI value `0-16383`, like a pitch bend where `8192` is the center.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct U14(u16);
+
+impl U14 {
+    /// The smallest value, `0`.
+    pub const MIN: Self = Self(0);
+    /// The center value of a pitch bend, `8192`.
+    pub const CENTER: Self = Self(8192);
+    /// The largest value, `16383`.
+    pub const MAX: Self = Self(16383);
+
+    /// Creates a value `0-16383`.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`OutOfRange`](crate::error::MidiError::OutOfRange)
+    pub const fn new(value: u16) -> Result<Self, MidiError> {
+        if value > Self::MAX.0 {
+            return Err(MidiError::OutOfRange {
+                value: value as i32,
+                max: Self::MAX.0 as i32,
+            });
+        }
+        Ok(Self(value))
+    }
+
+    /// Creates a value from its least and most significant 7 bits, the way it is sent in MIDI messages.
+    pub const fn from_bytes(lsb: U7, msb: U7) -> Self {
+        Self(((msb.0 as u16) << 7) | lsb.0 as u16)
+    }
+
+    /// Gets the value.
+    pub const fn value(self) -> u16 {
+        self.0
+    }
+
+    /// Gets the least significant 7 bits.
+    pub const fn lsb(self) -> U7 {
+        U7((self.0 & 0x7F) as u8)
+    }
+
+    /// Gets the most significant 7 bits.
+    #[expect(
+        clippy::cast_possible_truncation,
+        reason = "The value is at most 14 bits so the upper 7 bits fit in a byte."
+    )]
+    pub const fn msb(self) -> U7 {
+        U7((self.0 >> 7) as u8)
+    }
+
+    /// Gets the value relative to the center, `-8192-8191`, the way libpd sends and receives pitch bends.
+    pub const fn bend(self) -> i32 {
+        self.0 as i32 - Self::CENTER.0 as i32
+    }
+
+    /// Converts a pitch bend which is received from libpd, `-8192-8191`, clamping it to the range of the type.
+    pub(crate) fn saturating_from_bend(bend: i32) -> Self {
+        let value = bend.saturating_add(i32::from(Self::CENTER.0));
+        u16::try_from(value.clamp(0, i32::from(Self::MAX.0))).map_or(Self::MIN, Self)
+    }
+}
+
+impl Default for U14 {
+    /// The center value.
+    fn default() -> Self {
+        Self::CENTER
+    }
+}
+
+impl TryFrom<u16> for U14 {
+    type Error = MidiError;
+
+    fn try_from(value: u16) -> Result<Self, Self::Error> {
+        Self::new(value)
+    }
+}
+
+impl TryFrom<i32> for U14 {
+    type Error = MidiError;
+
+    fn try_from(value: i32) -> Result<Self, Self::Error> {
+        u16::try_from(value)
+            .ok()
+            .and_then(|value| Self::new(value).ok())
+            .ok_or_else(|| MidiError::OutOfRange {
+                value,
+                max: i32::from(Self::MAX.0),
+            })
+    }
+}
+
+impl From<U14> for u16 {
+    fn from(value: U14) -> Self {
+        value.0
+    }
+}
+
+impl From<U14> for i32 {
+    fn from(value: U14) -> Self {
+        Self::from(value.0)
+    }
+}
+
+impl fmt::Display for U14 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+/// A MIDI channel voice message.
+///
+/// It converts from and to the bytes of the message, the channel of the bytes is on the first port.
+///
+/// # Examples
+/// ```rust
+/// use libpd_rs::midi::{Channel, MidiMessage, U7};
+///
+/// let message = MidiMessage::try_from(&[0x91, 60, 100][..]).unwrap();
+/// assert_eq!(
+///     message,
+///     MidiMessage::NoteOn {
+///         channel: Channel::new(1).unwrap(),
+///         pitch: U7::new(60).unwrap(),
+///         velocity: U7::new(100).unwrap(),
+///     }
+/// );
+/// assert_eq!(Vec::from(message), [0x91, 60, 100]);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MidiMessage {
+    /// A note off, pd receives it as a note on with a velocity of `0`.
+    NoteOff {
+        /// The channel of the note.
+        channel: Channel,
+        /// The pitch of the note.
+        pitch: U7,
+        /// The release velocity of the note, pd ignores it.
+        velocity: U7,
+    },
+    /// A note on, a velocity of `0` means note off.
+    NoteOn {
+        /// The channel of the note.
+        channel: Channel,
+        /// The pitch of the note.
+        pitch: U7,
+        /// The velocity of the note.
+        velocity: U7,
+    },
+    /// A poly after touch, the pressure of a single note.
+    PolyAfterTouch {
+        /// The channel of the note.
+        channel: Channel,
+        /// The pitch of the note.
+        pitch: U7,
+        /// The amount of pressure.
+        value: U7,
+    },
+    /// A control change.
+    ControlChange {
+        /// The channel of the controller.
+        channel: Channel,
+        /// The number of the controller.
+        controller: U7,
+        /// The value of the controller.
+        value: U7,
+    },
+    /// A program change.
+    ProgramChange {
+        /// The channel of the program.
+        channel: Channel,
+        /// The number of the program.
+        program: U7,
+    },
+    /// An after touch, the pressure of the whole channel.
+    AfterTouch {
+        /// The channel of the pressure.
+        channel: Channel,
+        /// The amount of pressure.
+        value: U7,
+    },
+    /// A pitch bend.
+    PitchBend {
+        /// The channel of the bend.
+        channel: Channel,
+        /// The amount of bend, [`U14::CENTER`] is no bend.
+        value: U14,
+    },
+}
+
+impl MidiMessage {
+    /// Gets the channel of the message.
+    pub const fn channel(self) -> Channel {
+        match self {
+            Self::NoteOff { channel, .. }
+            | Self::NoteOn { channel, .. }
+            | Self::PolyAfterTouch { channel, .. }
+            | Self::ControlChange { channel, .. }
+            | Self::ProgramChange { channel, .. }
+            | Self::AfterTouch { channel, .. }
+            | Self::PitchBend { channel, .. } => channel,
+        }
+    }
+
+    /// Gets the status byte of the message with the channel on its port.
+    const fn status(self) -> u8 {
+        let kind = match self {
+            Self::NoteOff { .. } => 0x80,
+            Self::NoteOn { .. } => 0x90,
+            Self::PolyAfterTouch { .. } => 0xA0,
+            Self::ControlChange { .. } => 0xB0,
+            Self::ProgramChange { .. } => 0xC0,
+            Self::AfterTouch { .. } => 0xD0,
+            Self::PitchBend { .. } => 0xE0,
+        };
+        kind | self.channel().channel()
+    }
+}
+
+impl TryFrom<&[u8]> for MidiMessage {
+    type Error = MidiError;
+
+    /// Parses the bytes of a single channel voice message.
+    fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+        let invalid = || MidiError::InvalidMessage(bytes.to_vec());
+        let (&status, data) = bytes.split_first().ok_or_else(invalid)?;
+        let channel = Channel::new(status & 0x0F)?;
+        let data = data
+            .iter()
+            .map(|byte| U7::new(*byte).map_err(|_| invalid()))
+            .collect::<Result<Vec<_>, _>>()?;
+        let message = match (status & 0xF0, data.as_slice()) {
+            (0x80, &[pitch, velocity]) => Self::NoteOff {
+                channel,
+                pitch,
+                velocity,
+            },
+            (0x90, &[pitch, velocity]) => Self::NoteOn {
+                channel,
+                pitch,
+                velocity,
+            },
+            (0xA0, &[pitch, value]) => Self::PolyAfterTouch {
+                channel,
+                pitch,
+                value,
+            },
+            (0xB0, &[controller, value]) => Self::ControlChange {
+                channel,
+                controller,
+                value,
+            },
+            (0xC0, &[program]) => Self::ProgramChange { channel, program },
+            (0xD0, &[value]) => Self::AfterTouch { channel, value },
+            (0xE0, &[lsb, msb]) => Self::PitchBend {
+                channel,
+                value: U14::from_bytes(lsb, msb),
+            },
+            _ => return Err(invalid()),
+        };
+        Ok(message)
+    }
+}
+
+impl From<MidiMessage> for Vec<u8> {
+    /// Converts the message to its bytes, the port of the channel is not a part of them.
+    fn from(message: MidiMessage) -> Self {
+        let status = message.status();
+        let data = match message {
+            MidiMessage::NoteOff {
+                pitch, velocity, ..
+            }
+            | MidiMessage::NoteOn {
+                pitch, velocity, ..
+            } => vec![pitch, velocity],
+            MidiMessage::PolyAfterTouch { pitch, value, .. } => vec![pitch, value],
+            MidiMessage::ControlChange {
+                controller, value, ..
+            } => vec![controller, value],
+            MidiMessage::ProgramChange { program, .. } => vec![program],
+            MidiMessage::AfterTouch { value, .. } => vec![value],
+            MidiMessage::PitchBend { value, .. } => vec![value.lsb(), value.msb()],
+        };
+        let mut bytes = vec![status];
+        bytes.extend(data.into_iter().map(u8::from));
+        bytes
+    }
+}
@@ -0,0 +1,387 @@
+#![expect(
+    clippy::big_endian_bytes,
+    reason = "Standard MIDI files store their numbers in big endian."
+)]
+
+use std::{fs, path::Path, time::Duration};
+
+use crate::{
+    error::{IoError, MidiError, PdError},
+    midi::MidiMessage,
+};
+
+/// The tempo of a file which does not set one, 120 beats per minute.
+const DEFAULT_TEMPO: u32 = 500_000;
+
+/// A channel voice message of a MIDI file with the time it is played at.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MidiEvent {
+    /// The time from the start of the file.
+    pub time: Duration,
+    /// The index of the track which the message is in.
+    pub track: usize,
+    /// The message.
+    pub message: MidiMessage,
+}
+
+/// A standard MIDI file of format `0` or `1` with the times of its events resolved through its tempo map.
+///
+/// The channel voice messages of all the tracks are merged in the order they are played,
+/// system exclusive and meta events other than tempo changes are skipped.
+///
+/// # Examples
+/// ```no_run
+/// use libpd_rs::midi::MidiFile;
+///
+/// let file = MidiFile::open("song.mid").unwrap();
+/// for event in file.events() {
+///     println!("{:?}: {:?}", event.time, event.message);
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MidiFile {
+    format: u16,
+    tracks: usize,
+    events: Vec<MidiEvent>,
+    duration: Duration,
+}
+
+impl MidiFile {
+    /// Reads and parses a MIDI file.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`IoError`](crate::error::IoError)
+    ///   - [`PathDoesNotExist`](crate::error::IoError::PathDoesNotExist)
+    /// - [`MidiError`](crate::error::MidiError)
+    ///   - [`InvalidFile`](crate::error::MidiError::InvalidFile)
+    ///   - [`UnsupportedFormat`](crate::error::MidiError::UnsupportedFormat)
+    pub fn open<T: AsRef<Path>>(path: T) -> Result<Self, PdError> {
+        let path = path.as_ref();
+        let bytes = fs::read(path)
+            .map_err(|_| IoError::PathDoesNotExist(path.to_string_lossy().into_owned()))?;
+        Ok(Self::parse(&bytes)?)
+    }
+
+    /// Parses the contents of a MIDI file.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`InvalidFile`](crate::error::MidiError::InvalidFile)
+    /// - [`UnsupportedFormat`](crate::error::MidiError::UnsupportedFormat)
+    pub fn parse(bytes: &[u8]) -> Result<Self, MidiError> {
+        let mut reader = Reader::new(bytes);
+        if reader.take(4)? != b"MThd" {
+            return Err(invalid("the header chunk is missing"));
+        }
+        let header_length = reader.u32()?;
+        let format = reader.u16()?;
+        let track_count = usize::from(reader.u16()?);
+        let timing = Timing::new(reader.u16()?)?;
+        reader.skip(header_length.saturating_sub(6))?;
+        if format > 1 {
+            return Err(MidiError::UnsupportedFormat(format));
+        }
+
+        let mut records = vec![];
+        let mut tracks = 0;
+        while tracks < track_count && !reader.is_empty() {
+            let id = reader.take(4)?;
+            let length = reader.u32()?;
+            let chunk = reader.take(usize::try_from(length).unwrap_or(usize::MAX))?;
+            // Chunks of unknown types are skipped as the specification asks.
+            if id == b"MTrk" {
+                read_track(chunk, tracks, &mut records)?;
+                tracks += 1;
+            }
+        }
+        if tracks < track_count {
+            return Err(invalid("the file ends before all of its tracks"));
+        }
+
+        // Tempo changes apply to all the tracks, they go before the messages of the same tick.
+        records.sort_by_key(|record| (record.tick, !matches!(record.kind, Kind::Tempo(_))));
+        let mut tempo_map = TempoMap::new(timing);
+        let mut events = vec![];
+        let mut duration = Duration::ZERO;
+        for record in records {
+            let time = tempo_map.time(record.tick);
+            duration = duration.max(time);
+            match record.kind {
+                Kind::Tempo(tempo) => tempo_map.set_tempo(record.tick, tempo),
+                Kind::Message(message) => events.push(MidiEvent {
+                    time,
+                    track: record.track,
+                    message,
+                }),
+                Kind::End => {}
+            }
+        }
+
+        Ok(Self {
+            format,
+            tracks,
+            events,
+            duration,
+        })
+    }
+
+    /// Gets the format of the file, `0` for a single track or `1` for simultaneous tracks.
+    pub const fn format(&self) -> u16 {
+        self.format
+    }
+
+    /// Gets the number of tracks in the file.
+    pub const fn track_count(&self) -> usize {
+        self.tracks
+    }
+
+    /// Gets the messages of all the tracks in the order they are played.
+    pub fn events(&self) -> &[MidiEvent] {
+        &self.events
+    }
+
+    /// Gets the length of the file, which is the end of its longest track.
+    pub const fn duration(&self) -> Duration {
+        self.duration
+    }
+}
+
+fn invalid(reason: &str) -> MidiError {
+    MidiError::InvalidFile(reason.to_owned())
+}
+
+/// An event of a track at the tick it happens.
+struct Record {
+    tick: u64,
+    track: usize,
+    kind: Kind,
+}
+
+enum Kind {
+    /// A tempo change in microseconds per quarter note.
+    Tempo(u32),
+    Message(MidiMessage),
+    /// The end of a track.
+    End,
+}
+
+fn read_track(chunk: &[u8], track: usize, records: &mut Vec<Record>) -> Result<(), MidiError> {
+    let mut reader = Reader::new(chunk);
+    let mut tick = 0u64;
+    let mut running_status = None;
+    while !reader.is_empty() {
+        tick = tick.saturating_add(u64::from(reader.vlq()?));
+        let first = reader.u8()?;
+        let kind = match first {
+            0xFF => {
+                // Meta events cancel the running status like system exclusive ones.
+                running_status = None;
+                let meta = reader.u8()?;
+                let length = reader.vlq()?;
+                let data = reader.take(usize::try_from(length).unwrap_or(usize::MAX))?;
+                match (meta, data) {
+                    (0x2F, _) => {
+                        records.push(Record {
+                            tick,
+                            track,
+                            kind: Kind::End,
+                        });
+                        return Ok(());
+                    }
+                    (0x51, &[high, middle, low]) => {
+                        Kind::Tempo(u32::from_be_bytes([0, high, middle, low]))
+                    }
+                    _ => continue,
+                }
+            }
+            0xF0 | 0xF7 => {
+                running_status = None;
+                let length = reader.vlq()?;
+                reader.skip(length)?;
+                continue;
+            }
+            0xF1..=0xFE => return Err(invalid("a track has a system common or realtime message")),
+            0x80..=0xEF => {
+                running_status = Some(first);
+                Kind::Message(read_message(&mut reader, first, None)?)
+            }
+            _ => {
+                let status = running_status
+                    .ok_or_else(|| invalid("a track has data without a status byte"))?;
+                Kind::Message(read_message(&mut reader, status, Some(first))?)
+            }
+        };
+        records.push(Record { tick, track, kind });
+    }
+    // Tracks without an end of track event are accepted, they end at their last event.
+    records.push(Record {
+        tick,
+        track,
+        kind: Kind::End,
+    });
+    Ok(())
+}
+
+/// Reads a channel voice message, the first data byte is already read if the status is running.
+fn read_message(
+    reader: &mut Reader<'_>,
+    status: u8,
+    first: Option<u8>,
+) -> Result<MidiMessage, MidiError> {
+    let first = match first {
+        Some(first) => first,
+        None => reader.u8()?,
+    };
+    let message = if matches!(status & 0xF0, 0xC0 | 0xD0) {
+        MidiMessage::try_from(&[status, first][..])
+    } else {
+        MidiMessage::try_from(&[status, first, reader.u8()?][..])
+    };
+    message.map_err(|_| invalid("a track has a data byte which is out of range"))
+}
+
+/// How the ticks of the file relate to time.
+#[derive(Debug, Clone, Copy)]
+enum Timing {
+    /// Ticks per quarter note, their length depends on the tempo.
+    Metrical(u16),
+    /// Ticks of a fixed length, as a fraction of nanoseconds.
+    Timecode { nanos: u128, ticks: u128 },
+}
+
+impl Timing {
+    fn new(division: u16) -> Result<Self, MidiError> {
+        let [high, low] = division.to_be_bytes();
+        if high & 0x80 == 0 {
+            if division == 0 {
+                return Err(invalid("the file has no ticks per quarter note"));
+            }
+            return Ok(Self::Metrical(division));
+        }
+        // The frame rate is stored as a negative number.
+        let ticks_per_frame = u128::from(low);
+        let (nanos, frames) = match i8::from_be_bytes([high]).unsigned_abs() {
+            // 29 stands for the drop frame rate of 29.97 frames per second.
+            29 => (1_001_000_000_000, 30_000),
+            fps @ (24 | 25 | 30) => (1_000_000_000, u128::from(fps)),
+            _ => return Err(invalid("the file has an unknown frame rate")),
+        };
+        if ticks_per_frame == 0 {
+            return Err(invalid("the file has no ticks per frame"));
+        }
+        Ok(Self::Timecode {
+            nanos,
+            ticks: frames * ticks_per_frame,
+        })
+    }
+}
+
+/// Converts ticks to time, following the tempo changes which are set in the order of their ticks.
+struct TempoMap {
+    timing: Timing,
+    /// The tick of the last tempo change.
+    tick: u64,
+    /// The time of the last tempo change in nanoseconds.
+    nanos: u128,
+    /// The tempo in microseconds per quarter note.
+    tempo: u32,
+}
+
+impl TempoMap {
+    const fn new(timing: Timing) -> Self {
+        Self {
+            timing,
+            tick: 0,
+            nanos: 0,
+            tempo: DEFAULT_TEMPO,
+        }
+    }
+
+    fn nanos(&self, tick: u64) -> u128 {
+        let (nanos, ticks) = match self.timing {
+            Timing::Metrical(ticks) => (u128::from(self.tempo) * 1000, u128::from(ticks)),
+            Timing::Timecode { nanos, ticks } => (nanos, ticks),
+        };
+        let elapsed = u128::from(tick.saturating_sub(self.tick)) * nanos;
+        self.nanos + elapsed.checked_div(ticks).unwrap_or_default()
+    }
+
+    fn time(&self, tick: u64) -> Duration {
+        Duration::from_nanos(u64::try_from(self.nanos(tick)).unwrap_or(u64::MAX))
+    }
+
+    fn set_tempo(&mut self, tick: u64, tempo: u32) {
+        self.nanos = self.nanos(tick);
+        self.tick = tick;
+        self.tempo = tempo;
+    }
+}
+
+/// Reads the big endian numbers of a MIDI file.
+struct Reader<'bytes> {
+    bytes: &'bytes [u8],
+}
+
+impl<'bytes> Reader<'bytes> {
+    const fn new(bytes: &'bytes [u8]) -> Self {
+        Self { bytes }
+    }
+
+    const fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    fn take(&mut self, count: usize) -> Result<&'bytes [u8], MidiError> {
+        let (taken, rest) = self
+            .bytes
+            .split_at_checked(count)
+            .ok_or_else(|| invalid("the file ends unexpectedly"))?;
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    fn skip(&mut self, count: u32) -> Result<(), MidiError> {
+        self.take(usize::try_from(count).unwrap_or(usize::MAX))
+            .map(|_| ())
+    }
+
+    fn u8(&mut self) -> Result<u8, MidiError> {
+        let (&byte, rest) = self
+            .bytes
+            .split_first()
+            .ok_or_else(|| invalid("the file ends unexpectedly"))?;
+        self.bytes = rest;
+        Ok(byte)
+    }
+
+    fn u16(&mut self) -> Result<u16, MidiError> {
+        Ok(u16::from_be_bytes([self.u8()?, self.u8()?]))
+    }
+
+    fn u32(&mut self) -> Result<u32, MidiError> {
+        Ok(u32::from_be_bytes([
+            self.u8()?,
+            self.u8()?,
+            self.u8()?,
+            self.u8()?,
+        ]))
+    }
+
+    /// Reads a variable length quantity, 7 bits per byte with the high bit set on all but the last byte.
+    fn vlq(&mut self) -> Result<u32, MidiError> {
+        let mut value = 0u32;
+        for _ in 0..4 {
+            let byte = self.u8()?;
+            value = (value << 7) | u32::from(byte & 0x7F);
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(invalid(
+            "the file has a variable length number which is too long",
+        ))
+    }
+}
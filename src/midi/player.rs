@@ -0,0 +1,229 @@
+use std::time::Duration;
+
+use crate::{
+    audio::{frames_in, ScheduleAt, Scheduler},
+    error::{PdError, SizeError},
+    midi::{Channel, MidiFile, MidiMessage, U7},
+    types::OutgoingMessage,
+};
+
+/// The number of notes which can sound at the same time before the player allocates.
+const SOUNDING_CAPACITY: usize = 128;
+
+/// Plays a [`MidiFile`] into pd by scheduling its messages on a [`Scheduler`], in sync with its logical time.
+///
+/// Before each buffer is processed, [`schedule`](MidiPlayer::schedule) puts the messages which fall into the
+/// next frames of the file on the scheduler, which sends them right before the blocks they fall into.
+/// This works the same in an audio callback and in an offline loop.
+///
+/// The player keeps track of the notes which are sounding, they are released when it seeks or loops.
+///
+/// # Examples
+/// ```no_run
+/// use libpd_rs::{midi::{MidiFile, MidiPlayer}, Pd};
+///
+/// let pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+/// let _patch = pd.open_patch("tests/patches/synth.pd").unwrap();
+/// let mut scheduler = pd.audio_context().scheduler(256);
+///
+/// let file = MidiFile::open("song.mid").unwrap();
+/// let mut player = MidiPlayer::new(&file, 44100).unwrap();
+/// player.set_looping(true);
+///
+/// // In the audio callback.
+/// let mut output = [0.0_f32; 1024];
+/// player.schedule(&mut scheduler, output.len() / 2).unwrap();
+/// scheduler.process(&[], &mut output).unwrap();
+/// ```
+#[derive(Debug, Clone)]
+pub struct MidiPlayer {
+    /// The messages of the file at the frames they are played at.
+    events: Vec<(u64, MidiMessage)>,
+    /// The length of the file in frames.
+    length: u64,
+    sample_rate: u32,
+    /// The frame of the file which the next scheduled buffer starts at.
+    position: u64,
+    /// The index of the next event to be scheduled.
+    next: usize,
+    looping: bool,
+    /// The notes which are turned on and not off yet.
+    sounding: Vec<(Channel, U7)>,
+    /// Whether the sounding notes are released before the next buffer.
+    release: bool,
+}
+
+impl MidiPlayer {
+    /// Creates a player which starts at the beginning of the file,
+    /// the sample rate should be the one which pd is configured with.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`SizeError`](crate::error::SizeError)
+    ///   - [`TooLarge`](crate::error::SizeError::TooLarge)
+    pub fn new(file: &MidiFile, sample_rate: u32) -> Result<Self, PdError> {
+        let frames = |time| -> Result<u64, PdError> {
+            u64::try_from(frames_in(time, sample_rate)?).map_err(|_| SizeError::TooLarge.into())
+        };
+        let events = file
+            .events()
+            .iter()
+            .map(|event| Ok((frames(event.time)?, event.message)))
+            .collect::<Result<Vec<_>, PdError>>()?;
+        Ok(Self {
+            events,
+            length: frames(file.duration())?,
+            sample_rate,
+            position: 0,
+            next: 0,
+            looping: false,
+            sounding: Vec::with_capacity(SOUNDING_CAPACITY),
+            release: false,
+        })
+    }
+
+    /// Gets the position in the file which the next buffer starts at.
+    pub fn position(&self) -> Duration {
+        let nanos = u128::from(self.position) * 1_000_000_000;
+        let nanos = nanos
+            .checked_div(u128::from(self.sample_rate))
+            .unwrap_or_default();
+        Duration::from_nanos(u64::try_from(nanos).unwrap_or(u64::MAX))
+    }
+
+    /// Whether the player starts again from the beginning when it reaches the end of the file.
+    pub const fn is_looping(&self) -> bool {
+        self.looping
+    }
+
+    /// Sets whether the player starts again from the beginning when it reaches the end of the file.
+    pub const fn set_looping(&mut self, looping: bool) {
+        self.looping = looping;
+    }
+
+    /// Whether all the messages of the file are scheduled, a looping player never finishes.
+    pub const fn is_finished(&self) -> bool {
+        !self.looping && self.next >= self.events.len()
+    }
+
+    /// Moves to a position in the file, the notes which are sounding are released before the next buffer.
+    ///
+    /// Positions past the end of a looping file wrap around to its beginning.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`SizeError`](crate::error::SizeError)
+    ///   - [`TooLarge`](crate::error::SizeError::TooLarge)
+    pub fn seek(&mut self, position: Duration) -> Result<(), PdError> {
+        let mut frame = u64::try_from(frames_in(position, self.sample_rate)?)
+            .map_err(|_| SizeError::TooLarge)?;
+        if self.looping {
+            frame = frame.checked_rem(self.length).unwrap_or_default();
+        }
+        self.position = frame;
+        self.next = self.events.partition_point(|(event, _)| *event < frame);
+        self.release = true;
+        Ok(())
+    }
+
+    /// Schedules the messages which fall into the next `frames` frames of the file,
+    /// starting from the logical time of the scheduler.
+    ///
+    /// Call this with the number of frames of each buffer before the scheduler processes it.
+    ///
+    /// The player only moves past the messages which are scheduled,
+    /// so the ones which are left when this fails are scheduled by the next call.
+    ///
+    /// # Errors
+    ///
+    /// The errors of [`Scheduler::schedule`].
+    pub fn schedule(&mut self, scheduler: &mut Scheduler, frames: usize) -> Result<(), PdError> {
+        let frames = u64::try_from(frames).map_err(|_| SizeError::TooLarge)?;
+        // The frame of the scheduler which the position of the file is played at.
+        let mut start = scheduler.frame();
+        if self.release {
+            self.release_notes(scheduler, start)?;
+            self.release = false;
+        }
+        let end = start + frames;
+        loop {
+            let remaining = end - start;
+            let wraps = self.looping && self.length > 0 && self.position + remaining >= self.length;
+            let until = if wraps {
+                self.length
+            } else {
+                self.position + remaining
+            };
+            // The messages at the very end of a looping file go before it starts again.
+            while let Some(&(frame, message)) = self
+                .events
+                .get(self.next)
+                .filter(|(frame, _)| *frame < until || (wraps && *frame == until))
+            {
+                let at = start + (frame - self.position);
+                scheduler.schedule(ScheduleAt::Frame(at), message.into())?;
+                self.track(message);
+                self.next += 1;
+            }
+            start += until - self.position;
+            self.position = until;
+            if !wraps {
+                return Ok(());
+            }
+            self.restart(scheduler, start)?;
+            if start >= end {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Starts the file again from its beginning at a frame of the scheduler.
+    fn restart(&mut self, scheduler: &mut Scheduler, at: u64) -> Result<(), PdError> {
+        self.release_notes(scheduler, at)?;
+        self.position = 0;
+        self.next = 0;
+        Ok(())
+    }
+
+    /// Schedules a note off for each note which is sounding, the notes stay sounding until theirs are scheduled.
+    fn release_notes(&mut self, scheduler: &mut Scheduler, at: u64) -> Result<(), PdError> {
+        let mut released = 0;
+        let result = self.sounding.iter().try_for_each(|&(channel, pitch)| {
+            let note_off = OutgoingMessage::NoteOn {
+                channel,
+                pitch,
+                velocity: U7::MIN,
+            };
+            scheduler.schedule(ScheduleAt::Frame(at), note_off)?;
+            released += 1;
+            Ok(())
+        });
+        self.sounding.drain(..released);
+        result
+    }
+
+    fn track(&mut self, message: MidiMessage) {
+        let (channel, pitch, on) = match message {
+            MidiMessage::NoteOn {
+                channel,
+                pitch,
+                velocity,
+            } => (channel, pitch, velocity > U7::MIN),
+            MidiMessage::NoteOff { channel, pitch, .. } => (channel, pitch, false),
+            _ => return,
+        };
+        let position = self
+            .sounding
+            .iter()
+            .position(|note| *note == (channel, pitch));
+        match (on, position) {
+            (true, None) => self.sounding.push((channel, pitch)),
+            (false, Some(position)) => {
+                self.sounding.swap_remove(position);
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,268 @@
+/// Reading and writing the contents of pd files.
+///
+/// [`PatchFile`](crate::patch::file::PatchFile) parses the records of a pd file into a typed tree
+/// and writes it back to the same text.
+pub mod file;
+
+mod builder;
+mod canvas;
+mod watch;
+
+pub use builder::PatchBuilder;
+pub use canvas::Canvas;
+pub(crate) use watch::Watcher;
+
+use libpd_sys::_pdinstance;
+use std::{
+    fs, mem,
+    path::{Path, PathBuf},
+    sync::{Mutex, PoisonError},
+};
+use tempfile::NamedTempFile;
+
+use crate::{
+    error::{IoError, PatchLifeCycleError, PdError},
+    functions,
+    instance::ActiveInstanceGuard,
+    patch::file::PatchFile,
+    types::PatchFileHandle,
+};
+
+/// The index of a box in the patch which is being built, to connect it to other boxes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BoxId(usize);
+
+impl BoxId {
+    /// The index of the box which pd uses in the `#X connect` records.
+    pub const fn index(self) -> usize {
+        self.0
+    }
+}
+
+/// The patches which are open, as pairs of the instance and the patch handle pointers.
+///
+/// A patch is only closed if it is still in here so patches of freed instances are not touched.
+static OPEN_PATCHES: Mutex<Vec<(usize, usize)>> = Mutex::new(Vec::new());
+
+/// A patch which is open in a pd instance.
+///
+/// The patch is closed when this is dropped,
+/// any number of patches can be open at the same time in an instance.
+///
+/// If the instance which the patch is opened in is freed first, dropping this does nothing.
+///
+/// # Examples
+/// ```no_run
+/// use libpd_rs::Pd;
+///
+/// let pd = Pd::init_and_configure(1, 2, 44100).unwrap();
+///
+/// let synth = pd.open_patch("tests/patches/sine.pd").unwrap();
+/// let echo = pd.open_patch("tests/patches/echo.pd").unwrap();
+/// assert_ne!(synth.dollar_zero(), echo.dollar_zero());
+///
+/// // Closes the patch.
+/// drop(synth);
+/// // Closes the patch and reports errors.
+/// echo.close().unwrap();
+/// ```
+#[derive(Debug)]
+#[must_use = "The patch is closed when it is dropped."]
+pub struct Patch {
+    handle: Option<PatchFileHandle>,
+    instance: *mut _pdinstance,
+    dollar_zero: i32,
+    path: PathBuf,
+    /// The number of boxes in the root canvas, counted when a [`Canvas`] is first requested.
+    boxes: Option<usize>,
+    /// Holds the file of an evaluated patch until the patch is closed.
+    _temporary_file: Option<NamedTempFile>,
+}
+
+// Closing the patch sets its own instance as the active one before operating.
+#[expect(
+    clippy::non_send_fields_in_send_ty,
+    reason = "The pointers are only used while their instance is active."
+)]
+unsafe impl Send for Patch {}
+
+impl Patch {
+    /// Opens a patch in the current instance.
+    pub(crate) fn open<T: AsRef<Path>>(
+        path: T,
+        temporary_file: Option<NamedTempFile>,
+    ) -> Result<Self, PdError> {
+        let path = functions::resolve_patch_path(path)?;
+        let handle = functions::open_patch(&path)?;
+        let instance = unsafe { libpd_sys::libpd_this_instance() };
+        let dollar_zero = match functions::get_dollar_zero(&handle) {
+            Ok(dollar_zero) => dollar_zero,
+            Err(err) => {
+                functions::close_patch(handle)?;
+                return Err(err.into());
+            }
+        };
+        OPEN_PATCHES
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .push((instance as usize, handle.as_mut_ptr() as usize));
+        Ok(Self {
+            handle: Some(handle),
+            instance,
+            dollar_zero,
+            path,
+            boxes: None,
+            _temporary_file: temporary_file,
+        })
+    }
+
+    /// Gets the `$0` of the patch.
+    ///
+    /// `$0` id in pd could be thought as a auto generated unique identifier for the patch.
+    pub const fn dollar_zero(&self) -> i32 {
+        self.dollar_zero
+    }
+
+    /// Gets the path of the file which the patch is opened from, relative paths are resolved
+    /// the way [`open_patch`](crate::functions::open_patch) resolves them.
+    ///
+    /// For evaluated patches this is the path of the temporary file which holds the contents.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Gets the handle of the patch to be used with the functions in [`functions`](crate::functions).
+    ///
+    /// The handle stays valid as long as this patch is alive.
+    pub const fn handle(&self) -> Option<&PatchFileHandle> {
+        self.handle.as_ref()
+    }
+
+    /// Gets the root canvas of the patch to edit it while it is running.
+    ///
+    /// The boxes which are already in the patch are counted from its file when this is first called,
+    /// after that the indices only account for the changes which are made through the canvas.
+    ///
+    /// # Examples
+    /// ```no_run
+    /// use libpd_rs::Pd;
+    ///
+    /// let pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+    /// let mut patch = pd.open_patch("tests/patches/sine.pd").unwrap();
+    ///
+    /// let mut canvas = patch.canvas().unwrap();
+    /// let oscillator = canvas.add_object(20, 20, "osc~ 220").unwrap();
+    /// let dac = canvas.add_object(20, 60, "dac~").unwrap();
+    /// canvas.connect(oscillator, 0, dac, 1).unwrap();
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`PatchLifeCycleError`](crate::error::PatchLifeCycleError)
+    ///   - [`PatchIsNotOpen`](crate::error::PatchLifeCycleError::PatchIsNotOpen)
+    /// - [`IoError`](crate::error::IoError)
+    ///   - [`PathDoesNotExist`](crate::error::IoError::PathDoesNotExist)
+    /// - [`PatchFileError`](crate::error::PatchFileError)
+    pub fn canvas(&mut self) -> Result<Canvas<'_>, PdError> {
+        if !self.is_open() {
+            return Err(PatchLifeCycleError::PatchIsNotOpen.into());
+        }
+        if self.boxes.is_none() {
+            let contents = fs::read_to_string(&self.path)
+                .map_err(|_| IoError::PathDoesNotExist(self.path.to_string_lossy().into_owned()))?;
+            let file: PatchFile = contents.parse()?;
+            let boxes = file
+                .canvas
+                .entries
+                .iter()
+                .filter(|entry| entry.is_box())
+                .count();
+            self.boxes = Some(boxes);
+        }
+        Ok(Canvas::new(self))
+    }
+
+    /// Whether the patch is still open, it is not after its instance is freed.
+    fn is_open(&self) -> bool {
+        self.handle.as_ref().is_some_and(|handle| {
+            let key = (self.instance as usize, handle.as_mut_ptr() as usize);
+            OPEN_PATCHES
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .contains(&key)
+        })
+    }
+
+    /// Closes the patch.
+    ///
+    /// This is what dropping the patch does, use this to be notified about errors.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`PatchLifeCycleError`](crate::error::PatchLifeCycleError)
+    ///   - [`FailedToClosePatch`](crate::error::PatchLifeCycleError::FailedToClosePatch)
+    pub fn close(mut self) -> Result<(), PdError> {
+        self.close_inner()
+    }
+
+    fn close_inner(&mut self) -> Result<(), PdError> {
+        let Some(handle) = self.handle.take() else {
+            return Ok(());
+        };
+        let mut open_patches = OPEN_PATCHES.lock().unwrap_or_else(PoisonError::into_inner);
+        let key = (self.instance as usize, handle.as_mut_ptr() as usize);
+        let Some(position) = open_patches.iter().position(|open| *open == key) else {
+            // The instance is already freed.
+            return Ok(());
+        };
+        open_patches.swap_remove(position);
+        drop(open_patches);
+
+        let _guard = ActiveInstanceGuard::activate(self.instance);
+        functions::close_patch(handle)?;
+        Ok(())
+    }
+}
+
+impl Drop for Patch {
+    #[expect(
+        clippy::let_underscore_must_use,
+        clippy::let_underscore_untyped,
+        reason = "There is no way to report an error from drop, use `close` to handle it."
+    )]
+    fn drop(&mut self) {
+        let _ = self.close_inner();
+    }
+}
+
+/// Forgets the patches of an instance which is about to be freed.
+pub(crate) fn forget_patches_of_instance(instance: *mut _pdinstance) {
+    OPEN_PATCHES
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .retain(|(open_instance, _)| *open_instance != instance as usize);
+}
+
+/// Splits the text of a box into atoms like pd does when it is typed, commas and semicolons are atoms on their own.
+fn atoms(text: &str) -> Vec<String> {
+    let mut atoms = vec![];
+    let mut atom = String::new();
+    for character in text.chars() {
+        if character.is_whitespace() || matches!(character, ',' | ';') {
+            if !atom.is_empty() {
+                atoms.push(mem::take(&mut atom));
+            }
+            if !character.is_whitespace() {
+                atoms.push(character.to_string());
+            }
+        } else {
+            atom.push(character);
+        }
+    }
+    if !atom.is_empty() {
+        atoms.push(atom);
+    }
+    atoms
+}
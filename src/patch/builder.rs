@@ -0,0 +1,150 @@
+use std::fmt;
+
+use crate::patch::{
+    atoms,
+    file::{
+        Canvas, Comment, Connection, Entry, Gui, GuiKind, Message, Object, PatchFile, Subpatch,
+    },
+    BoxId,
+};
+
+/// The horizontal position of the first box.
+const MARGIN_X: i32 = 20;
+/// The vertical position of the first box.
+const MARGIN_Y: i32 = 20;
+/// The vertical distance between the boxes which are placed one after another.
+const ROW_HEIGHT: i32 = 30;
+
+/// Builds the contents of a patch in code, keeping track of the indices and positions of the boxes.
+///
+/// Boxes are placed under each other starting from the top left corner of the canvas,
+/// [`at`](PatchBuilder::at) moves the position of the next box.
+///
+/// # Examples
+/// ```no_run
+/// use libpd_rs::{patch::PatchBuilder, Pd};
+///
+/// let mut builder = PatchBuilder::new();
+/// let oscillator = builder.obj("osc~ 440");
+/// let volume = builder.obj("*~ 0.1");
+/// let dac = builder.at(20, 150).obj("dac~");
+/// builder
+///     .connect(oscillator, 0, volume, 0)
+///     .connect(volume, 0, dac, 0)
+///     .connect(volume, 0, dac, 1);
+///
+/// let pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+/// let _patch = pd.load_patch(&builder).unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct PatchBuilder {
+    file: PatchFile,
+    boxes: usize,
+    x: i32,
+    y: i32,
+}
+
+impl Default for PatchBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PatchBuilder {
+    /// Creates an empty patch.
+    pub fn new() -> Self {
+        Self {
+            file: PatchFile::new(Canvas::new(0, 50, 450, 300)),
+            boxes: 0,
+            x: MARGIN_X,
+            y: MARGIN_Y,
+        }
+    }
+
+    /// Moves the position of the next box, the boxes after it are placed under it.
+    pub const fn at(&mut self, x: i32, y: i32) -> &mut Self {
+        self.x = x;
+        self.y = y;
+        self
+    }
+
+    /// Adds an object box with the text which would be typed into it, like `osc~ 440`.
+    pub fn obj(&mut self, text: &str) -> BoxId {
+        let mut atoms = atoms(text).into_iter();
+        let class = atoms.next().unwrap_or_default();
+        let object = Object::new(self.x, self.y, class, atoms.collect());
+        self.add(Entry::Object(object))
+    }
+
+    /// Adds a message box with the text which would be typed into it, like `1 8, 0 0 10`.
+    pub fn msg(&mut self, text: &str) -> BoxId {
+        let message = Message::new(self.x, self.y, atoms(text));
+        self.add(Entry::Message(message))
+    }
+
+    /// Adds a comment.
+    ///
+    /// Comments don't have inlets or outlets but they take an index like the other boxes.
+    pub fn text(&mut self, text: &str) -> BoxId {
+        let comment = Comment::new(self.x, self.y, atoms(text));
+        self.add(Entry::Comment(comment))
+    }
+
+    /// Adds a GUI object with its properties in the order pd saves them.
+    ///
+    /// Empty properties create the GUI object with its defaults.
+    pub fn gui(&mut self, kind: GuiKind, properties: &str) -> BoxId {
+        let gui = Gui::new(kind, self.x, self.y, atoms(properties));
+        self.add(Entry::Gui(gui))
+    }
+
+    /// Adds a `pd name` subpatch with the contents of another builder.
+    pub fn subpatch(&mut self, name: &str, contents: Self) -> BoxId {
+        let subpatch = Subpatch::new(name, self.x, self.y, contents.file.canvas);
+        self.add(Entry::Subpatch(subpatch))
+    }
+
+    /// Connects an outlet of a box to an inlet of another.
+    pub fn connect(
+        &mut self,
+        source: BoxId,
+        outlet: usize,
+        sink: BoxId,
+        inlet: usize,
+    ) -> &mut Self {
+        let connection = Connection::new(source.0, outlet, sink.0, inlet);
+        self.file.canvas.entries.push(Entry::Connection(connection));
+        self
+    }
+
+    /// Gets the contents of the patch which is built so far.
+    pub const fn file(&self) -> &PatchFile {
+        &self.file
+    }
+
+    fn add(&mut self, entry: Entry) -> BoxId {
+        self.file.canvas.entries.push(entry);
+        self.y += ROW_HEIGHT;
+        let id = BoxId(self.boxes);
+        self.boxes += 1;
+        id
+    }
+}
+
+impl AsRef<PatchFile> for PatchBuilder {
+    fn as_ref(&self) -> &PatchFile {
+        &self.file
+    }
+}
+
+impl From<PatchBuilder> for PatchFile {
+    fn from(builder: PatchBuilder) -> Self {
+        builder.file
+    }
+}
+
+impl fmt::Display for PatchBuilder {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.file.fmt(f)
+    }
+}
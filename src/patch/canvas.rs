@@ -0,0 +1,181 @@
+use std::ffi::CString;
+
+use crate::{
+    atom::{make_t_atom_list_from_atom_list, Atom},
+    error::{PatchLifeCycleError, PdError, StringConversionError},
+    instance::ActiveInstanceGuard,
+    patch::{atoms, BoxId, Patch},
+};
+
+/// The root canvas of an open patch which can be edited while the patch is running.
+///
+/// The changes are made by sending the messages which a pd patch sends to the `pd-<file name>`
+/// receiver of a canvas, they are sent to the canvas of this patch directly so other open copies of
+/// the same file are not edited.
+/// The canvas keeps track of the indices of the boxes which are added to it.
+///
+/// # Examples
+/// ```no_run
+/// use libpd_rs::Pd;
+///
+/// let pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+/// let mut patch = pd.eval_patch("#N canvas 0 50 450 300 12;").unwrap();
+/// let mut canvas = patch.canvas().unwrap();
+///
+/// let oscillator = canvas.add_object(20, 20, "osc~ 440").unwrap();
+/// let dac = canvas.add_object(20, 60, "dac~").unwrap();
+/// canvas.connect(oscillator, 0, dac, 0).unwrap();
+///
+/// // Rewire the oscillator to the other channel.
+/// canvas.disconnect(oscillator, 0, dac, 0).unwrap();
+/// canvas.connect(oscillator, 0, dac, 1).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct Canvas<'patch> {
+    patch: &'patch mut Patch,
+}
+
+impl<'patch> Canvas<'patch> {
+    pub(super) const fn new(patch: &'patch mut Patch) -> Self {
+        Self { patch }
+    }
+
+    /// Gets the number of boxes in the canvas.
+    pub fn box_count(&self) -> usize {
+        self.patch.boxes.unwrap_or_default()
+    }
+
+    /// Adds an object box with the text which would be typed into it, like `osc~ 440`.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`PatchIsNotOpen`](crate::error::PatchLifeCycleError::PatchIsNotOpen)
+    /// - [`StringConversionError`](crate::error::StringConversionError)
+    pub fn add_object(&mut self, x: i32, y: i32, text: &str) -> Result<BoxId, PdError> {
+        self.add("obj", x, y, text)
+    }
+
+    /// Adds a message box with the text which would be typed into it, like `1 8, 0 0 10`.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`PatchIsNotOpen`](crate::error::PatchLifeCycleError::PatchIsNotOpen)
+    /// - [`StringConversionError`](crate::error::StringConversionError)
+    pub fn add_message(&mut self, x: i32, y: i32, text: &str) -> Result<BoxId, PdError> {
+        self.add("msg", x, y, text)
+    }
+
+    /// Connects an outlet of a box to an inlet of another.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`PatchIsNotOpen`](crate::error::PatchLifeCycleError::PatchIsNotOpen)
+    /// - [`StringConversionError`](crate::error::StringConversionError)
+    pub fn connect(
+        &mut self,
+        source: BoxId,
+        outlet: usize,
+        sink: BoxId,
+        inlet: usize,
+    ) -> Result<(), PdError> {
+        self.send("connect", &connection(source, outlet, sink, inlet))
+    }
+
+    /// Removes the connection from an outlet of a box to an inlet of another.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`PatchIsNotOpen`](crate::error::PatchLifeCycleError::PatchIsNotOpen)
+    /// - [`StringConversionError`](crate::error::StringConversionError)
+    pub fn disconnect(
+        &mut self,
+        source: BoxId,
+        outlet: usize,
+        sink: BoxId,
+        inlet: usize,
+    ) -> Result<(), PdError> {
+        self.send("disconnect", &connection(source, outlet, sink, inlet))
+    }
+
+    /// Removes all the boxes from the canvas, the next box takes the index `0`.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`PatchIsNotOpen`](crate::error::PatchLifeCycleError::PatchIsNotOpen)
+    /// - [`StringConversionError`](crate::error::StringConversionError)
+    pub fn clear(&mut self) -> Result<(), PdError> {
+        self.send("clear", &[])?;
+        self.patch.boxes = Some(0);
+        Ok(())
+    }
+
+    /// Saves the canvas to the file which the patch is opened from.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`PatchIsNotOpen`](crate::error::PatchLifeCycleError::PatchIsNotOpen)
+    /// - [`StringConversionError`](crate::error::StringConversionError)
+    pub fn save(&mut self) -> Result<(), PdError> {
+        self.send("menusave", &[])
+    }
+
+    fn add(&mut self, kind: &str, x: i32, y: i32, text: &str) -> Result<BoxId, PdError> {
+        let mut list = vec![Atom::Float(x.into()), Atom::Float(y.into())];
+        list.extend(atoms(text).into_iter().map(|atom| {
+            atom.parse()
+                .map_or_else(|_| Atom::Symbol(atom), Atom::Float)
+        }));
+        self.send(kind, &list)?;
+
+        let boxes = self.patch.boxes.get_or_insert(0);
+        let id = BoxId(*boxes);
+        *boxes += 1;
+        Ok(id)
+    }
+
+    fn send(&self, message: &str, list: &[Atom]) -> Result<(), PdError> {
+        let handle = self
+            .patch
+            .handle
+            .as_ref()
+            .filter(|_| self.patch.is_open())
+            .ok_or(PatchLifeCycleError::PatchIsNotOpen)?;
+        let message = CString::new(message).map_err(StringConversionError::from)?;
+        let mut atoms = make_t_atom_list_from_atom_list(list)?;
+        #[expect(
+            clippy::cast_possible_wrap,
+            clippy::cast_possible_truncation,
+            reason = "The messages to a canvas have a handful of atoms."
+        )]
+        let count = atoms.len() as i32;
+
+        let _guard = ActiveInstanceGuard::activate(self.patch.instance);
+        unsafe { libpd_sys::sys_lock() };
+        let selector = unsafe { libpd_sys::gensym(message.as_ptr()) };
+        // The handle of a patch is its root canvas.
+        unsafe {
+            libpd_sys::pd_typedmess(
+                handle.as_mut_ptr().cast(),
+                selector,
+                count,
+                atoms.as_mut_ptr(),
+            );
+        };
+        unsafe { libpd_sys::sys_unlock() };
+        Ok(())
+    }
+}
+
+#[expect(
+    clippy::cast_precision_loss,
+    reason = "Indices of boxes and their inlets and outlets are far below the precision of the float."
+)]
+fn connection(source: BoxId, outlet: usize, sink: BoxId, inlet: usize) -> [Atom; 4] {
+    [source.0, outlet, sink.0, inlet].map(|index| Atom::Float(index as f64))
+}
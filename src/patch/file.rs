@@ -0,0 +1,1136 @@
+use std::{fmt, iter, mem, str::FromStr, vec};
+
+use crate::error::PatchFileError;
+
+/// The contents of a pd file.
+///
+/// Parsing and writing the contents back reproduces the original text byte for byte,
+/// the formatting of every record is remembered as long as it is not edited.
+/// Edited or newly created records are written in the way pd saves them.
+///
+/// Records which are not represented by a typed entry are kept as [`Record`]s.
+///
+/// # Examples
+/// ```rust
+/// use libpd_rs::patch::file::{Entry, PatchFile};
+///
+/// let contents = "#N canvas 0 50 450 300 12;\n#X obj 20 20 osc~ 440;\n";
+/// let mut patch: PatchFile = contents.parse().unwrap();
+/// assert_eq!(patch.to_string(), contents);
+///
+/// if let Some(Entry::Object(oscillator)) = patch.canvas.entries.first_mut() {
+///     assert_eq!(oscillator.class, "osc~");
+///     oscillator.args = vec!["220".to_owned()];
+/// }
+/// assert_eq!(
+///     patch.to_string(),
+///     "#N canvas 0 50 450 300 12;\n#X obj 20 20 osc~ 220;\n"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct PatchFile {
+    /// Records which come before the root canvas, like `#N struct` declarations.
+    pub header: Vec<Record>,
+    /// The font size of the patch.
+    pub font: i32,
+    /// The root canvas of the patch.
+    pub canvas: Canvas,
+}
+
+impl PatchFile {
+    /// Creates the contents of a pd file with the given root canvas and the default font size.
+    pub const fn new(canvas: Canvas) -> Self {
+        Self {
+            header: Vec::new(),
+            font: 12,
+            canvas,
+        }
+    }
+}
+
+impl AsRef<Self> for PatchFile {
+    fn as_ref(&self) -> &Self {
+        self
+    }
+}
+
+impl FromStr for PatchFile {
+    type Err = PatchFileError;
+
+    fn from_str(contents: &str) -> Result<Self, Self::Err> {
+        let mut records = lex(contents)?.into_iter().peekable();
+        let mut header = vec![];
+        let root = loop {
+            let Some(record) = records.next() else {
+                return Err(PatchFileError::MissingCanvas);
+            };
+            if record_kind(&record.tokens) == ("#N", "canvas") {
+                break record;
+            }
+            header.push(Record::from_lexed(record));
+        };
+
+        let (mut canvas, tail) = Canvas::parse(&root)?;
+        let font = match tail.as_slice() {
+            [font] => font.parse().ok(),
+            _ => None,
+        }
+        .ok_or_else(|| PatchFileError::InvalidRecord(root.text()))?;
+        if let Some(restore) = canvas.parse_entries(&mut records)? {
+            return Err(PatchFileError::UnexpectedRestore(restore.text()));
+        }
+
+        let patch = Self {
+            header,
+            font,
+            canvas,
+        };
+        if patch.canvas.header_tokens(&[patch.font.to_string()]) != root.tokens {
+            return Err(PatchFileError::InvalidRecord(root.text()));
+        }
+        Ok(patch)
+    }
+}
+
+impl fmt::Display for PatchFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for record in &self.header {
+            write_record(f, &record.tokens, &record.layout)?;
+        }
+        self.canvas.write(f, &[self.font.to_string()])
+    }
+}
+
+/// A canvas which holds the entries of a patch or a subpatch.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Canvas {
+    /// The horizontal position of the window of the canvas.
+    pub x: i32,
+    /// The vertical position of the window of the canvas.
+    pub y: i32,
+    /// The width of the window of the canvas.
+    pub width: i32,
+    /// The height of the window of the canvas.
+    pub height: i32,
+    /// The entries of the canvas in the order they are saved.
+    pub entries: Vec<Entry>,
+    layout: Layout,
+}
+
+impl Canvas {
+    /// Creates an empty canvas with the given window position and size.
+    pub fn new(x: i32, y: i32, width: i32, height: i32) -> Self {
+        Self {
+            x,
+            y,
+            width,
+            height,
+            entries: Vec::new(),
+            layout: Layout::default(),
+        }
+    }
+
+    /// Parses the `#N canvas` record and returns the values which come after the window size.
+    fn parse(record: &Lexed) -> Result<(Self, Vec<String>), PatchFileError> {
+        let invalid = || PatchFileError::InvalidRecord(record.text());
+        let mut values = record.tokens.iter().skip(2);
+        let mut window = [0; 4];
+        for value in &mut window {
+            *value = values
+                .next()
+                .and_then(|token| token.parse().ok())
+                .ok_or_else(invalid)?;
+        }
+        let [x, y, width, height] = window;
+        let canvas = Self {
+            x,
+            y,
+            width,
+            height,
+            entries: Vec::new(),
+            layout: record.layout.clone(),
+        };
+        Ok((canvas, values.cloned().collect()))
+    }
+
+    /// Parses the entries until the end of the canvas and returns the `#X restore` record which ends it.
+    fn parse_entries(
+        &mut self,
+        records: &mut iter::Peekable<vec::IntoIter<Lexed>>,
+    ) -> Result<Option<Lexed>, PatchFileError> {
+        while let Some(record) = records.next() {
+            let entry = match record_kind(&record.tokens) {
+                ("#N", "canvas") => Entry::Subpatch(Subpatch::parse(&record, records)?),
+                ("#X", "restore") => return Ok(Some(record)),
+                ("#X", "array") => match verified(Array::parse(&record), &record) {
+                    Some(mut array) => {
+                        while let Some(data) = records
+                            .peek()
+                            .and_then(|next| verified(ArrayData::parse(next), next))
+                        {
+                            array.data.push(data);
+                            records.next();
+                        }
+                        Entry::Array(array)
+                    }
+                    None => Entry::Record(Record::from_lexed(record)),
+                },
+                _ => Entry::parse(record),
+            };
+            self.entries.push(entry);
+        }
+        Ok(None)
+    }
+
+    fn header_tokens(&self, tail: &[String]) -> Vec<String> {
+        let mut tokens = vec![
+            "#N".to_owned(),
+            "canvas".to_owned(),
+            self.x.to_string(),
+            self.y.to_string(),
+            self.width.to_string(),
+            self.height.to_string(),
+        ];
+        tokens.extend_from_slice(tail);
+        tokens
+    }
+
+    fn write(&self, f: &mut fmt::Formatter<'_>, tail: &[String]) -> fmt::Result {
+        write_record(f, &self.header_tokens(tail), &self.layout)?;
+        for entry in &self.entries {
+            entry.write(f)?;
+        }
+        Ok(())
+    }
+}
+
+/// An entry of a canvas.
+///
+/// Objects, messages, comments, GUI objects, subpatches and arrays are counted in the order they appear
+/// to find the indices which are used in [`Connection`]s.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Entry {
+    /// An object box, `#X obj`.
+    Object(Object),
+    /// A message box, `#X msg`.
+    Message(Message),
+    /// A comment, `#X text`.
+    Comment(Comment),
+    /// A GUI object, either an IEM GUI object or an atom box.
+    Gui(Gui),
+    /// A subpatch or a graph with its canvas, from `#N canvas` to `#X restore`.
+    Subpatch(Subpatch),
+    /// An array with its saved contents, `#X array` followed by `#A` records.
+    Array(Array),
+    /// A connection between two entries, `#X connect`.
+    Connection(Connection),
+    /// Any other record, like `#X coords` or `#X declare`.
+    Record(Record),
+}
+
+impl Entry {
+    /// Whether the entry is a box which takes an index in its canvas.
+    pub fn is_box(&self) -> bool {
+        match self {
+            Self::Connection(_) => false,
+            Self::Record(record) => record_kind(&record.tokens) == ("#X", "scalar"),
+            _ => true,
+        }
+    }
+
+    fn parse(record: Lexed) -> Self {
+        let entry = match record_kind(&record.tokens) {
+            ("#X", "obj") => verified(Gui::parse(&record), &record)
+                .map(Self::Gui)
+                .or_else(|| verified(Object::parse(&record), &record).map(Self::Object)),
+            ("#X", "msg") => verified(Message::parse(&record), &record).map(Self::Message),
+            ("#X", "text") => verified(Comment::parse(&record), &record).map(Self::Comment),
+            ("#X", "connect") => {
+                verified(Connection::parse(&record), &record).map(Self::Connection)
+            }
+            ("#X", _) => verified(Gui::parse(&record), &record).map(Self::Gui),
+            _ => None,
+        };
+        entry.unwrap_or_else(|| Self::Record(Record::from_lexed(record)))
+    }
+
+    fn write(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Object(object) => write_record(f, &object.tokens(), &object.layout),
+            Self::Message(message) => write_record(f, &message.tokens(), &message.layout),
+            Self::Comment(comment) => write_record(f, &comment.tokens(), &comment.layout),
+            Self::Gui(gui) => write_record(f, &gui.tokens(), &gui.layout),
+            Self::Subpatch(subpatch) => subpatch.write(f),
+            Self::Array(array) => array.write(f),
+            Self::Connection(connection) => {
+                write_record(f, &connection.tokens(), &connection.layout)
+            }
+            Self::Record(record) => write_record(f, &record.tokens, &record.layout),
+        }
+    }
+}
+
+/// An object box.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Object {
+    /// The horizontal position of the object.
+    pub x: i32,
+    /// The vertical position of the object.
+    pub y: i32,
+    /// The class of the object, like `osc~`, empty for an empty object box.
+    pub class: String,
+    /// The creation arguments of the object.
+    pub args: Vec<String>,
+    /// The width of the box in characters if it is set.
+    pub width: Option<u32>,
+    layout: Layout,
+}
+
+impl Object {
+    /// Creates an object box at the given position.
+    pub fn new<T: Into<String>>(x: i32, y: i32, class: T, args: Vec<String>) -> Self {
+        Self {
+            x,
+            y,
+            class: class.into(),
+            args,
+            width: None,
+            layout: Layout::default(),
+        }
+    }
+
+    fn parse(record: &Lexed) -> Option<Self> {
+        let fields = BoxFields::parse(&record.tokens)?;
+        let mut content = fields.content.into_iter();
+        Some(Self {
+            x: fields.x,
+            y: fields.y,
+            class: content.next().unwrap_or_default(),
+            args: content.collect(),
+            width: fields.width,
+            layout: record.layout.clone(),
+        })
+    }
+}
+
+impl Tokens for Object {
+    fn tokens(&self) -> Vec<String> {
+        box_tokens(
+            "obj",
+            self.x,
+            self.y,
+            iter::once(&self.class)
+                .filter(|class| !class.is_empty())
+                .chain(&self.args),
+            self.width,
+        )
+    }
+}
+
+/// A message box.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Message {
+    /// The horizontal position of the message.
+    pub x: i32,
+    /// The vertical position of the message.
+    pub y: i32,
+    /// The atoms of the message, commas and semicolons are separate atoms.
+    pub content: Vec<String>,
+    /// The width of the box in characters if it is set.
+    pub width: Option<u32>,
+    layout: Layout,
+}
+
+impl Message {
+    /// Creates a message box at the given position.
+    pub fn new(x: i32, y: i32, content: Vec<String>) -> Self {
+        Self {
+            x,
+            y,
+            content,
+            width: None,
+            layout: Layout::default(),
+        }
+    }
+
+    fn parse(record: &Lexed) -> Option<Self> {
+        let fields = BoxFields::parse(&record.tokens)?;
+        Some(Self {
+            x: fields.x,
+            y: fields.y,
+            content: fields.content,
+            width: fields.width,
+            layout: record.layout.clone(),
+        })
+    }
+}
+
+impl Tokens for Message {
+    fn tokens(&self) -> Vec<String> {
+        box_tokens("msg", self.x, self.y, &self.content, self.width)
+    }
+}
+
+/// A comment.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comment {
+    /// The horizontal position of the comment.
+    pub x: i32,
+    /// The vertical position of the comment.
+    pub y: i32,
+    /// The words of the comment, commas and semicolons are separate words.
+    pub content: Vec<String>,
+    /// The width of the comment in characters if it is set.
+    pub width: Option<u32>,
+    layout: Layout,
+}
+
+impl Comment {
+    /// Creates a comment at the given position.
+    pub fn new(x: i32, y: i32, content: Vec<String>) -> Self {
+        Self {
+            x,
+            y,
+            content,
+            width: None,
+            layout: Layout::default(),
+        }
+    }
+
+    fn parse(record: &Lexed) -> Option<Self> {
+        let fields = BoxFields::parse(&record.tokens)?;
+        Some(Self {
+            x: fields.x,
+            y: fields.y,
+            content: fields.content,
+            width: fields.width,
+            layout: record.layout.clone(),
+        })
+    }
+}
+
+impl Tokens for Comment {
+    fn tokens(&self) -> Vec<String> {
+        box_tokens("text", self.x, self.y, &self.content, self.width)
+    }
+}
+
+/// The kinds of GUI objects.
+#[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GuiKind {
+    /// `bng`
+    Bang,
+    /// `tgl`
+    Toggle,
+    /// `nbx`
+    NumberBox,
+    /// `hsl`
+    HorizontalSlider,
+    /// `vsl`
+    VerticalSlider,
+    /// `hradio`
+    HorizontalRadio,
+    /// `vradio`
+    VerticalRadio,
+    /// `vu`
+    VuMeter,
+    /// `cnv`
+    Canvas,
+    /// `floatatom`
+    FloatAtom,
+    /// `symbolatom`
+    SymbolAtom,
+    /// `listbox`
+    ListBox,
+}
+
+impl GuiKind {
+    const ALL: [Self; 12] = [
+        Self::Bang,
+        Self::Toggle,
+        Self::NumberBox,
+        Self::HorizontalSlider,
+        Self::VerticalSlider,
+        Self::HorizontalRadio,
+        Self::VerticalRadio,
+        Self::VuMeter,
+        Self::Canvas,
+        Self::FloatAtom,
+        Self::SymbolAtom,
+        Self::ListBox,
+    ];
+
+    /// The name which the GUI object is saved with.
+    pub const fn name(self) -> &'static str {
+        match self {
+            Self::Bang => "bng",
+            Self::Toggle => "tgl",
+            Self::NumberBox => "nbx",
+            Self::HorizontalSlider => "hsl",
+            Self::VerticalSlider => "vsl",
+            Self::HorizontalRadio => "hradio",
+            Self::VerticalRadio => "vradio",
+            Self::VuMeter => "vu",
+            Self::Canvas => "cnv",
+            Self::FloatAtom => "floatatom",
+            Self::SymbolAtom => "symbolatom",
+            Self::ListBox => "listbox",
+        }
+    }
+
+    /// Whether this is an atom box which is saved as its own record instead of an object.
+    pub const fn is_atom_box(self) -> bool {
+        matches!(self, Self::FloatAtom | Self::SymbolAtom | Self::ListBox)
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.name() == name)
+    }
+}
+
+/// A GUI object.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Gui {
+    /// The kind of the GUI object.
+    pub kind: GuiKind,
+    /// The horizontal position of the GUI object.
+    pub x: i32,
+    /// The vertical position of the GUI object.
+    pub y: i32,
+    /// The properties of the GUI object in the order they are saved.
+    pub args: Vec<String>,
+    /// The width of the box in characters if it is set.
+    pub width: Option<u32>,
+    layout: Layout,
+}
+
+impl Gui {
+    /// Creates a GUI object at the given position.
+    pub fn new(kind: GuiKind, x: i32, y: i32, args: Vec<String>) -> Self {
+        Self {
+            kind,
+            x,
+            y,
+            args,
+            width: None,
+            layout: Layout::default(),
+        }
+    }
+
+    fn parse(record: &Lexed) -> Option<Self> {
+        let fields = BoxFields::parse(&record.tokens)?;
+        let mut content = fields.content.into_iter();
+        let kind = match record.tokens.get(1).map(String::as_str) {
+            Some("obj") => GuiKind::from_name(&content.next()?).filter(|kind| !kind.is_atom_box()),
+            Some(name) => GuiKind::from_name(name).filter(|kind| kind.is_atom_box()),
+            None => None,
+        }?;
+        Some(Self {
+            kind,
+            x: fields.x,
+            y: fields.y,
+            args: content.collect(),
+            width: fields.width,
+            layout: record.layout.clone(),
+        })
+    }
+}
+
+impl Tokens for Gui {
+    fn tokens(&self) -> Vec<String> {
+        let name = self.kind.name().to_owned();
+        if self.kind.is_atom_box() {
+            box_tokens(&name, self.x, self.y, &self.args, self.width)
+        } else {
+            box_tokens(
+                "obj",
+                self.x,
+                self.y,
+                iter::once(&name).chain(&self.args),
+                self.width,
+            )
+        }
+    }
+}
+
+/// A subpatch or a graph.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Subpatch {
+    /// The name of the canvas.
+    pub name: String,
+    /// Whether the window of the canvas is open when the patch is loaded.
+    pub open: bool,
+    /// The canvas of the subpatch.
+    pub canvas: Canvas,
+    /// The horizontal position of the subpatch in its parent.
+    pub x: i32,
+    /// The vertical position of the subpatch in its parent.
+    pub y: i32,
+    /// The text of the box in its parent, like `pd name` or `graph`.
+    pub content: Vec<String>,
+    /// The width of the box in characters if it is set.
+    pub width: Option<u32>,
+    restore_layout: Layout,
+}
+
+impl Subpatch {
+    /// Creates a `pd name` subpatch at the given position with the given canvas.
+    pub fn new<T: Into<String>>(name: T, x: i32, y: i32, canvas: Canvas) -> Self {
+        let name = name.into();
+        Self {
+            content: vec!["pd".to_owned(), name.clone()],
+            name,
+            open: false,
+            canvas,
+            x,
+            y,
+            width: None,
+            restore_layout: Layout::default(),
+        }
+    }
+
+    fn parse(
+        header: &Lexed,
+        records: &mut iter::Peekable<vec::IntoIter<Lexed>>,
+    ) -> Result<Self, PatchFileError> {
+        let invalid = || PatchFileError::InvalidRecord(header.text());
+        let (mut canvas, tail) = Canvas::parse(header)?;
+        let (name, open) = match tail.as_slice() {
+            [name, open] => (unescape(name), open == "1"),
+            _ => return Err(invalid()),
+        };
+        let restore = canvas
+            .parse_entries(records)?
+            .ok_or_else(|| PatchFileError::UnclosedSubpatch(name.clone()))?;
+        let fields = BoxFields::parse(&restore.tokens)
+            .ok_or_else(|| PatchFileError::InvalidRecord(restore.text()))?;
+
+        let subpatch = Self {
+            name,
+            open,
+            canvas,
+            x: fields.x,
+            y: fields.y,
+            content: fields.content,
+            width: fields.width,
+            restore_layout: restore.layout.clone(),
+        };
+        if subpatch.canvas.header_tokens(&subpatch.header_tail()) != header.tokens {
+            return Err(invalid());
+        }
+        if subpatch.tokens() != restore.tokens {
+            return Err(PatchFileError::InvalidRecord(restore.text()));
+        }
+        Ok(subpatch)
+    }
+
+    fn header_tail(&self) -> [String; 2] {
+        [
+            escape(&self.name),
+            if self.open { "1" } else { "0" }.to_owned(),
+        ]
+    }
+
+    fn write(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.canvas.write(f, &self.header_tail())?;
+        write_record(f, &self.tokens(), &self.restore_layout)
+    }
+}
+
+impl Tokens for Subpatch {
+    /// The tokens of the `#X restore` record.
+    fn tokens(&self) -> Vec<String> {
+        box_tokens("restore", self.x, self.y, &self.content, self.width)
+    }
+}
+
+/// An array which is drawn in a graph.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Array {
+    /// The name of the array.
+    pub name: String,
+    /// The number of elements in the array.
+    pub size: usize,
+    /// The type of the elements, `float` for the arrays which are created from the editor.
+    pub element_type: String,
+    /// The flags of the array, the first bit is set when the contents are saved with the patch
+    /// and the next two bits select the plot style.
+    pub flags: i32,
+    /// The saved contents of the array.
+    pub data: Vec<ArrayData>,
+    layout: Layout,
+}
+
+impl Array {
+    /// Creates an array of floats which saves its contents and is plotted as a polygon.
+    pub fn new<T: Into<String>>(name: T, size: usize) -> Self {
+        Self {
+            name: name.into(),
+            size,
+            element_type: "float".to_owned(),
+            flags: 3,
+            data: Vec::new(),
+            layout: Layout::default(),
+        }
+    }
+
+    fn parse(record: &Lexed) -> Option<Self> {
+        let mut tokens = record.tokens.iter().skip(2);
+        let name = unescape(tokens.next()?);
+        let size = tokens.next()?.parse().ok()?;
+        let element_type = unescape(tokens.next()?);
+        let flags = tokens.next()?.parse().ok()?;
+        Some(Self {
+            name,
+            size,
+            element_type,
+            flags,
+            data: Vec::new(),
+            layout: record.layout.clone(),
+        })
+        .filter(|_| tokens.next().is_none())
+    }
+
+    fn write(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write_record(f, &self.tokens(), &self.layout)?;
+        for data in &self.data {
+            write_record(f, &data.tokens(), &data.layout)?;
+        }
+        Ok(())
+    }
+}
+
+impl Tokens for Array {
+    /// The tokens of the `#X array` record.
+    fn tokens(&self) -> Vec<String> {
+        vec![
+            "#X".to_owned(),
+            "array".to_owned(),
+            escape(&self.name),
+            self.size.to_string(),
+            escape(&self.element_type),
+            self.flags.to_string(),
+        ]
+    }
+}
+
+/// A part of the saved contents of an array, `#A`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArrayData {
+    /// The index of the first value in the array.
+    pub start: usize,
+    /// The values starting from the index.
+    pub values: Vec<f64>,
+    /// The values as they are written in the file, to write unchanged values the same way.
+    texts: Vec<String>,
+    layout: Layout,
+}
+
+impl ArrayData {
+    /// Creates a part of the contents of an array starting from the given index.
+    pub fn new(start: usize, values: Vec<f64>) -> Self {
+        Self {
+            start,
+            values,
+            texts: Vec::new(),
+            layout: Layout::default(),
+        }
+    }
+
+    fn parse(record: &Lexed) -> Option<Self> {
+        if record.tokens.first().map(String::as_str) != Some("#A") {
+            return None;
+        }
+        let mut tokens = record.tokens.iter().skip(1);
+        let start = tokens.next()?.parse().ok()?;
+        let texts: Vec<String> = tokens.cloned().collect();
+        let values = texts
+            .iter()
+            .map(|text| text.parse().ok())
+            .collect::<Option<_>>()?;
+        Some(Self {
+            start,
+            values,
+            texts,
+            layout: record.layout.clone(),
+        })
+    }
+}
+
+impl Tokens for ArrayData {
+    fn tokens(&self) -> Vec<String> {
+        let mut tokens = vec!["#A".to_owned(), self.start.to_string()];
+        tokens.extend(self.values.iter().enumerate().map(|(index, value)| {
+            self.texts
+                .get(index)
+                .filter(|text| {
+                    text.parse::<f64>()
+                        .is_ok_and(|parsed| parsed.to_bits() == value.to_bits())
+                })
+                .map_or_else(|| value.to_string(), Clone::clone)
+        }));
+        tokens
+    }
+}
+
+/// A connection from an outlet of an entry to an inlet of another.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Connection {
+    /// The index of the entry which the connection starts from.
+    pub source: usize,
+    /// The outlet of the source.
+    pub outlet: usize,
+    /// The index of the entry which the connection ends at.
+    pub sink: usize,
+    /// The inlet of the sink.
+    pub inlet: usize,
+    layout: Layout,
+}
+
+impl Connection {
+    /// Creates a connection from an outlet of an entry to an inlet of another.
+    pub fn new(source: usize, outlet: usize, sink: usize, inlet: usize) -> Self {
+        Self {
+            source,
+            outlet,
+            sink,
+            inlet,
+            layout: Layout::default(),
+        }
+    }
+
+    fn parse(record: &Lexed) -> Option<Self> {
+        let mut values = record.tokens.iter().skip(2).map(|token| token.parse().ok());
+        let mut next = || values.next().flatten();
+        Some(Self {
+            source: next()?,
+            outlet: next()?,
+            sink: next()?,
+            inlet: next()?,
+            layout: record.layout.clone(),
+        })
+    }
+}
+
+impl Tokens for Connection {
+    fn tokens(&self) -> Vec<String> {
+        vec![
+            "#X".to_owned(),
+            "connect".to_owned(),
+            self.source.to_string(),
+            self.outlet.to_string(),
+            self.sink.to_string(),
+            self.inlet.to_string(),
+        ]
+    }
+}
+
+/// A record which is kept as it is.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Record {
+    /// The atoms of the record as they are written in the file, escapes included.
+    pub tokens: Vec<String>,
+    layout: Layout,
+}
+
+impl Record {
+    /// Creates a record from atoms which are written as they are.
+    pub fn new(tokens: Vec<String>) -> Self {
+        Self {
+            tokens,
+            layout: Layout::default(),
+        }
+    }
+
+    fn from_lexed(record: Lexed) -> Self {
+        Self {
+            tokens: record.tokens,
+            layout: record.layout,
+        }
+    }
+}
+
+/// The tokens which an entry is written with.
+trait Tokens {
+    fn tokens(&self) -> Vec<String>;
+}
+
+/// Keeps a parsed entry only if it is written back the same way.
+fn verified<T: Tokens>(entry: Option<T>, record: &Lexed) -> Option<T> {
+    entry.filter(|entry| entry.tokens() == record.tokens)
+}
+
+/// The whitespace around the atoms of a record.
+///
+/// It doesn't take part in comparisons, two entries with the same contents are equal however they are formatted.
+#[derive(Debug, Clone)]
+struct Layout {
+    leading: String,
+    separators: Vec<String>,
+    before_end: String,
+    trailing: String,
+}
+
+impl Default for Layout {
+    fn default() -> Self {
+        Self {
+            leading: String::new(),
+            separators: Vec::new(),
+            before_end: String::new(),
+            trailing: "\n".to_owned(),
+        }
+    }
+}
+
+impl PartialEq for Layout {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+/// A record as it is read from the file.
+struct Lexed {
+    tokens: Vec<String>,
+    layout: Layout,
+}
+
+impl Lexed {
+    fn text(&self) -> String {
+        self.tokens.join(" ")
+    }
+}
+
+/// The fields which boxes share, `#X <kind> <x> <y> <content>... [, f <width>]`.
+struct BoxFields {
+    x: i32,
+    y: i32,
+    content: Vec<String>,
+    width: Option<u32>,
+}
+
+impl BoxFields {
+    fn parse(tokens: &[String]) -> Option<Self> {
+        let mut rest = tokens.iter().skip(2);
+        let x = rest.next()?.parse().ok()?;
+        let y = rest.next()?.parse().ok()?;
+        let mut content: Vec<&String> = rest.collect();
+        let width = match content.as_slice() {
+            [.., comma, f, width] if *comma == "," && *f == "f" => width.parse().ok(),
+            _ => None,
+        };
+        if width.is_some() {
+            content.truncate(content.len() - 3);
+        }
+        Some(Self {
+            x,
+            y,
+            content: content.into_iter().map(|token| unescape(token)).collect(),
+            width,
+        })
+    }
+}
+
+fn box_tokens<'content, I>(
+    kind: &str,
+    x: i32,
+    y: i32,
+    content: I,
+    width: Option<u32>,
+) -> Vec<String>
+where
+    I: IntoIterator<Item = &'content String>,
+{
+    let mut tokens = vec![
+        "#X".to_owned(),
+        kind.to_owned(),
+        x.to_string(),
+        y.to_string(),
+    ];
+    tokens.extend(content.into_iter().map(|atom| escape(atom)));
+    if let Some(width) = width {
+        tokens.extend([",".to_owned(), "f".to_owned(), width.to_string()]);
+    }
+    tokens
+}
+
+fn record_kind(tokens: &[String]) -> (&str, &str) {
+    let mut kind = tokens.iter().map(String::as_str);
+    (
+        kind.next().unwrap_or_default(),
+        kind.next().unwrap_or_default(),
+    )
+}
+
+/// Escapes an atom the way pd saves it.
+fn escape(atom: &str) -> String {
+    let mut escaped = String::with_capacity(atom.len());
+    let mut characters = atom.chars().peekable();
+    while let Some(character) = characters.next() {
+        let dollar = character == '$' && characters.peek().is_some_and(char::is_ascii_digit);
+        if dollar || matches!(character, ';' | ',' | '\\') || character.is_whitespace() {
+            escaped.push('\\');
+        }
+        escaped.push(character);
+    }
+    escaped
+}
+
+fn unescape(token: &str) -> String {
+    let mut atom = String::with_capacity(token.len());
+    let mut characters = token.chars();
+    while let Some(character) = characters.next() {
+        if character == '\\' {
+            if let Some(escaped) = characters.next() {
+                atom.push(escaped);
+                continue;
+            }
+        }
+        atom.push(character);
+    }
+    atom
+}
+
+/// Splits the contents into records, remembering the whitespace around the atoms.
+fn lex(contents: &str) -> Result<Vec<Lexed>, PatchFileError> {
+    let mut lexer = Lexer::default();
+    let mut escaped = false;
+    for character in contents.chars() {
+        if escaped {
+            lexer.push(character);
+            escaped = false;
+            continue;
+        }
+        match character {
+            '\\' => {
+                lexer.push(character);
+                escaped = true;
+            }
+            ';' => lexer.end_record(),
+            ',' => {
+                lexer.end_token();
+                lexer.push(character);
+                lexer.end_token();
+            }
+            _ if character.is_whitespace() => {
+                lexer.end_token();
+                lexer.whitespace.push(character);
+            }
+            _ => lexer.push(character),
+        }
+    }
+    lexer.finish()
+}
+
+#[derive(Default)]
+struct Lexer {
+    records: Vec<Lexed>,
+    tokens: Vec<String>,
+    layout: Layout,
+    token: Option<String>,
+    whitespace: String,
+    /// Whether the last record is ended and the whitespace after it is being read.
+    ended: bool,
+}
+
+impl Lexer {
+    fn push(&mut self, character: char) {
+        if self.token.is_none() {
+            if self.ended {
+                self.finish_record();
+            }
+            let whitespace = mem::take(&mut self.whitespace);
+            if self.tokens.is_empty() {
+                self.layout.leading = whitespace;
+            } else {
+                self.layout.separators.push(whitespace);
+            }
+        }
+        self.token.get_or_insert_with(String::new).push(character);
+    }
+
+    fn end_token(&mut self) {
+        if let Some(token) = self.token.take() {
+            self.tokens.push(token);
+        }
+    }
+
+    fn end_record(&mut self) {
+        self.end_token();
+        if self.ended {
+            self.finish_record();
+        }
+        self.layout.before_end = mem::take(&mut self.whitespace);
+        self.ended = true;
+    }
+
+    fn finish_record(&mut self) {
+        self.layout.trailing = mem::take(&mut self.whitespace);
+        self.records.push(Lexed {
+            tokens: mem::take(&mut self.tokens),
+            layout: mem::take(&mut self.layout),
+        });
+        self.ended = false;
+    }
+
+    fn finish(mut self) -> Result<Vec<Lexed>, PatchFileError> {
+        self.end_token();
+        if !self.ended && !self.tokens.is_empty() {
+            return Err(PatchFileError::UnterminatedRecord(self.tokens.join(" ")));
+        }
+        if self.ended {
+            self.finish_record();
+        }
+        Ok(self.records)
+    }
+}
+
+/// Writes a record with its layout, or the way pd saves it if the atoms don't fit the layout anymore.
+fn write_record(f: &mut fmt::Formatter<'_>, tokens: &[String], layout: &Layout) -> fmt::Result {
+    let wrapped;
+    let (separators, before_end) = if layout.separators.len() + 1 == tokens.len()
+        || (tokens.is_empty() && layout.separators.is_empty())
+    {
+        (layout.separators.as_slice(), layout.before_end.as_str())
+    } else {
+        wrapped = wrap(tokens);
+        (wrapped.0.as_slice(), wrapped.1)
+    };
+
+    f.write_str(&layout.leading)?;
+    let separators = iter::once("").chain(separators.iter().map(String::as_str));
+    for (separator, token) in separators.zip(tokens) {
+        f.write_str(separator)?;
+        f.write_str(token)?;
+    }
+    f.write_str(before_end)?;
+    f.write_str(";")?;
+    f.write_str(&layout.trailing)
+}
+
+/// Separates the atoms with spaces and breaks the lines which are longer than 65 characters like pd does.
+fn wrap(tokens: &[String]) -> (Vec<String>, &'static str) {
+    let mut separators = Vec::with_capacity(tokens.len());
+    let mut column = 0;
+    let mut next = "";
+    for (index, token) in tokens.iter().enumerate() {
+        if index > 0 {
+            // Commas stick to the atom before them.
+            let separator = if token == "," && next == " " {
+                ""
+            } else {
+                next
+            };
+            separators.push(separator.to_owned());
+        }
+        column += token.len();
+        next = if column > 65 {
+            column = 0;
+            "\n"
+        } else {
+            column += 1;
+            " "
+        };
+    }
+    (separators, if next == "\n" { next } else { "" })
+}
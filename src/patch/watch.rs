@@ -0,0 +1,120 @@
+use std::{
+    fs, iter, mem,
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use crate::{
+    error::PdError,
+    functions,
+    patch::{
+        file::{Canvas, Entry, PatchFile},
+        Patch,
+    },
+};
+
+/// A patch which is reopened when its file or the files of the abstractions it uses change.
+#[derive(Debug)]
+pub struct Watcher {
+    path: PathBuf,
+    patch: Patch,
+    /// The files of the patch and its abstractions with the times they were last modified.
+    files: Vec<(PathBuf, Option<SystemTime>)>,
+}
+
+impl Watcher {
+    /// Opens the patch in the current instance and starts watching it.
+    pub fn new(path: &Path, search_paths: &[PathBuf]) -> Result<Self, PdError> {
+        let resolved = functions::resolve_patch_path(path)?;
+        let files = watched_files(&resolved, search_paths);
+        Ok(Self {
+            path: path.to_path_buf(),
+            patch: Patch::open(path, None)?,
+            files,
+        })
+    }
+
+    /// The path which the patch is watched with.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The patch which is open currently.
+    pub const fn patch(&self) -> &Patch {
+        &self.patch
+    }
+
+    /// Gives up watching and returns the patch which is open.
+    pub fn into_patch(self) -> Patch {
+        self.patch
+    }
+
+    /// Whether any of the watched files are modified, created or removed since they were last checked.
+    pub fn is_changed(&self) -> bool {
+        self.files
+            .iter()
+            .any(|(file, modified)| last_modified(file) != *modified)
+    }
+
+    /// Opens the patch again in the current instance and closes the previous one if it opens.
+    ///
+    /// The previous patch keeps running when the file is broken, so both are open while the new one loads.
+    /// Meanwhile the arrays of the patch are defined twice and messages which are sent to its receivers,
+    /// like the ones which the `[loadbang]`s of the new patch trigger, reach both.
+    ///
+    /// The files are watched again from their current state whether the patch opens or not,
+    /// so a broken file is not tried again until it changes.
+    pub fn reload(&mut self, search_paths: &[PathBuf]) -> Result<(), PdError> {
+        let resolved = functions::resolve_patch_path(&self.path);
+        self.files = watched_files(resolved.as_deref().unwrap_or(&self.path), search_paths);
+        resolved?;
+        let previous = mem::replace(&mut self.patch, Patch::open(&self.path, None)?);
+        previous.close()
+    }
+}
+
+fn last_modified(file: &Path) -> Option<SystemTime> {
+    fs::metadata(file)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+}
+
+/// Collects the patch file and the files of the abstractions it uses, looking in the directory of
+/// each file and then in the search paths.
+fn watched_files(path: &Path, search_paths: &[PathBuf]) -> Vec<(PathBuf, Option<SystemTime>)> {
+    let mut files: Vec<(PathBuf, Option<SystemTime>)> = vec![];
+    let mut pending = vec![path.to_path_buf()];
+    while let Some(file) = pending.pop() {
+        if files.iter().any(|(watched, _)| *watched == file) {
+            continue;
+        }
+        let parsed = fs::read_to_string(&file)
+            .ok()
+            .and_then(|contents| contents.parse::<PatchFile>().ok());
+        if let Some(parsed) = parsed {
+            let directory = file.parent().map(Path::to_path_buf).unwrap_or_default();
+            let mut classes = vec![];
+            collect_classes(&parsed.canvas, &mut classes);
+            pending.extend(classes.into_iter().filter_map(|class| {
+                let file_name = format!("{class}.pd");
+                iter::once(&directory)
+                    .chain(search_paths)
+                    .map(|directory| directory.join(&file_name))
+                    .find(|abstraction| abstraction.is_file())
+            }));
+        }
+        let modified = last_modified(&file);
+        files.push((file, modified));
+    }
+    files
+}
+
+fn collect_classes<'canvas>(canvas: &'canvas Canvas, classes: &mut Vec<&'canvas str>) {
+    for entry in &canvas.entries {
+        match entry {
+            Entry::Object(object) => classes.push(&object.class),
+            Entry::Subpatch(subpatch) => collect_classes(&subpatch.canvas, classes),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,178 @@
+use futures_channel::mpsc::{self, UnboundedReceiver, UnboundedSender};
+use futures_core::Stream;
+#[cfg(feature = "tokio")]
+use std::time::Duration;
+use std::{
+    pin::Pin,
+    task::{Context, Poll},
+};
+#[cfg(feature = "tokio")]
+use tokio::{task::JoinHandle, time};
+
+use crate::{
+    error::{PdError, SendError},
+    functions,
+    types::{HookHandle, OutgoingMessage, PdEvent},
+    PdAudioContext,
+};
+
+/// Creates an async bridge to the instance which the audio context belongs to.
+///
+/// - [`AsyncSender`] queues messages to be sent to pd from any thread or task.
+/// - [`EventStream`] yields everything pd sends as [`PdEvent`]s.
+/// - [`Pump`] sends the queued messages and drains the message queues of pd.
+///
+/// Events arrive in the stream whenever the message queues of the instance are drained.
+/// This is done by [`Pump::pump`] but calling
+/// [`receive_messages_from_pd`](crate::PdAudioContext::receive_messages_from_pd) from the audio callback works as well.
+///
+/// Registering the stream replaces the closures which are set with the `on_*` functions for this instance
+/// until the [`EventStream`] is dropped.
+///
+/// # Examples
+/// ```no_run
+/// use futures::StreamExt;
+/// use libpd_rs::{stream, types::OutgoingMessage, Pd};
+///
+/// # futures::executor::block_on(async {
+/// let mut pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+/// let _patch = pd.open_patch("tests/patches/echo.pd").unwrap();
+/// pd.subscribe_to("float_from_pd").unwrap();
+///
+/// let (sender, mut events, mut pump) = stream::channel(&pd.audio_context());
+///
+/// sender
+///     .send(OutgoingMessage::Float {
+///         receiver: "float_from_rust".to_owned(),
+///         value: 42.0,
+///     })
+///     .unwrap();
+/// pump.pump().unwrap();
+///
+/// if let Some(event) = events.next().await {
+///     println!("{event:?}");
+/// }
+/// # });
+/// ```
+#[expect(
+    clippy::let_underscore_must_use,
+    clippy::let_underscore_untyped,
+    reason = "If the stream is dropped there is no one to deliver the event to, so it is fine to drop the event."
+)]
+pub fn channel(ctx: &PdAudioContext) -> (AsyncSender, EventStream, Pump) {
+    let (event_sender, event_receiver) = mpsc::unbounded();
+    let (message_sender, message_receiver) = mpsc::unbounded();
+
+    let hooks = {
+        let _guard = ctx.instance.set_as_active_instance();
+        functions::receive::forward_events(move |event| {
+            let _ = event_sender.unbounded_send(event);
+        })
+    };
+
+    (
+        AsyncSender {
+            sender: message_sender,
+        },
+        EventStream {
+            receiver: event_receiver,
+            _hooks: hooks,
+        },
+        Pump {
+            ctx: ctx.clone(),
+            receiver: message_receiver,
+        },
+    )
+}
+
+/// A cloneable handle which queues messages to be sent to pd by the [`Pump`].
+///
+/// Sending never blocks, so it can be used freely in async code.
+#[derive(Debug, Clone)]
+pub struct AsyncSender {
+    sender: UnboundedSender<OutgoingMessage>,
+}
+
+impl AsyncSender {
+    /// Queues a message to be sent on the next [`Pump::pump`].
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`Disconnected`](crate::error::SendError::Disconnected)
+    pub fn send(&self, message: OutgoingMessage) -> Result<(), SendError> {
+        self.sender
+            .unbounded_send(message)
+            .map_err(|_| SendError::Disconnected)
+    }
+}
+
+/// A [`Stream`] of everything pd sends to the application.
+///
+/// The listeners which feed the stream are unregistered when this is dropped.
+#[derive(Debug)]
+pub struct EventStream {
+    receiver: UnboundedReceiver<PdEvent>,
+    _hooks: Vec<HookHandle>,
+}
+
+impl Stream for EventStream {
+    type Item = PdEvent;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        Pin::new(&mut self.get_mut().receiver).poll_next(cx)
+    }
+}
+
+/// Moves messages between the async side and the pd instance.
+#[derive(Debug)]
+pub struct Pump {
+    ctx: PdAudioContext,
+    receiver: UnboundedReceiver<OutgoingMessage>,
+}
+
+impl Pump {
+    /// Sends the messages which are queued by the [`AsyncSender`]s and drains the message and MIDI queues of pd.
+    ///
+    /// All queued messages are tried to be sent even if some of them fail.
+    ///
+    /// # Errors
+    ///
+    /// The first error of the failed messages, see [`OutgoingMessage::send`].
+    pub fn pump(&mut self) -> Result<(), PdError> {
+        let _guard = self.ctx.instance.set_as_active_instance();
+        let mut result = Ok(());
+        while let Ok(message) = self.receiver.try_recv() {
+            let sent = message.send();
+            if result.is_ok() {
+                result = sent;
+            }
+        }
+        functions::receive::receive_messages_from_pd();
+        functions::receive::receive_midi_messages_from_pd();
+        result
+    }
+
+    /// Spawns a tokio task which calls [`pump`](Pump::pump) every `period`.
+    ///
+    /// Errors of failed messages are ignored. The task runs until the returned handle is aborted.
+    ///
+    /// # Panics
+    ///
+    /// If it is not called in the context of a tokio runtime.
+    #[cfg(feature = "tokio")]
+    #[expect(
+        clippy::let_underscore_must_use,
+        clippy::let_underscore_untyped,
+        reason = "There is no one to report the errors to in a detached task."
+    )]
+    pub fn spawn(mut self, period: Duration) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = time::interval(period);
+            loop {
+                interval.tick().await;
+                let _ = self.pump();
+            }
+        })
+    }
+}
@@ -0,0 +1,389 @@
+use std::{
+    env, fmt,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use hound::{SampleFormat, WavReader, WavSpec, WavWriter};
+
+use crate::{
+    audio::{self, frames_in},
+    error::{AudioProcessingError, IoError, PdError, SizeError, WavError},
+    types::OutgoingMessage,
+    Pd,
+};
+
+/// The environment variable which makes golden tests write their output as the reference instead of comparing it.
+///
+/// Set it to `1` to create the reference files of new tests or to accept a change in the output of a patch.
+pub const UPDATE_GOLDEN: &str = "LIBPD_RS_UPDATE_GOLDEN";
+
+/// The largest differences from the reference which are accepted.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tolerance {
+    /// The largest difference of a single sample.
+    pub peak: f32,
+    /// The largest root mean square of the differences of all the samples.
+    pub rms: f32,
+}
+
+impl Default for Tolerance {
+    /// Accepts the differences of rounding only.
+    fn default() -> Self {
+        Self {
+            peak: 1e-6,
+            rms: 1e-7,
+        }
+    }
+}
+
+/// A test which renders a patch offline and compares its output with a reference wav file.
+///
+/// Messages are sent to the patch at logical times from the start of the rendering,
+/// before the block which the time falls into is processed.
+/// The reference is rendered the same way, so the output is deterministic as long as the patch does not change.
+///
+/// When the [`UPDATE_GOLDEN`] environment variable is set the output is written as the reference instead.
+///
+/// # Examples
+/// ```no_run
+/// use std::time::Duration;
+/// use libpd_rs::{
+///     testing::{GoldenTest, Tolerance},
+///     types::OutgoingMessage,
+///     Pd,
+/// };
+///
+/// let mut pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+///
+/// GoldenTest::new("patches/synth.pd", 689)
+///     .send_at(
+///         Duration::from_millis(500),
+///         OutgoingMessage::Float {
+///             receiver: "frequency".to_owned(),
+///             value: 220.0,
+///         },
+///     )
+///     .tolerance(Tolerance {
+///         peak: 1e-4,
+///         rms: 1e-5,
+///     })
+///     .assert_matches(&mut pd, "tests/golden/synth.wav");
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct GoldenTest {
+    patch: PathBuf,
+    blocks: usize,
+    /// The messages to send sorted by their times.
+    script: Vec<(Duration, OutgoingMessage)>,
+    tolerance: Tolerance,
+}
+
+impl GoldenTest {
+    /// Creates a test which renders a number of blocks of the patch with the default tolerance.
+    pub fn new<T: AsRef<Path>>(patch: T, blocks: usize) -> Self {
+        Self {
+            patch: patch.as_ref().to_path_buf(),
+            blocks,
+            script: vec![],
+            tolerance: Tolerance::default(),
+        }
+    }
+
+    /// Sends a message at a logical time from the start of the rendering.
+    ///
+    /// Messages at the same time are sent in the order they are added.
+    pub fn send_at(&mut self, time: Duration, message: OutgoingMessage) -> &mut Self {
+        let position = self
+            .script
+            .partition_point(|(scheduled, _)| *scheduled <= time);
+        self.script.insert(position, (time, message));
+        self
+    }
+
+    /// Sets the largest differences from the reference which are accepted.
+    pub const fn tolerance(&mut self, tolerance: Tolerance) -> &mut Self {
+        self.tolerance = tolerance;
+        self
+    }
+
+    /// Opens the patch, renders its interleaved output while sending the messages and closes it.
+    ///
+    /// # Errors
+    ///
+    /// The errors of [`Pd::open_patch`], [`OutgoingMessage::send`] and [`Pd::activate_audio`] and:
+    /// - [`AudioProcessingError`](crate::error::AudioProcessingError)
+    ///   - [`NoChannels`](crate::error::AudioProcessingError::NoChannels)
+    /// - [`SizeError`](crate::error::SizeError)
+    ///   - [`TooLarge`](crate::error::SizeError::TooLarge)
+    pub fn render(&self, pd: &mut Pd) -> Result<Vec<f32>, PdError> {
+        let audio_active = pd.audio_active();
+        let patch = pd.open_patch(&self.patch)?;
+        pd.activate_audio(true)?;
+        let output = self.render_blocks(pd);
+        pd.activate_audio(audio_active)?;
+        patch.close()?;
+        output
+    }
+
+    /// Renders the patch and compares its output with the reference wav file.
+    ///
+    /// When the [`UPDATE_GOLDEN`] environment variable is set the output is written to the reference file
+    /// and compared with itself.
+    ///
+    /// # Errors
+    ///
+    /// The errors of [`render`](GoldenTest::render) and:
+    /// - [`IoError`](crate::error::IoError)
+    ///   - [`PathDoesNotExist`](crate::error::IoError::PathDoesNotExist)
+    /// - [`WavError`](crate::error::WavError)
+    ///   - [`InvalidFile`](crate::error::WavError::InvalidFile)
+    ///   - [`ChannelCount`](crate::error::WavError::ChannelCount)
+    ///   - [`SampleRate`](crate::error::WavError::SampleRate)
+    pub fn compare<T: AsRef<Path>>(
+        &self,
+        pd: &mut Pd,
+        reference: T,
+    ) -> Result<Comparison, PdError> {
+        let reference = reference.as_ref();
+        let output = self.render(pd)?;
+        let channels = usize::try_from(pd.output_channels()).unwrap_or_default();
+        let spec = WavSpec {
+            channels: u16::try_from(channels).map_err(|_| SizeError::TooLarge)?,
+            sample_rate: u32::try_from(pd.sample_rate()).map_err(|_| SizeError::TooLarge)?,
+            bits_per_sample: 32,
+            sample_format: SampleFormat::Float,
+        };
+
+        if env::var_os(UPDATE_GOLDEN).is_some() {
+            write_reference(reference, spec, &output).map_err(WavError::from)?;
+            return Ok(Comparison::new(&output, &output, channels, self.tolerance));
+        }
+        if !reference.is_file() {
+            return Err(IoError::PathDoesNotExist(reference.to_string_lossy().into_owned()).into());
+        }
+        let expected = read_reference(reference, spec)?;
+        Ok(Comparison::new(
+            &output,
+            &expected,
+            channels,
+            self.tolerance,
+        ))
+    }
+
+    /// Renders the patch and panics with the differences if its output does not match the reference wav file.
+    ///
+    /// # Panics
+    ///
+    /// If the output does not match the reference or the test could not be run.
+    #[expect(clippy::panic, reason = "This is an assertion to be used in tests.")]
+    pub fn assert_matches<T: AsRef<Path>>(&self, pd: &mut Pd, reference: T) {
+        let reference = reference.as_ref();
+        let patch = self.patch.display();
+        match self.compare(pd, reference) {
+            Ok(comparison) if comparison.passed() => {}
+            Ok(comparison) => panic!(
+                "The output of {patch} does not match {}.\n{comparison}\nRun with {UPDATE_GOLDEN}=1 to accept the new output.",
+                reference.display()
+            ),
+            Err(PdError::IoError(IoError::PathDoesNotExist(_))) => panic!(
+                "The reference {} does not exist, run with {UPDATE_GOLDEN}=1 to create it from the output of {patch}.",
+                reference.display()
+            ),
+            Err(err) => panic!("The golden test of {patch} could not be run: {err}"),
+        }
+    }
+
+    fn render_blocks(&self, pd: &Pd) -> Result<Vec<f32>, PdError> {
+        let processor = pd.audio_context().processor();
+        let block_size = processor.block_size();
+        let output_block = block_size * processor.output_channels();
+        if output_block == 0 {
+            return Err(AudioProcessingError::NoChannels.into());
+        }
+        let sample_rate = u32::try_from(pd.sample_rate()).map_err(|_| SizeError::TooLarge)?;
+        let mut script = self
+            .script
+            .iter()
+            .map(|(time, message)| Ok((frames_in(*time, sample_rate)?, message)))
+            .collect::<Result<Vec<_>, PdError>>()?
+            .into_iter()
+            .peekable();
+
+        let input = vec![0.0f32; block_size * processor.input_channels()];
+        let mut output = vec![0.0f32; output_block * self.blocks];
+        for (index, block) in output.chunks_exact_mut(output_block).enumerate() {
+            let end = (index + 1) * block_size;
+            while let Some((_, message)) = script.next_if(|(frame, _)| *frame < end) {
+                let _guard = pd.set_as_active_instance();
+                message.send()?;
+            }
+            processor.process_float(&input, block)?;
+        }
+        Ok(output)
+    }
+}
+
+/// The result of comparing the output of a patch with its reference.
+///
+/// Its [`Display`](fmt::Display) implementation describes the differences.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Comparison {
+    /// The number of channels in a frame.
+    pub channels: usize,
+    /// The number of frames in the output.
+    pub frames: usize,
+    /// The number of frames in the reference.
+    pub reference_frames: usize,
+    /// The first sample which differs more than the peak tolerance.
+    pub first: Option<Difference>,
+    /// The sample which differs the most.
+    pub peak: Option<Difference>,
+    /// The root mean square of the differences of the samples which are in both.
+    pub rms: f32,
+    /// The tolerance which the differences are compared with.
+    pub tolerance: Tolerance,
+}
+
+/// A sample which differs from the reference.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Difference {
+    /// The index of the frame.
+    pub frame: usize,
+    /// The index of the channel.
+    pub channel: usize,
+    /// The sample in the reference.
+    pub expected: f32,
+    /// The sample in the output.
+    pub actual: f32,
+}
+
+impl Difference {
+    /// Gets the absolute difference of the samples.
+    pub fn amount(&self) -> f32 {
+        (self.actual - self.expected).abs()
+    }
+}
+
+impl Comparison {
+    #[expect(
+        clippy::cast_precision_loss,
+        clippy::cast_possible_truncation,
+        reason = "The root mean square is an approximation."
+    )]
+    fn new(output: &[f32], reference: &[f32], channels: usize, tolerance: Tolerance) -> Self {
+        let mut first = None;
+        let mut peak: Option<Difference> = None;
+        let mut squares = 0.0f64;
+        for (index, (actual, expected)) in output.iter().zip(reference).enumerate() {
+            let difference = Difference {
+                frame: index.checked_div(channels).unwrap_or_default(),
+                channel: index.checked_rem(channels).unwrap_or_default(),
+                expected: *expected,
+                actual: *actual,
+            };
+            let amount = difference.amount();
+            squares += f64::from(amount) * f64::from(amount);
+            if first.is_none() && amount > tolerance.peak {
+                first = Some(difference);
+            }
+            if peak.is_none_or(|peak| amount > peak.amount()) && amount > 0.0 {
+                peak = Some(difference);
+            }
+        }
+        let compared = output.len().min(reference.len());
+        let rms = if compared == 0 {
+            0.0
+        } else {
+            (squares / compared as f64).sqrt() as f32
+        };
+        Self {
+            channels,
+            frames: output.len().checked_div(channels).unwrap_or_default(),
+            reference_frames: reference.len().checked_div(channels).unwrap_or_default(),
+            first,
+            peak,
+            rms,
+            tolerance,
+        }
+    }
+
+    /// Whether the output has the length of the reference and its differences are within the tolerance.
+    pub fn passed(&self) -> bool {
+        self.frames == self.reference_frames
+            && self
+                .peak
+                .is_none_or(|peak| peak.amount() <= self.tolerance.peak)
+            && self.rms <= self.tolerance.rms
+    }
+}
+
+impl fmt::Display for Comparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.frames != self.reference_frames {
+            writeln!(
+                f,
+                "  length: {} frames but the reference has {} frames",
+                self.frames, self.reference_frames
+            )?;
+        }
+        if let Some(first) = self.first {
+            writeln!(f, "  first difference: {first}")?;
+        }
+        match self.peak {
+            Some(peak) => writeln!(
+                f,
+                "  peak difference:  {peak}, tolerance {}",
+                self.tolerance.peak
+            )?,
+            None => writeln!(f, "  peak difference:  none")?,
+        }
+        write!(
+            f,
+            "  rms difference:   {}, tolerance {}",
+            self.rms, self.tolerance.rms
+        )
+    }
+}
+
+impl fmt::Display for Difference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} at frame {} of channel {}, expected {} but got {}",
+            self.amount(),
+            self.frame,
+            self.channel,
+            self.expected,
+            self.actual
+        )
+    }
+}
+
+fn write_reference(path: &Path, spec: WavSpec, output: &[f32]) -> Result<(), hound::Error> {
+    let mut writer = WavWriter::create(path, spec)?;
+    for sample in output {
+        writer.write_sample(*sample)?;
+    }
+    writer.finalize()
+}
+
+fn read_reference(path: &Path, spec: WavSpec) -> Result<Vec<f32>, PdError> {
+    let reader = WavReader::open(path).map_err(WavError::from)?;
+    let reference = reader.spec();
+    if reference.channels != spec.channels {
+        return Err(WavError::ChannelCount {
+            channels: reference.channels,
+            expected: usize::from(spec.channels),
+        }
+        .into());
+    }
+    if reference.sample_rate != spec.sample_rate {
+        return Err(WavError::SampleRate {
+            sample_rate: reference.sample_rate,
+            expected: spec.sample_rate,
+        }
+        .into());
+    }
+    audio::samples(reader).collect()
+}
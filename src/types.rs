@@ -1,4 +1,23 @@
 use core::ffi;
+use std::{
+    fmt,
+    ops::Deref,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc::Receiver,
+        Arc,
+    },
+};
+
+use crossbeam_queue::ArrayQueue;
+
+use crate::{
+    atom::Atom,
+    error::{PdError, SendError},
+    functions::{receive::hooks, send},
+    midi::{Channel, MidiMessage, U14, U7},
+};
 
 /// The handle which is returned from opening a patch.
 ///
@@ -42,3 +61,556 @@ impl From<*mut ffi::c_void> for ReceiverHandle {
         Self(ptr)
     }
 }
+
+/// The handle which is returned from registering a listener (hook in libpd lingo).
+///
+/// The listener stays registered as long as this handle is alive.
+///
+/// When the handle is dropped, the listener is unregistered and the listener which was registered
+/// before it is restored. If there was none, the hook is cleared.
+/// The handle can be dropped in any thread, its closure is freed after the next time the queue
+/// which calls it is drained, since a drain in another thread may be calling it at that moment.
+#[must_use = "The listener is unregistered when the handle is dropped."]
+pub struct HookHandle {
+    id: u64,
+    /// The closures which pd calls, `None` for handles which only clear a hook.
+    trampoline: Option<Box<dyn Send>>,
+}
+
+impl HookHandle {
+    /// Installs the hook for the current instance and keeps the closures it points to alive.
+    pub(crate) fn register(hook: hooks::RawHook, trampoline: Option<Box<dyn Send>>) -> Self {
+        Self {
+            id: hooks::register(hook),
+            trampoline,
+        }
+    }
+}
+
+impl fmt::Debug for HookHandle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HookHandle")
+            .field("id", &self.id)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for HookHandle {
+    fn drop(&mut self) {
+        // The trampoline is kept until pd can not be calling into it anymore.
+        hooks::unregister(self.id, self.trampoline.take());
+    }
+}
+
+// The trampoline is never accessed through a shared reference.
+unsafe impl Sync for HookHandle {}
+
+/// A message which is sent from a pd patch to one of the subscribed receivers.
+///
+/// Every variant carries the name of the receiver in pd which the message was sent to as its `source`.
+///
+/// Floats are carried as `f64` since pd is compiled with double precision, this matches [`Atom::Float`](crate::atom::Atom::Float).
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+pub enum PdMessage {
+    /// A `bang` which is sent to `source`.
+    Bang {
+        /// The name of the receiver which the message was sent to.
+        source: String,
+    },
+    /// A float which is sent to `source`.
+    Float {
+        /// The name of the receiver which the message was sent to.
+        source: String,
+        /// The value which is sent.
+        value: f64,
+    },
+    /// A symbol which is sent to `source`.
+    Symbol {
+        /// The name of the receiver which the message was sent to.
+        source: String,
+        /// The value which is sent.
+        value: String,
+    },
+    /// A list which is sent to `source`.
+    List {
+        /// The name of the receiver which the message was sent to.
+        source: String,
+        /// The elements of the list.
+        values: Vec<Atom>,
+    },
+    /// A typed message which is sent to `source`.
+    ///
+    /// In a message like `[; foo hello 1.0 merhaba]`, `message` would be `hello`.
+    Message {
+        /// The name of the receiver which the message was sent to.
+        source: String,
+        /// The selector of the message.
+        message: String,
+        /// The arguments of the message.
+        values: Vec<Atom>,
+    },
+}
+
+impl PdMessage {
+    /// Returns the name of the receiver which the message was sent to.
+    pub fn source(&self) -> &str {
+        match self {
+            Self::Bang { source }
+            | Self::Float { source, .. }
+            | Self::Symbol { source, .. }
+            | Self::List { source, .. }
+            | Self::Message { source, .. } => source,
+        }
+    }
+}
+
+/// Anything which pd can send to the application.
+///
+/// This unifies messages, MIDI events and console output so they can be consumed from a single place,
+/// see [`poll_events`](crate::Pd::poll_events).
+///
+/// MIDI values follow the same conventions as the listeners in [`receive`](crate::functions::receive)
+/// and use the types in [`midi`](crate::midi).
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+pub enum PdEvent {
+    /// A message which is sent to one of the subscribed receivers.
+    Message(PdMessage),
+    /// A MIDI note on event, a velocity of `0` means note off.
+    NoteOn {
+        /// The channel with its port.
+        channel: Channel,
+        /// `0-127`
+        pitch: U7,
+        /// `0-127`
+        velocity: U7,
+    },
+    /// A MIDI control change event.
+    ControlChange {
+        /// The channel with its port.
+        channel: Channel,
+        /// `0-127`
+        controller: U7,
+        /// `0-127`
+        value: U7,
+    },
+    /// A MIDI program change event.
+    ProgramChange {
+        /// The channel with its port.
+        channel: Channel,
+        /// `0-127`
+        value: U7,
+    },
+    /// A MIDI pitch bend event.
+    PitchBend {
+        /// The channel with its port.
+        channel: Channel,
+        /// `0-16383`, [`U14::CENTER`] is no bend.
+        value: U14,
+    },
+    /// A MIDI after touch event.
+    AfterTouch {
+        /// The channel with its port.
+        channel: Channel,
+        /// `0-127`
+        value: U7,
+    },
+    /// A MIDI poly after touch event.
+    PolyAfterTouch {
+        /// The channel with its port.
+        channel: Channel,
+        /// `0-127`
+        pitch: U7,
+        /// `0-127`
+        value: U7,
+    },
+    /// A single raw MIDI byte.
+    MidiByte {
+        /// Zero-indexed port.
+        port: u8,
+        /// The raw byte.
+        byte: u8,
+    },
+    /// A line which is written to the pd console.
+    Print(String),
+    /// A watched patch is reopened after its file or one of its abstractions changed.
+    PatchReloaded {
+        /// The path which the patch is watched with.
+        path: PathBuf,
+    },
+    /// A watched patch changed but could not be reopened, the previous version keeps running.
+    PatchReloadFailed {
+        /// The path which the patch is watched with.
+        path: PathBuf,
+        /// The reason of the failure.
+        error: String,
+    },
+}
+
+impl From<PdMessage> for PdEvent {
+    fn from(message: PdMessage) -> Self {
+        Self::Message(message)
+    }
+}
+
+/// A message which can be sent to pd.
+///
+/// This is the owned counterpart of the functions in [`send`](crate::functions::send)
+/// so messages can be built on one thread and sent on another.
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, PartialOrd)]
+pub enum OutgoingMessage {
+    /// A `bang` which is sent to `receiver`.
+    Bang {
+        /// The name of the receiver in pd.
+        receiver: String,
+    },
+    /// A float which is sent to `receiver`.
+    Float {
+        /// The name of the receiver in pd.
+        receiver: String,
+        /// The value which is sent.
+        value: f64,
+    },
+    /// A symbol which is sent to `receiver`.
+    Symbol {
+        /// The name of the receiver in pd.
+        receiver: String,
+        /// The value which is sent.
+        value: String,
+    },
+    /// A list which is sent to `receiver`.
+    List {
+        /// The name of the receiver in pd.
+        receiver: String,
+        /// The elements of the list.
+        values: Vec<Atom>,
+    },
+    /// A typed message which is sent to `receiver`.
+    Message {
+        /// The name of the receiver in pd.
+        receiver: String,
+        /// The selector of the message.
+        message: String,
+        /// The arguments of the message.
+        values: Vec<Atom>,
+    },
+    /// A MIDI note on event, see [`send_note_on`](crate::functions::send::send_note_on).
+    NoteOn {
+        /// The channel with its port.
+        channel: Channel,
+        /// `0-127`
+        pitch: U7,
+        /// `0-127`
+        velocity: U7,
+    },
+    /// A MIDI control change event, see [`send_control_change`](crate::functions::send::send_control_change).
+    ControlChange {
+        /// The channel with its port.
+        channel: Channel,
+        /// `0-127`
+        controller: U7,
+        /// `0-127`
+        value: U7,
+    },
+    /// A MIDI program change event, see [`send_program_change`](crate::functions::send::send_program_change).
+    ProgramChange {
+        /// The channel with its port.
+        channel: Channel,
+        /// `0-127`
+        value: U7,
+    },
+    /// A MIDI pitch bend event, see [`send_pitch_bend`](crate::functions::send::send_pitch_bend).
+    PitchBend {
+        /// The channel with its port.
+        channel: Channel,
+        /// `0-16383`, [`U14::CENTER`] is no bend.
+        value: U14,
+    },
+    /// A MIDI after touch event, see [`send_after_touch`](crate::functions::send::send_after_touch).
+    AfterTouch {
+        /// The channel with its port.
+        channel: Channel,
+        /// `0-127`
+        value: U7,
+    },
+    /// A MIDI poly after touch event, see [`send_poly_after_touch`](crate::functions::send::send_poly_after_touch).
+    PolyAfterTouch {
+        /// The channel with its port.
+        channel: Channel,
+        /// `0-127`
+        pitch: U7,
+        /// `0-127`
+        value: U7,
+    },
+    /// A raw MIDI byte, see [`send_midi_byte`](crate::functions::send::send_midi_byte).
+    MidiByte {
+        /// Zero-indexed port.
+        port: u8,
+        /// The raw byte.
+        byte: u8,
+    },
+    /// A raw MIDI byte for `|sysexin|`, see [`send_sysex`](crate::functions::send::send_sysex).
+    Sysex {
+        /// Zero-indexed port.
+        port: u8,
+        /// The raw byte.
+        byte: u8,
+    },
+    /// A raw MIDI byte for `|midirealtimein|`, see [`send_sys_realtime`](crate::functions::send::send_sys_realtime).
+    SysRealtime {
+        /// Zero-indexed port.
+        port: u8,
+        /// The raw byte.
+        byte: u8,
+    },
+}
+
+impl From<MidiMessage> for OutgoingMessage {
+    /// Converts the message to the variant which sends it,
+    /// a note off becomes a note on with velocity = 0 like [`send_midi`](crate::functions::send::send_midi) sends it.
+    fn from(message: MidiMessage) -> Self {
+        match message {
+            MidiMessage::NoteOff { channel, pitch, .. } => Self::NoteOn {
+                channel,
+                pitch,
+                velocity: U7::MIN,
+            },
+            MidiMessage::NoteOn {
+                channel,
+                pitch,
+                velocity,
+            } => Self::NoteOn {
+                channel,
+                pitch,
+                velocity,
+            },
+            MidiMessage::PolyAfterTouch {
+                channel,
+                pitch,
+                value,
+            } => Self::PolyAfterTouch {
+                channel,
+                pitch,
+                value,
+            },
+            MidiMessage::ControlChange {
+                channel,
+                controller,
+                value,
+            } => Self::ControlChange {
+                channel,
+                controller,
+                value,
+            },
+            MidiMessage::ProgramChange { channel, program } => Self::ProgramChange {
+                channel,
+                value: program,
+            },
+            MidiMessage::AfterTouch { channel, value } => Self::AfterTouch { channel, value },
+            MidiMessage::PitchBend { channel, value } => Self::PitchBend { channel, value },
+        }
+    }
+}
+
+impl OutgoingMessage {
+    /// Sends the message to the current instance.
+    ///
+    /// # Errors
+    ///
+    /// The errors of the corresponding function in [`send`](crate::functions::send) are returned.
+    pub fn send(&self) -> Result<(), PdError> {
+        match self {
+            Self::Bang { receiver } => send::send_bang_to(receiver)?,
+            Self::Float { receiver, value } => send::send_double_to(receiver, *value)?,
+            Self::Symbol { receiver, value } => send::send_symbol_to(receiver, value)?,
+            Self::List { receiver, values } => send::send_list_to(receiver, values)?,
+            Self::Message {
+                receiver,
+                message,
+                values,
+            } => send::send_message_to(receiver.as_str(), message.as_str(), values)?,
+            Self::NoteOn {
+                channel,
+                pitch,
+                velocity,
+            } => send::send_note_on(*channel, *pitch, *velocity)?,
+            Self::ControlChange {
+                channel,
+                controller,
+                value,
+            } => send::send_control_change(*channel, *controller, *value)?,
+            Self::ProgramChange { channel, value } => send::send_program_change(*channel, *value)?,
+            Self::PitchBend { channel, value } => send::send_pitch_bend(*channel, *value)?,
+            Self::AfterTouch { channel, value } => send::send_after_touch(*channel, *value)?,
+            Self::PolyAfterTouch {
+                channel,
+                pitch,
+                value,
+            } => send::send_poly_after_touch(*channel, *pitch, *value)?,
+            Self::MidiByte { port, byte } => send::send_midi_byte(*port, *byte)?,
+            Self::Sysex { port, byte } => send::send_sysex(*port, *byte)?,
+            Self::SysRealtime { port, byte } => send::send_sys_realtime(*port, *byte)?,
+        }
+        Ok(())
+    }
+}
+
+/// A cloneable handle which queues messages to be sent to pd on the audio thread.
+///
+/// The messages are pushed into a lock-free queue which is allocated when the sender is created,
+/// so sending never blocks or allocates and can be done from any thread.
+/// The [`PdAudioContext`](crate::PdAudioContext) which is created with the sender sends the queued messages
+/// before each block of audio it processes, so all the calls to libpd happen on the audio thread.
+///
+/// This is returned from [`audio_context_with_sender`](crate::Pd::audio_context_with_sender).
+///
+/// # Examples
+/// ```no_run
+/// use libpd_rs::{types::OutgoingMessage, Pd};
+///
+/// let pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+/// let (ctx, sender) = pd.audio_context_with_sender(256);
+///
+/// let ui = sender.clone();
+/// std::thread::spawn(move || {
+///     ui.send(OutgoingMessage::Float {
+///         receiver: "volume".to_owned(),
+///         value: 0.5,
+///     })
+///     .unwrap();
+/// });
+///
+/// // In the audio callback, the message is sent before the buffer is processed.
+/// let mut output = [0.0_f32; 512];
+/// ctx.process_float(4, &[], &mut output);
+/// ```
+#[derive(Debug, Clone)]
+pub struct PdSender {
+    queue: Arc<MessageQueue>,
+}
+
+impl PdSender {
+    pub(crate) fn new(capacity: usize) -> Self {
+        Self {
+            queue: Arc::new(MessageQueue {
+                messages: ArrayQueue::new(capacity.max(1)),
+                failed: AtomicUsize::new(0),
+            }),
+        }
+    }
+
+    pub(crate) fn queue(&self) -> Arc<MessageQueue> {
+        Arc::clone(&self.queue)
+    }
+
+    /// Queues a message to be sent before the next block which is processed.
+    ///
+    /// # Errors
+    ///
+    /// A list of errors that can occur:
+    /// - [`QueueFull`](crate::error::SendError::QueueFull)
+    pub fn send(&self, message: OutgoingMessage) -> Result<(), SendError> {
+        self.queue
+            .messages
+            .push(message)
+            .map_err(|_| SendError::QueueFull)
+    }
+
+    /// Gets the number of messages which the queue can hold.
+    pub fn capacity(&self) -> usize {
+        self.queue.messages.capacity()
+    }
+
+    /// Gets the number of messages which are waiting to be sent.
+    pub fn len(&self) -> usize {
+        self.queue.messages.len()
+    }
+
+    /// Whether there are no messages waiting to be sent.
+    pub fn is_empty(&self) -> bool {
+        self.queue.messages.is_empty()
+    }
+
+    /// Gets the number of queued messages which pd failed to receive, for example because the receiver does not exist.
+    pub fn failed(&self) -> usize {
+        self.queue.failed.load(Ordering::Relaxed)
+    }
+}
+
+/// The queue which is shared by the [`PdSender`]s and the audio contexts which send its messages.
+#[derive(Debug)]
+pub(crate) struct MessageQueue {
+    messages: ArrayQueue<OutgoingMessage>,
+    failed: AtomicUsize,
+}
+
+impl MessageQueue {
+    /// Sends the queued messages to the current instance and counts the ones which fail.
+    pub(crate) fn send_all(&self) {
+        while let Some(message) = self.messages.pop() {
+            if message.send().is_err() {
+                self.failed.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+}
+
+/// A channel which receives the messages sent from pd as [`PdMessage`]s.
+///
+/// This is returned from [`message_receiver`](crate::functions::receive::message_receiver),
+/// it dereferences to a [`Receiver`] so it can be used like one.
+///
+/// The listeners which feed the channel are unregistered when this is dropped.
+#[derive(Debug)]
+pub struct MessageReceiver {
+    receiver: Receiver<PdMessage>,
+    _hooks: Vec<HookHandle>,
+}
+
+impl MessageReceiver {
+    pub(crate) const fn new(receiver: Receiver<PdMessage>, hooks: Vec<HookHandle>) -> Self {
+        Self {
+            receiver,
+            _hooks: hooks,
+        }
+    }
+}
+
+impl Deref for MessageReceiver {
+    type Target = Receiver<PdMessage>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.receiver
+    }
+}
+
+/// A channel which receives everything pd sends as [`PdEvent`]s.
+///
+/// This is returned from [`event_receiver`](crate::functions::receive::event_receiver),
+/// it dereferences to a [`Receiver`] so it can be used like one.
+///
+/// The listeners which feed the channel are unregistered when this is dropped.
+#[derive(Debug)]
+pub struct EventReceiver {
+    receiver: Receiver<PdEvent>,
+    _hooks: Vec<HookHandle>,
+}
+
+impl EventReceiver {
+    pub(crate) const fn new(receiver: Receiver<PdEvent>, hooks: Vec<HookHandle>) -> Self {
+        Self {
+            receiver,
+            _hooks: hooks,
+        }
+    }
+}
+
+impl Deref for EventReceiver {
+    type Target = Receiver<PdEvent>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.receiver
+    }
+}
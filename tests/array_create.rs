@@ -0,0 +1,55 @@
+#![allow(clippy::restriction)]
+
+use libpd_rs::{
+    array::{ArrayOptions, PlotStyle},
+    error::{ArrayError, PdError},
+    Pd,
+};
+
+#[test]
+fn array_create() {
+    let pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+
+    let wavetable = pd
+        .create_array("wavetable", 64, &ArrayOptions::default())
+        .unwrap();
+    assert_eq!(wavetable.name(), "wavetable");
+    assert_eq!(wavetable.len().unwrap(), 64);
+    wavetable.write_from(&[0.5, -0.5], 0).unwrap();
+    let found = pd.array("wavetable").unwrap();
+    assert_eq!(found.slice(..2).unwrap(), [0.5, -0.5]);
+
+    // Names are unique.
+    assert!(matches!(
+        pd.create_array("wavetable", 8, &ArrayOptions::default()),
+        Err(PdError::ArrayError(ArrayError::AlreadyExists(name))) if name == "wavetable"
+    ));
+    let _patch = pd.open_patch("tests/patches/array_sketch_pad.pd").unwrap();
+    assert!(matches!(
+        pd.create_array("sketch_pad", 8, &ArrayOptions::default()),
+        Err(PdError::ArrayError(ArrayError::AlreadyExists(_)))
+    ));
+
+    let options = ArrayOptions {
+        save_contents: false,
+        style: PlotStyle::Bezier,
+        hide_name: true,
+        visible: false,
+    };
+    let delay_buffer = pd.create_array("delay buffer", 0, &options).unwrap();
+    assert_eq!(delay_buffer.len().unwrap(), 1);
+
+    // Dropping and deleting remove the arrays.
+    drop(wavetable);
+    assert!(matches!(found.len(), Err(ArrayError::FailedToFindArray)));
+    delay_buffer.delete().unwrap();
+    assert!(matches!(
+        pd.array("delay buffer"),
+        Err(ArrayError::FailedToFindArray)
+    ));
+
+    // The name can be used again.
+    let wavetable = pd.create_array("wavetable", 8, &options).unwrap();
+    assert_eq!(found.len().unwrap(), 8);
+    wavetable.delete().unwrap();
+}
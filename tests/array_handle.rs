@@ -0,0 +1,77 @@
+#![allow(clippy::restriction)]
+
+use libpd_rs::{error::ArrayError, Pd};
+
+#[test]
+fn array_handle() {
+    let pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+
+    assert!(matches!(
+        pd.array("sketch_pad"),
+        Err(ArrayError::FailedToFindArray)
+    ));
+    assert!(matches!(
+        pd.array("sketch\0pad"),
+        Err(ArrayError::StringConversion(_))
+    ));
+
+    let patch = pd.open_patch("tests/patches/array_sketch_pad.pd").unwrap();
+    let array = pd.array("sketch_pad").unwrap();
+    assert_eq!(array.name(), "sketch_pad");
+    assert_eq!(array.len().unwrap(), 100);
+
+    array.resize(8).unwrap();
+    assert_eq!(array.len().unwrap(), 8);
+    assert_eq!(array.to_vec().unwrap(), [0.0; 8]);
+
+    array.write_from(&[1.0, 2.0, 3.0], 2).unwrap();
+    assert_eq!(
+        array.to_vec().unwrap(),
+        [0.0, 0.0, 1.0, 2.0, 3.0, 0.0, 0.0, 0.0]
+    );
+    assert_eq!(array.slice(2..5).unwrap(), [1.0, 2.0, 3.0]);
+    assert_eq!(array.slice(..=2).unwrap(), [0.0, 0.0, 1.0]);
+    assert_eq!(array.slice(7..).unwrap(), [0.0]);
+    assert!(array.slice(8..).unwrap().is_empty());
+
+    array.set(7, 4.0).unwrap();
+    assert_eq!(array.get(7).unwrap(), 4.0);
+
+    let mut destination = [0.0f32; 4];
+    array.read_into(&mut destination, 3..5).unwrap();
+    assert_eq!(destination, [2.0, 3.0, 0.0, 0.0]);
+
+    // Bounds are checked.
+    assert!(matches!(array.get(8), Err(ArrayError::OutOfBounds)));
+    assert!(matches!(array.slice(6..9), Err(ArrayError::OutOfBounds)));
+    assert!(matches!(
+        array.write_from(&[1.0, 1.0], 7),
+        Err(ArrayError::OutOfBounds)
+    ));
+    assert!(matches!(
+        array.read_into(&mut destination, 0..5),
+        Err(ArrayError::OutOfBounds)
+    ));
+
+    array.fill(0.5).unwrap();
+    assert_eq!(array.to_vec().unwrap(), [0.5; 8]);
+
+    // The handle finds the array again after the patch is reopened.
+    patch.close().unwrap();
+    assert!(matches!(array.len(), Err(ArrayError::FailedToFindArray)));
+    let _patch = pd.open_patch("tests/patches/array_sketch_pad.pd").unwrap();
+    assert_eq!(array.len().unwrap(), 100);
+
+    // The handle stops working when its instance is dropped.
+    let pd_b = Pd::init_and_configure(0, 2, 44100).unwrap();
+    let patch_b = pd_b
+        .open_patch("tests/patches/array_sketch_pad.pd")
+        .unwrap();
+    let array_b = pd_b.array("sketch_pad").unwrap();
+    assert_eq!(array_b.len().unwrap(), 100);
+    drop(pd_b);
+    drop(patch_b);
+    assert!(matches!(array_b.len(), Err(ArrayError::FailedToFindArray)));
+    assert!(matches!(array_b.get(0), Err(ArrayError::FailedToFindArray)));
+    assert_eq!(array.len().unwrap(), 100);
+}
@@ -0,0 +1,61 @@
+#![allow(clippy::restriction)]
+
+use std::mem;
+
+use libpd_rs::{
+    error::ArrayError,
+    libpd_sys::{t_float, t_word},
+    Pd,
+};
+
+#[test]
+fn array_view() {
+    let mut pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+
+    assert!(matches!(
+        pd.with_array("sketch_pad", |_| ()),
+        Err(ArrayError::FailedToFindArray)
+    ));
+    assert!(matches!(
+        pd.with_array("sketch\0pad", |_| ()),
+        Err(ArrayError::StringConversion(_))
+    ));
+
+    let _patch = pd.open_patch("tests/patches/array_sketch_pad.pd").unwrap();
+    let array = pd.array("sketch_pad").unwrap();
+    array.resize(4).unwrap();
+    array.write_from(&[0.5, 1.0, 1.5, 2.0], 0).unwrap();
+
+    let contiguous = mem::size_of::<t_word>() == mem::size_of::<t_float>();
+    let len = pd
+        .with_array("sketch_pad", |view| {
+            assert!(!view.is_empty());
+            assert_eq!(view.get(1), Some(1.0));
+            assert_eq!(view.get(4), None);
+            assert_eq!(view.iter().collect::<Vec<_>>(), [0.5, 1.0, 1.5, 2.0]);
+
+            for value in view.iter_mut() {
+                *value *= 2.0;
+            }
+            *view.get_mut(0).unwrap() = -1.0;
+            assert!(view.get_mut(4).is_none());
+
+            // Slices are only available when the values are not spaced out.
+            assert_eq!(view.as_slice().is_some(), contiguous);
+            if let Some(values) = view.as_mut_slice() {
+                assert_eq!(values, [-1.0, 2.0, 3.0, 4.0]);
+                values[3] = 8.0;
+            }
+            view.len()
+        })
+        .unwrap();
+    assert_eq!(len, 4);
+
+    // The changes are made in the memory of pd and the lock is released after the closure.
+    let expected = if contiguous {
+        [-1.0, 2.0, 3.0, 8.0]
+    } else {
+        [-1.0, 2.0, 3.0, 4.0]
+    };
+    assert_eq!(array.to_vec().unwrap(), expected);
+}
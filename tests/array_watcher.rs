@@ -0,0 +1,56 @@
+#![allow(clippy::restriction)]
+
+use libpd_rs::{array::ArrayWatcher, error::ArrayError, Pd};
+
+#[test]
+fn array_watcher() {
+    let pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+    let patch = pd.open_patch("tests/patches/array_sketch_pad.pd").unwrap();
+    let array = pd.array("sketch_pad").unwrap();
+    array.resize(10).unwrap();
+
+    let mut watcher = ArrayWatcher::new(array.clone()).unwrap();
+    assert_eq!(watcher.array(), &array);
+    assert_eq!(watcher.values(), [0.0; 10]);
+    assert_eq!(watcher.version(), 0);
+
+    // Nothing changed.
+    assert!(!watcher.poll().unwrap());
+    assert!(watcher.changes().is_empty());
+    assert_eq!(watcher.version(), 0);
+
+    // Separate changes are separate ranges, neighbouring changes are merged.
+    array.write_from(&[1.0, 2.0], 1).unwrap();
+    array.set(5, 3.0).unwrap();
+    array.set(9, 4.0).unwrap();
+    assert!(watcher.poll().unwrap());
+    assert_eq!(watcher.changes(), [1..3, 5..6, 9..10]);
+    assert_eq!(
+        watcher.values(),
+        [0.0, 1.0, 2.0, 0.0, 0.0, 3.0, 0.0, 0.0, 0.0, 4.0]
+    );
+    assert_eq!(watcher.version(), 1);
+
+    // Writing the same values is not a change.
+    array.set(5, 3.0).unwrap();
+    assert!(!watcher.poll().unwrap());
+    assert!(watcher.changes().is_empty());
+    assert_eq!(watcher.version(), 1);
+
+    array.fill(f32::NAN).unwrap();
+    assert!(watcher.poll().unwrap());
+    assert_eq!(watcher.changes().len(), 1);
+    assert_eq!(watcher.changes().first(), Some(&(0..10)));
+    assert!(!watcher.poll().unwrap());
+
+    // Resizing changes all of the array.
+    array.resize(4).unwrap();
+    assert!(watcher.poll().unwrap());
+    assert_eq!(watcher.changes().len(), 1);
+    assert_eq!(watcher.changes().first(), Some(&(0..4)));
+    assert_eq!(watcher.values().len(), 4);
+    assert_eq!(watcher.version(), 3);
+
+    patch.close().unwrap();
+    assert!(matches!(watcher.poll(), Err(ArrayError::FailedToFindArray)));
+}
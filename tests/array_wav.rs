@@ -0,0 +1,132 @@
+#![allow(clippy::restriction)]
+
+use hound::{SampleFormat, WavReader, WavSpec, WavWriter};
+use libpd_rs::{
+    array::{self, LoadOptions},
+    error::{PdError, WavError},
+    Pd,
+};
+
+#[test]
+fn array_wav() {
+    let pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+    let _patch = pd
+        .eval_patch(
+            r"
+#N canvas 0 50 450 300 12;
+#X obj 20 20 table left;
+#X obj 20 60 table right;
+",
+        )
+        .unwrap();
+    let left = pd.array("left").unwrap();
+    let right = pd.array("right").unwrap();
+
+    // A stereo file of 16 bit integers, a ramp on the left and silence on the right.
+    let directory = tempfile::tempdir().unwrap();
+    let stereo = directory.path().join("stereo.wav");
+    let spec = WavSpec {
+        channels: 2,
+        sample_rate: 22050,
+        bits_per_sample: 16,
+        sample_format: SampleFormat::Int,
+    };
+    let mut writer = WavWriter::create(&stereo, spec).unwrap();
+    for index in 0..100_i16 {
+        writer.write_sample(index * 256).unwrap();
+        writer.write_sample(0_i16).unwrap();
+    }
+    writer.finalize().unwrap();
+
+    assert_eq!(left.load_wav(&stereo, 0).unwrap(), 100);
+    assert_eq!(left.len().unwrap(), 100);
+    assert_eq!(left.get(0).unwrap(), 0.0);
+    assert_eq!(left.get(64).unwrap(), 0.5);
+    assert_eq!(right.load_wav(&stereo, 1).unwrap(), 100);
+    assert_eq!(right.to_vec().unwrap(), [0.0; 100]);
+
+    // A channel which is not in the file.
+    assert!(matches!(
+        left.load_wav(&stereo, 2),
+        Err(PdError::WavError(WavError::ChannelCount {
+            channels: 2,
+            expected: 3
+        }))
+    ));
+    assert!(matches!(
+        left.load_wav(&stereo, usize::MAX),
+        Err(PdError::WavError(WavError::ChannelCount {
+            channels: 2,
+            ..
+        }))
+    ));
+    assert!(matches!(
+        left.load_wav(directory.path().join("missing.wav"), 0),
+        Err(PdError::WavError(WavError::InvalidFile(_)))
+    ));
+
+    // Resampling to the rate of the instance doubles the length and keeps the ramp.
+    let options = LoadOptions {
+        sample_rate: u32::try_from(pd.sample_rate()).ok(),
+    };
+    let frames = array::load_wav(&stereo, &[left.clone(), right.clone()], &options).unwrap();
+    assert_eq!(frames, 200);
+    assert_eq!(left.len().unwrap(), 200);
+    assert_eq!(right.len().unwrap(), 200);
+    assert_eq!(left.get(128).unwrap(), 0.5);
+    assert!((left.get(129).unwrap() - 0.503_906_25).abs() < 0.000_01);
+
+    assert!(matches!(
+        array::load_wav(
+            &stereo,
+            &[left.clone(), right.clone(), left.clone()],
+            &options
+        ),
+        Err(PdError::WavError(WavError::ChannelCount { .. }))
+    ));
+
+    // Saving writes 32 bit floats which load back as they are.
+    let mono = directory.path().join("mono.wav");
+    left.write_from(&[0.25, -0.75], 0).unwrap();
+    left.save_wav(&mono, 44100).unwrap();
+    let reader = WavReader::open(&mono).unwrap();
+    let spec = reader.spec();
+    assert_eq!(spec.channels, 1);
+    assert_eq!(spec.sample_rate, 44100);
+    assert_eq!(spec.sample_format, SampleFormat::Float);
+    assert_eq!(reader.duration(), 200);
+    let saved = left.to_vec().unwrap();
+    right.load_wav(&mono, 0).unwrap();
+    assert_eq!(right.to_vec().unwrap(), saved);
+
+    // Shorter arrays are padded with silence.
+    right.resize(2).unwrap();
+    array::save_wav(&stereo, &[left.clone(), right.clone()], 44100).unwrap();
+    let reader = WavReader::open(&stereo).unwrap();
+    assert_eq!(reader.spec().channels, 2);
+    assert_eq!(reader.duration(), 200);
+    let samples: Vec<f32> = reader.into_samples().map(Result::unwrap).collect();
+    assert_eq!(&samples[..4], [0.25, 0.25, -0.75, -0.75]);
+    assert_eq!(samples[5], 0.0);
+
+    // Downsampling filters out the frequencies which the lower rate can not hold instead of folding them back.
+    let tone = directory.path().join("tone.wav");
+    let spec = WavSpec {
+        channels: 1,
+        sample_rate: 44100,
+        bits_per_sample: 32,
+        sample_format: SampleFormat::Float,
+    };
+    let mut writer = WavWriter::create(&tone, spec).unwrap();
+    for index in 0..4410 {
+        let phase = 2.0 * std::f64::consts::PI * 15000.0 * f64::from(index) / 44100.0;
+        writer.write_sample(phase.sin() as f32).unwrap();
+    }
+    writer.finalize().unwrap();
+    let downsampled = LoadOptions {
+        sample_rate: Some(22050),
+    };
+    assert_eq!(left.load_wav_with(&tone, 0, &downsampled).unwrap(), 2205);
+    let values = left.slice(100..2100).unwrap();
+    assert!(values.iter().all(|value| value.abs() < 0.01));
+}
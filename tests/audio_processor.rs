@@ -0,0 +1,78 @@
+#![allow(clippy::restriction)]
+
+use libpd_rs::{
+    error::{AudioProcessingError, PdError},
+    Pd,
+};
+
+#[test]
+fn audio_processor() {
+    let mut pd = Pd::init_and_configure(1, 2, 44100).unwrap();
+    let processor = pd.audio_context().processor();
+    assert_eq!(processor.input_channels(), 1);
+    assert_eq!(processor.output_channels(), 2);
+    assert_eq!(processor.block_size(), 64);
+
+    let _patch = pd.open_patch("tests/patches/sine.pd").unwrap();
+    pd.activate_audio(true).unwrap();
+
+    assert_eq!(processor.ticks(256, 512).unwrap(), 4);
+    assert_eq!(processor.ticks(0, 0).unwrap(), 0);
+
+    // Float
+    let input = [0.0_f32; 256];
+    let mut output = [0.0_f32; 512];
+    for _ in 0..100 {
+        processor.process_float(&input, &mut output).unwrap();
+    }
+    assert!(output.iter().any(|sample| *sample != 0.0));
+
+    // Double
+    let input = [0.0_f64; 256];
+    let mut output = [0.0_f64; 512];
+    processor.process_double(&input, &mut output).unwrap();
+    assert!(output.iter().any(|sample| *sample != 0.0));
+
+    // Short
+    let input = [0_i16; 256];
+    let mut output = [0_i16; 512];
+    processor.process_short(&input, &mut output).unwrap();
+    assert!(output.iter().any(|sample| *sample != 0));
+
+    // Half a block.
+    let mut output = [0.0_f32; 64];
+    let result = processor.process_float(&[0.0; 32], &mut output);
+    assert!(matches!(
+        result,
+        Err(PdError::AudioProcessingError(
+            AudioProcessingError::PartialBlock {
+                len: 64,
+                block: 128
+            }
+        ))
+    ));
+    assert!(output.iter().all(|sample| *sample == 0.0));
+
+    // The input is shorter than the output.
+    let mut output = [0.0_f32; 512];
+    let result = processor.process_float(&[], &mut output);
+    assert!(matches!(
+        result,
+        Err(PdError::AudioProcessingError(
+            AudioProcessingError::MismatchedBuffers {
+                len: 0,
+                expected: 256
+            }
+        ))
+    ));
+    let result = processor.process_float(&[0.0; 512], &mut output);
+    assert!(matches!(
+        result,
+        Err(PdError::AudioProcessingError(
+            AudioProcessingError::MismatchedBuffers {
+                len: 512,
+                expected: 256
+            }
+        ))
+    ));
+}
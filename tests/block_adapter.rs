@@ -0,0 +1,60 @@
+#![allow(clippy::restriction)]
+
+use libpd_rs::{
+    error::{AudioProcessingError, PdError},
+    Pd,
+};
+
+#[test]
+fn block_adapter() {
+    let mut pd = Pd::init_and_configure(1, 1, 44100).unwrap();
+    let _patch = pd
+        .eval_patch(
+            r#"
+#N canvas 0 50 450 300 12;
+#X obj 20 20 adc~;
+#X obj 20 60 dac~;
+#X connect 0 0 1 0;
+"#,
+        )
+        .unwrap();
+    pd.activate_audio(true).unwrap();
+
+    let mut adapter = pd.audio_context().block_adapter::<f32>();
+    assert_eq!(adapter.latency(), 64);
+
+    // Buffers of frame counts which are not multiples of the block size.
+    let input: Vec<f32> = (0..4410)
+        .map(|index| (index % 1000) as f32 / 1000.0)
+        .collect();
+    let mut output = vec![0.0_f32; input.len()];
+    let mut start = 0;
+    for frames in [441, 1000, 7, 1, 64, 2897] {
+        let range = start..start + frames;
+        adapter
+            .process(&input[range.clone()], &mut output[range])
+            .unwrap();
+        start += frames;
+    }
+    assert_eq!(start, input.len());
+
+    // The output is the input delayed by the latency.
+    assert!(output[..64].iter().all(|sample| *sample == 0.0));
+    assert_eq!(&output[64..], &input[..input.len() - 64]);
+    assert_eq!(adapter.buffered_frames(), 4410 % 64);
+
+    adapter.reset();
+    assert_eq!(adapter.buffered_frames(), 0);
+
+    let result = adapter.process(&[0.0; 10], &mut [0.0; 11]);
+    assert!(matches!(
+        result,
+        Err(PdError::AudioProcessingError(
+            AudioProcessingError::MismatchedBuffers {
+                len: 10,
+                expected: 11
+            }
+        ))
+    ));
+    assert_eq!(adapter.buffered_frames(), 0);
+}
@@ -0,0 +1,105 @@
+#![allow(clippy::restriction)]
+
+use libpd_rs::{
+    functions::{
+        receive::receive_messages_from_pd,
+        send::{send_bang_to, send_float_to},
+    },
+    patch::PatchBuilder,
+    types::PdMessage,
+    Pd,
+};
+
+#[test]
+fn canvas() {
+    let mut pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+
+    let mut builder = PatchBuilder::new();
+    let _comment = builder.text("Boxes are added while the patch runs.");
+    let receive = builder.obj("r float_from_rust");
+    let mut patch = pd.load_patch(&builder).unwrap();
+
+    let messages = pd.message_receiver();
+    pd.subscribe_to("float_from_pd").unwrap();
+
+    let mut canvas = patch.canvas().unwrap();
+    // The boxes which are in the file are counted.
+    assert_eq!(canvas.box_count(), 2);
+
+    let double = canvas.add_object(20, 100, "* 2").unwrap();
+    let send = canvas.add_object(20, 130, "s float_from_pd").unwrap();
+    assert_eq!(double.index(), 2);
+    assert_eq!(send.index(), 3);
+    canvas.connect(receive, 0, double, 0).unwrap();
+    canvas.connect(double, 0, send, 0).unwrap();
+
+    send_float_to("float_from_rust", 21.0).unwrap();
+    receive_messages_from_pd();
+    assert_eq!(
+        messages.try_iter().collect::<Vec<_>>(),
+        vec![PdMessage::Float {
+            source: "float_from_pd".to_owned(),
+            value: 42.0
+        }]
+    );
+
+    // Rewire around the multiplication.
+    canvas.disconnect(receive, 0, double, 0).unwrap();
+    canvas.connect(receive, 0, send, 0).unwrap();
+
+    send_float_to("float_from_rust", 21.0).unwrap();
+    receive_messages_from_pd();
+    assert_eq!(
+        messages.try_iter().collect::<Vec<_>>(),
+        vec![PdMessage::Float {
+            source: "float_from_pd".to_owned(),
+            value: 21.0
+        }]
+    );
+
+    // Clearing removes everything and starts the indices over.
+    canvas.clear().unwrap();
+    assert_eq!(canvas.box_count(), 0);
+    let receive = canvas.add_object(20, 20, "r bang_from_rust").unwrap();
+    let message = canvas.add_message(20, 50, "7").unwrap();
+    let send = canvas.add_object(20, 80, "s float_from_pd").unwrap();
+    assert_eq!(receive.index(), 0);
+    assert_eq!(message.index(), 1);
+    canvas.connect(receive, 0, message, 0).unwrap();
+    canvas.connect(message, 0, send, 0).unwrap();
+
+    assert!(send_float_to("float_from_rust", 21.0).is_err());
+    send_bang_to("bang_from_rust").unwrap();
+    receive_messages_from_pd();
+    assert_eq!(
+        messages.try_iter().collect::<Vec<_>>(),
+        vec![PdMessage::Float {
+            source: "float_from_pd".to_owned(),
+            value: 7.0
+        }]
+    );
+
+    // The count is kept between the canvas handles of the patch.
+    assert_eq!(patch.canvas().unwrap().box_count(), 3);
+
+    // Only the canvas of the patch is edited when the same file is open more than once.
+    let mut copy = pd.open_patch("tests/patches/canvas_copy.pd").unwrap();
+    let _other_copy = pd.open_patch("tests/patches/canvas_copy.pd").unwrap();
+    let mut canvas = copy.canvas().unwrap();
+    let receive = canvas.add_object(20, 20, "r float_from_rust").unwrap();
+    let send = canvas.add_object(20, 50, "s float_from_pd").unwrap();
+    canvas.connect(receive, 0, send, 0).unwrap();
+
+    send_float_to("float_from_rust", 5.0).unwrap();
+    receive_messages_from_pd();
+    assert_eq!(
+        messages.try_iter().collect::<Vec<_>>(),
+        vec![PdMessage::Float {
+            source: "float_from_pd".to_owned(),
+            value: 5.0
+        }]
+    );
+
+    pd.unsubscribe_from_all();
+    patch.close().unwrap();
+}
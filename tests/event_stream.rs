@@ -0,0 +1,69 @@
+#![cfg(feature = "async")]
+#![allow(clippy::restriction)]
+
+use futures::{executor::block_on, StreamExt};
+use libpd_rs::{
+    stream,
+    types::{OutgoingMessage, PdEvent, PdMessage},
+    Pd,
+};
+
+#[test]
+fn event_stream() {
+    let mut pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+
+    let patch = pd.open_patch("tests/patches/echo.pd").unwrap();
+    pd.subscribe_to_many(&["float_from_pd", "symbol_from_pd"])
+        .unwrap();
+
+    let (sender, mut events, mut pump) = stream::channel(&pd.audio_context());
+
+    // Senders can be moved to other threads.
+    let other_sender = sender.clone();
+    std::thread::spawn(move || {
+        other_sender
+            .send(OutgoingMessage::Float {
+                receiver: "float_from_rust".to_owned(),
+                value: 42.0,
+            })
+            .unwrap();
+    })
+    .join()
+    .unwrap();
+    sender
+        .send(OutgoingMessage::Symbol {
+            receiver: "symbol_from_rust".to_owned(),
+            value: "hello".to_owned(),
+        })
+        .unwrap();
+
+    pump.pump().unwrap();
+
+    let received = block_on(async { vec![events.next().await, events.next().await] });
+
+    assert_eq!(
+        received,
+        vec![
+            Some(PdEvent::Message(PdMessage::Float {
+                source: "float_from_pd".to_owned(),
+                value: 42.0
+            })),
+            Some(PdEvent::Message(PdMessage::Symbol {
+                source: "symbol_from_pd".to_owned(),
+                value: "hello".to_owned()
+            })),
+        ]
+    );
+
+    // Sending fails when the pump is gone.
+    drop(pump);
+    assert!(sender
+        .send(OutgoingMessage::Bang {
+            receiver: "bang_from_rust".to_owned(),
+        })
+        .is_err());
+
+    drop(events);
+    pd.unsubscribe_from_all();
+    patch.close().unwrap();
+}
@@ -0,0 +1,38 @@
+#![cfg(feature = "tokio")]
+#![allow(clippy::restriction)]
+
+use futures::StreamExt;
+use libpd_rs::{
+    stream,
+    types::{OutgoingMessage, PdEvent, PdMessage},
+    Pd,
+};
+
+#[tokio::test]
+async fn event_stream_tokio() {
+    let mut pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+
+    let patch = pd.open_patch("tests/patches/echo.pd").unwrap();
+    pd.subscribe_to("bang_from_pd").unwrap();
+
+    let (sender, mut events, pump) = stream::channel(&pd.audio_context());
+    let pump_task = pump.spawn(std::time::Duration::from_millis(5));
+
+    sender
+        .send(OutgoingMessage::Bang {
+            receiver: "bang_from_rust".to_owned(),
+        })
+        .unwrap();
+
+    assert_eq!(
+        events.next().await,
+        Some(PdEvent::Message(PdMessage::Bang {
+            source: "bang_from_pd".to_owned()
+        }))
+    );
+
+    pump_task.abort();
+    drop(events);
+    pd.unsubscribe_from_all();
+    patch.close().unwrap();
+}
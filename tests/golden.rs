@@ -0,0 +1,59 @@
+#![allow(clippy::restriction)]
+
+use std::time::Duration;
+
+use libpd_rs::{
+    error::{IoError, PdError},
+    testing::{Difference, GoldenTest},
+    types::OutgoingMessage,
+    Pd,
+};
+
+#[test]
+fn golden() {
+    let mut pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+
+    // The level changes in the block of the 200th frame, which starts at frame 192.
+    let level = OutgoingMessage::Float {
+        receiver: "level".to_owned(),
+        value: 0.25,
+    };
+    let mut test = GoldenTest::new("tests/patches/level.pd", 10);
+    test.send_at(Duration::from_micros(4536), level);
+    test.assert_matches(&mut pd, "tests/golden/level.wav");
+
+    // Without the message the level stays the same.
+    let comparison = GoldenTest::new("tests/patches/level.pd", 10)
+        .compare(&mut pd, "tests/golden/level.wav")
+        .unwrap();
+    assert!(!comparison.passed());
+    assert_eq!(comparison.frames, 640);
+    assert_eq!(comparison.reference_frames, 640);
+    assert_eq!(
+        comparison.first,
+        Some(Difference {
+            frame: 192,
+            channel: 0,
+            expected: 0.25,
+            actual: 0.5,
+        })
+    );
+    assert!(comparison
+        .to_string()
+        .contains("first difference: 0.25 at frame 192 of channel 0, expected 0.25 but got 0.5"));
+
+    // A longer render does not match either.
+    let comparison = GoldenTest::new("tests/patches/level.pd", 11)
+        .compare(&mut pd, "tests/golden/level.wav")
+        .unwrap();
+    assert!(!comparison.passed());
+    assert!(comparison
+        .to_string()
+        .contains("length: 704 frames but the reference has 640 frames"));
+
+    let result = test.compare(&mut pd, "tests/golden/missing.wav");
+    assert!(matches!(
+        result,
+        Err(PdError::IoError(IoError::PathDoesNotExist(_)))
+    ));
+}
@@ -0,0 +1,94 @@
+#![allow(clippy::restriction)]
+
+use std::{
+    sync::{Arc, Mutex},
+    thread,
+};
+
+use libpd_rs::{
+    functions::{
+        receive::{message_receiver, on_bang, on_double, on_float, receive_messages_from_pd},
+        send::{send_bang_to, send_float_to},
+    },
+    Pd,
+};
+
+#[test]
+fn hook_handle() {
+    let mut pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+
+    let patch = pd.open_patch("tests/patches/echo.pd").unwrap();
+    pd.subscribe_to("bang_from_pd").unwrap();
+
+    let bangs: Arc<Mutex<Vec<&str>>> = Arc::new(Mutex::new(vec![]));
+
+    let bangs_to_fill = bangs.clone();
+    let first_hook = on_bang(move |_| {
+        bangs_to_fill.lock().unwrap().push("first");
+    });
+
+    send_bang_to("bang_from_rust").unwrap();
+    receive_messages_from_pd();
+
+    // The second hook replaces the first one while it is alive.
+    let bangs_to_fill = bangs.clone();
+    let second_hook = on_bang(move |_| {
+        bangs_to_fill.lock().unwrap().push("second");
+    });
+
+    send_bang_to("bang_from_rust").unwrap();
+    receive_messages_from_pd();
+
+    // Dropping it restores the first one.
+    drop(second_hook);
+
+    send_bang_to("bang_from_rust").unwrap();
+    receive_messages_from_pd();
+
+    // Dropping the last one clears the hook.
+    drop(first_hook);
+
+    send_bang_to("bang_from_rust").unwrap();
+    receive_messages_from_pd();
+
+    assert_eq!(*bangs.lock().unwrap(), vec!["first", "second", "first"]);
+    // All closures are freed.
+    assert_eq!(Arc::strong_count(&bangs), 1);
+
+    // Float and double hooks share a slot in libpd, dropping one restores the other.
+    pd.subscribe_to("float_from_pd").unwrap();
+    let floats: Arc<Mutex<Vec<f32>>> = Arc::new(Mutex::new(vec![]));
+    let floats_to_fill = floats.clone();
+    let float_hook = on_float(move |_, value| {
+        floats_to_fill.lock().unwrap().push(value);
+    });
+
+    let receiver = message_receiver();
+    send_float_to("float_from_rust", 1.0).unwrap();
+    receive_messages_from_pd();
+    assert_eq!(receiver.try_iter().count(), 1);
+    drop(receiver);
+
+    let double_hook = on_double(|_, _| {});
+    drop(double_hook);
+
+    send_float_to("float_from_rust", 2.0).unwrap();
+    receive_messages_from_pd();
+    assert_eq!(*floats.lock().unwrap(), vec![2.0]);
+    drop(float_hook);
+
+    // A handle which is dropped in another thread keeps its closure until the queue is drained again,
+    // a drain in progress may still be calling it.
+    let dropped = Arc::new(());
+    let dropped_in_closure = dropped.clone();
+    let hook = on_bang(move |_| {
+        let _alive = &dropped_in_closure;
+    });
+    thread::spawn(move || drop(hook)).join().unwrap();
+    assert_eq!(Arc::strong_count(&dropped), 2);
+    receive_messages_from_pd();
+    assert_eq!(Arc::strong_count(&dropped), 1);
+
+    pd.unsubscribe_from_all();
+    patch.close().unwrap();
+}
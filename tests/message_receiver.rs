@@ -0,0 +1,96 @@
+#![allow(clippy::restriction)]
+
+use std::sync::mpsc;
+
+use libpd_rs::{
+    functions::{
+        block_size,
+        send::{send_bang_to, send_float_to, send_list_to, send_symbol_to},
+    },
+    types::PdMessage,
+    Atom, Pd,
+};
+
+#[test]
+fn message_receiver() {
+    let sample_rate = 44100;
+    let output_channels = 2;
+
+    let mut pd = Pd::init_and_configure(0, output_channels, sample_rate).unwrap();
+    let ctx = pd.audio_context();
+
+    pd.activate_audio(true).unwrap();
+    let patch = pd.open_patch("tests/patches/echo.pd").unwrap();
+
+    let messages = pd.message_receiver();
+    pd.subscribe_to_many(&[
+        "float_from_pd",
+        "symbol_from_pd",
+        "bang_from_pd",
+        "list_from_pd",
+    ])
+    .unwrap();
+
+    let (tx, rx) = mpsc::channel::<()>();
+
+    let handle = std::thread::spawn(move || {
+        // Mimic audio callback buffers.
+        let input_buffer = [0.0f32; 512];
+        let mut output_buffer = [0.0f32; 1024];
+
+        // Run pd
+        loop {
+            // Mimic an audio callback.
+            let approximate_buffer_duration =
+                (output_buffer.len() as f32 / sample_rate as f32) * 1000.0;
+            std::thread::sleep(std::time::Duration::from_millis(
+                approximate_buffer_duration as u64,
+            ));
+
+            ctx.receive_messages_from_pd();
+            let ticks = output_buffer.len() as i32 / (block_size() * output_channels);
+            ctx.process_float(ticks, &input_buffer, &mut output_buffer);
+            match rx.try_recv() {
+                Ok(_) => break,
+                _ => continue,
+            }
+        }
+    });
+
+    send_float_to("float_from_rust", 42.0).unwrap();
+    send_symbol_to("symbol_from_rust", "hello").unwrap();
+    send_bang_to("bang_from_rust").unwrap();
+    send_list_to("list_from_rust", &[Atom::from(1.0), Atom::from("world")]).unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(50));
+
+    // Stop pd.
+    tx.send(()).unwrap();
+    handle.join().unwrap();
+
+    let received: Vec<PdMessage> = messages.try_iter().collect();
+
+    assert_eq!(
+        received,
+        vec![
+            PdMessage::Float {
+                source: "float_from_pd".to_owned(),
+                value: 42.0
+            },
+            PdMessage::Symbol {
+                source: "symbol_from_pd".to_owned(),
+                value: "hello".to_owned()
+            },
+            PdMessage::Bang {
+                source: "bang_from_pd".to_owned()
+            },
+            PdMessage::List {
+                source: "list_from_pd".to_owned(),
+                values: vec![Atom::from(1.0), Atom::from("world")]
+            },
+        ]
+    );
+    assert_eq!(received[0].source(), "float_from_pd");
+
+    pd.unsubscribe_from_all();
+    patch.close().unwrap();
+}
@@ -0,0 +1,133 @@
+#![allow(clippy::restriction)]
+
+use libpd_rs::{
+    error::MidiError,
+    functions::send::send_midi,
+    midi::{Channel, MidiMessage, U14, U7},
+    types::{OutgoingMessage, PdEvent},
+    Pd,
+};
+
+#[test]
+fn values_are_range_checked() {
+    assert_eq!(U7::new(127).unwrap().value(), 127);
+    assert_eq!(
+        U7::new(128),
+        Err(MidiError::OutOfRange {
+            value: 128,
+            max: 127
+        })
+    );
+    assert!(U7::try_from(-1).is_err());
+    assert_eq!(U14::try_from(16383).unwrap(), U14::MAX);
+    assert!(U14::new(16384).is_err());
+    assert!(Channel::new(16).is_err());
+}
+
+#[test]
+fn channels_encode_ports() {
+    let channel = Channel::on_port(2, 3).unwrap();
+    assert_eq!(channel.raw(), 35);
+    assert_eq!(Channel::try_from(35).unwrap(), channel);
+    assert_eq!(channel.port(), 2);
+    assert_eq!(channel.channel(), 3);
+    assert!(Channel::try_from(-1).is_err());
+    assert!(Channel::try_from(4096).is_err());
+}
+
+#[test]
+fn pitch_bend_splits_into_bytes() {
+    let bend = U14::new(0x2F42).unwrap();
+    assert_eq!(bend.lsb().value(), 0x42);
+    assert_eq!(bend.msb().value(), 0x5E);
+    assert_eq!(U14::from_bytes(bend.lsb(), bend.msb()), bend);
+    assert_eq!(U14::CENTER.bend(), 0);
+    assert_eq!(U14::MIN.bend(), -8192);
+    assert_eq!(U14::MAX.bend(), 8191);
+}
+
+#[test]
+fn messages_round_trip_through_bytes() {
+    let messages: [&[u8]; 7] = [
+        &[0x80, 60, 0],
+        &[0x91, 60, 100],
+        &[0xA2, 60, 10],
+        &[0xB3, 7, 127],
+        &[0xC4, 42],
+        &[0xD5, 64],
+        &[0xEF, 0x00, 0x40],
+    ];
+    for bytes in messages {
+        let message = MidiMessage::try_from(bytes).unwrap();
+        assert_eq!(Vec::from(message), bytes);
+    }
+
+    let bend = MidiMessage::try_from(&[0xEF, 0x00, 0x40][..]).unwrap();
+    assert_eq!(
+        bend,
+        MidiMessage::PitchBend {
+            channel: Channel::new(15).unwrap(),
+            value: U14::CENTER
+        }
+    );
+}
+
+#[test]
+fn invalid_bytes_are_rejected() {
+    for bytes in [
+        &[][..],
+        &[0x90, 60][..],
+        &[0x90, 60, 100, 1][..],
+        &[0x90, 60, 128][..],
+        &[0xF0, 1, 2][..],
+        &[60, 100][..],
+    ] {
+        assert_eq!(
+            MidiMessage::try_from(bytes),
+            Err(MidiError::InvalidMessage(bytes.to_vec()))
+        );
+    }
+}
+
+#[test]
+fn note_off_is_sent_as_note_on_without_velocity() {
+    let channel = Channel::new(1).unwrap();
+    let pitch = U7::new(60).unwrap();
+    let message = MidiMessage::NoteOff {
+        channel,
+        pitch,
+        velocity: U7::MAX,
+    };
+    assert_eq!(
+        OutgoingMessage::from(message),
+        OutgoingMessage::NoteOn {
+            channel,
+            pitch,
+            velocity: U7::MIN
+        }
+    );
+}
+
+#[test]
+fn send_midi_to_pd() {
+    let mut pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+    let patch = pd.open_patch("tests/patches/echo.pd").unwrap();
+
+    let channel = Channel::on_port(1, 2).unwrap();
+    let message = MidiMessage::ControlChange {
+        channel,
+        controller: U7::new(7).unwrap(),
+        value: U7::new(100).unwrap(),
+    };
+    send_midi(message).unwrap();
+
+    let mut events = vec![];
+    pd.poll_events(&mut events).unwrap();
+    assert!(events.contains(&PdEvent::ControlChange {
+        channel,
+        controller: U7::new(7).unwrap(),
+        value: U7::new(100).unwrap(),
+    }));
+
+    patch.close().unwrap();
+}
@@ -0,0 +1,161 @@
+#![allow(clippy::restriction)]
+
+use std::time::Duration;
+
+use libpd_rs::{
+    error::MidiError,
+    midi::{Channel, MidiFile, MidiMessage, MidiPlayer, U7},
+    Pd,
+};
+
+fn smf(format: u16, division: [u8; 2], tracks: &[&[u8]]) -> Vec<u8> {
+    let mut bytes = b"MThd".to_vec();
+    bytes.extend(6u32.to_be_bytes());
+    bytes.extend(format.to_be_bytes());
+    bytes.extend((tracks.len() as u16).to_be_bytes());
+    bytes.extend(division);
+    for track in tracks {
+        bytes.extend(b"MTrk");
+        bytes.extend((track.len() as u32).to_be_bytes());
+        bytes.extend(*track);
+    }
+    bytes
+}
+
+/// Two tracks at 480 ticks per quarter note, the tempo halves after the first quarter note.
+fn song() -> Vec<u8> {
+    let tempo_map: &[u8] = &[
+        0x00, 0xFF, 0x51, 0x03, 0x07, 0xA1, 0x20, // 120 bpm
+        0x83, 0x60, 0xFF, 0x51, 0x03, 0x0F, 0x42, 0x40, // 60 bpm
+        0x00, 0xFF, 0x2F, 0x00,
+    ];
+    let notes: &[u8] = &[
+        0x00, 0x90, 0x3C, 0x64, // note on
+        0x83, 0x60, 0x3C, 0x00, // running status, note on without velocity
+        0x83, 0x60, 0x80, 0x3C, 0x40, // note off
+        0x00, 0xFF, 0x2F, 0x00,
+    ];
+    smf(1, [0x01, 0xE0], &[tempo_map, notes])
+}
+
+fn note(velocity: u8) -> MidiMessage {
+    MidiMessage::NoteOn {
+        channel: Channel::new(0).unwrap(),
+        pitch: U7::new(60).unwrap(),
+        velocity: U7::new(velocity).unwrap(),
+    }
+}
+
+#[test]
+fn parses_tempo_map_and_running_status() {
+    let file = MidiFile::parse(&song()).unwrap();
+    assert_eq!(file.format(), 1);
+    assert_eq!(file.track_count(), 2);
+    assert_eq!(file.duration(), Duration::from_millis(1500));
+
+    let events: Vec<_> = file
+        .events()
+        .iter()
+        .map(|event| (event.time, event.track, event.message))
+        .collect();
+    assert_eq!(
+        events,
+        [
+            (Duration::ZERO, 1, note(100)),
+            (Duration::from_millis(500), 1, note(0)),
+            (
+                Duration::from_millis(1500),
+                1,
+                MidiMessage::NoteOff {
+                    channel: Channel::new(0).unwrap(),
+                    pitch: U7::new(60).unwrap(),
+                    velocity: U7::new(64).unwrap(),
+                }
+            ),
+        ]
+    );
+}
+
+#[test]
+fn parses_timecode_division() {
+    // 25 frames per second with 40 ticks per frame, a tick is a millisecond.
+    let track: &[u8] = &[0x87, 0x68, 0xC0, 0x05, 0x00, 0xFF, 0x2F, 0x00];
+    let file = MidiFile::parse(&smf(0, [0xE7, 0x28], &[track])).unwrap();
+    assert_eq!(file.events().len(), 1);
+    assert_eq!(file.events()[0].time, Duration::from_secs(1));
+    assert_eq!(
+        file.events()[0].message,
+        MidiMessage::ProgramChange {
+            channel: Channel::new(0).unwrap(),
+            program: U7::new(5).unwrap(),
+        }
+    );
+}
+
+#[test]
+fn rejects_invalid_files() {
+    assert_eq!(
+        MidiFile::parse(&smf(2, [0x01, 0xE0], &[])),
+        Err(MidiError::UnsupportedFormat(2))
+    );
+    let song = song();
+    assert!(matches!(
+        MidiFile::parse(&song[..song.len() - 4]),
+        Err(MidiError::InvalidFile(_))
+    ));
+    assert!(matches!(
+        MidiFile::parse(b"RIFF"),
+        Err(MidiError::InvalidFile(_))
+    ));
+    let missing_status: &[u8] = &[0x00, 0x3C, 0x64];
+    assert!(matches!(
+        MidiFile::parse(&smf(0, [0x01, 0xE0], &[missing_status])),
+        Err(MidiError::InvalidFile(_))
+    ));
+    // Meta events cancel the running status.
+    let status_after_meta: &[u8] = &[
+        0x00, 0x90, 0x3C, 0x64, // note on
+        0x00, 0xFF, 0x01, 0x00, // empty text
+        0x00, 0x3C, 0x00, // running status
+    ];
+    assert!(matches!(
+        MidiFile::parse(&smf(0, [0x01, 0xE0], &[status_after_meta])),
+        Err(MidiError::InvalidFile(_))
+    ));
+}
+
+#[test]
+fn player_schedules_loops_and_seeks() {
+    let pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+    let file = MidiFile::parse(&song()).unwrap();
+
+    // The notes are at frames 0, 22050 and 66150.
+    let mut scheduler = pd.audio_context().scheduler(16);
+    let mut player = MidiPlayer::new(&file, 44100).unwrap();
+    player.schedule(&mut scheduler, 22050).unwrap();
+    assert_eq!(scheduler.pending(), 1);
+    player.schedule(&mut scheduler, 44100).unwrap();
+    assert_eq!(scheduler.pending(), 2);
+    assert_eq!(player.position(), Duration::from_millis(1500));
+    assert!(!player.is_finished());
+    player.schedule(&mut scheduler, 1).unwrap();
+    assert_eq!(scheduler.pending(), 3);
+    assert!(player.is_finished());
+
+    // The file starts again after its end.
+    let mut scheduler = pd.audio_context().scheduler(16);
+    let mut player = MidiPlayer::new(&file, 44100).unwrap();
+    player.set_looping(true);
+    player.schedule(&mut scheduler, 66160).unwrap();
+    assert_eq!(scheduler.pending(), 4);
+    assert_eq!(player.position(), Duration::from_nanos(226_757));
+
+    // The sounding note is released when seeking.
+    let mut scheduler = pd.audio_context().scheduler(16);
+    let mut player = MidiPlayer::new(&file, 44100).unwrap();
+    player.schedule(&mut scheduler, 1).unwrap();
+    player.seek(Duration::from_secs(1)).unwrap();
+    player.schedule(&mut scheduler, 1).unwrap();
+    assert_eq!(scheduler.pending(), 2);
+    assert_eq!(player.position(), Duration::from_nanos(1_000_022_675));
+}
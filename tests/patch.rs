@@ -0,0 +1,64 @@
+#![allow(clippy::restriction)]
+
+use libpd_rs::{
+    functions::{receive::receive_messages_from_pd, send::send_float_to},
+    types::PdMessage,
+    Pd,
+};
+
+#[test]
+fn multiple_open_patches() {
+    let mut pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+
+    let sine = pd.open_patch("tests/patches/sine.pd").unwrap();
+    let echo = pd.open_patch("tests/patches/echo.pd").unwrap();
+    let evaluated = pd
+        .eval_patch(
+            r#"
+    #N canvas 0 50 450 300 12;
+    #X obj 20 20 r float_from_rust;
+    #X obj 20 60 s float_from_evaluated;
+    #X connect 0 0 1 0;
+        "#,
+        )
+        .unwrap();
+
+    assert_ne!(sine.dollar_zero(), echo.dollar_zero());
+    assert_ne!(echo.dollar_zero(), evaluated.dollar_zero());
+    assert!(sine.handle().is_some());
+    assert!(sine.path().ends_with("sine.pd"));
+    // Relative paths are resolved to the file which is opened.
+    assert!(sine.path().is_absolute());
+    assert!(sine.path().exists());
+    assert!(evaluated.path().exists());
+
+    let messages = pd.message_receiver();
+    pd.subscribe_to_many(&["float_from_pd", "float_from_evaluated"])
+        .unwrap();
+
+    // Both patches respond while they are open.
+    send_float_to("float_from_rust", 1.0).unwrap();
+    receive_messages_from_pd();
+    let received: Vec<PdMessage> = messages.try_iter().collect();
+    assert_eq!(received.len(), 2);
+
+    // Closing one of them leaves the other one running.
+    let evaluated_path = evaluated.path().to_path_buf();
+    drop(evaluated);
+    assert!(!evaluated_path.exists());
+
+    send_float_to("float_from_rust", 2.0).unwrap();
+    receive_messages_from_pd();
+    let received: Vec<PdMessage> = messages.try_iter().collect();
+    assert_eq!(
+        received,
+        vec![PdMessage::Float {
+            source: "float_from_pd".to_owned(),
+            value: 2.0
+        }]
+    );
+
+    pd.unsubscribe_from_all();
+    assert!(echo.close().is_ok());
+    assert!(pd.close_patch(sine).is_ok());
+}
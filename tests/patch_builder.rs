@@ -0,0 +1,89 @@
+#![allow(clippy::restriction)]
+
+use libpd_rs::{
+    functions::{receive::receive_messages_from_pd, send::send_float_to},
+    patch::{
+        file::{GuiKind, PatchFile},
+        PatchBuilder,
+    },
+    types::PdMessage,
+    Pd,
+};
+
+#[test]
+fn render() {
+    let mut inner = PatchBuilder::new();
+    let inlet = inner.obj("inlet");
+    let outlet = inner.obj("outlet");
+    inner.connect(inlet, 0, outlet, 0);
+
+    let mut builder = PatchBuilder::new();
+    let receive = builder.obj("r $0-in");
+    let comment = builder.text("Passes the values, as they are");
+    let through = builder.subpatch("through", inner);
+    let toggle = builder.at(200, 20).gui(GuiKind::Toggle, "");
+    let message = builder.msg("1 8, 0 0 10");
+    builder
+        .connect(receive, 0, through, 0)
+        .connect(through, 0, toggle, 0)
+        .connect(toggle, 0, message, 0);
+
+    assert_eq!(receive.index(), 0);
+    assert_eq!(comment.index(), 1);
+    assert_eq!(through.index(), 2);
+    assert_eq!(message.index(), 4);
+
+    assert_eq!(
+        builder.to_string(),
+        "#N canvas 0 50 450 300 12;
+#X obj 20 20 r \\$0-in;
+#X text 20 50 Passes the values \\, as they are;
+#N canvas 0 50 450 300 through 0;
+#X obj 20 20 inlet;
+#X obj 20 50 outlet;
+#X connect 0 0 1 0;
+#X restore 20 80 pd through;
+#X obj 200 20 tgl;
+#X msg 200 50 1 8 \\, 0 0 10;
+#X connect 0 0 2 0;
+#X connect 2 0 3 0;
+#X connect 3 0 4 0;
+"
+    );
+    // The rendered text parses back to the same contents.
+    assert_eq!(
+        &builder.to_string().parse::<PatchFile>().unwrap(),
+        builder.file()
+    );
+}
+
+#[test]
+fn load_patch() {
+    let mut pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+
+    let mut builder = PatchBuilder::new();
+    let receive = builder.obj("r float_from_rust");
+    let double = builder.obj("* 2");
+    let send = builder.obj("s float_from_pd");
+    builder
+        .connect(receive, 0, double, 0)
+        .connect(double, 0, send, 0);
+
+    let patch = pd.load_patch(&builder).unwrap();
+    let messages = pd.message_receiver();
+    pd.subscribe_to("float_from_pd").unwrap();
+
+    send_float_to("float_from_rust", 21.0).unwrap();
+    receive_messages_from_pd();
+
+    assert_eq!(
+        messages.try_iter().collect::<Vec<_>>(),
+        vec![PdMessage::Float {
+            source: "float_from_pd".to_owned(),
+            value: 42.0
+        }]
+    );
+
+    pd.unsubscribe_from_all();
+    patch.close().unwrap();
+}
@@ -0,0 +1,267 @@
+#![allow(clippy::restriction)]
+
+use std::fs;
+
+use libpd_rs::{
+    error::PatchFileError,
+    patch::file::{
+        Array, ArrayData, Canvas, Comment, Connection, Entry, GuiKind, Object, PatchFile,
+    },
+};
+
+fn corpus() -> Vec<String> {
+    ["tests/patches", "examples/with_nannou/patches"]
+        .iter()
+        .flat_map(|dir| fs::read_dir(dir).unwrap())
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|extension| extension == "pd"))
+        .map(|path| fs::read_to_string(path).unwrap())
+        .collect()
+}
+
+#[test]
+fn round_trip() {
+    let corpus = corpus();
+    assert!(corpus.len() >= 9);
+    for contents in corpus {
+        let patch: PatchFile = contents.parse().unwrap();
+        assert_eq!(patch.to_string(), contents);
+        // Parsing the written contents gives the same tree.
+        assert_eq!(patch.to_string().parse::<PatchFile>().unwrap(), patch);
+    }
+}
+
+#[test]
+fn typed_entries() {
+    let patch: PatchFile = fs::read_to_string("tests/patches/structure.pd")
+        .unwrap()
+        .parse()
+        .unwrap();
+    assert_eq!(patch.font, 12);
+    assert_eq!(
+        (
+            patch.canvas.x,
+            patch.canvas.y,
+            patch.canvas.width,
+            patch.canvas.height
+        ),
+        (200, 100, 640, 480)
+    );
+
+    let entries = &patch.canvas.entries;
+    assert_eq!(entries.len(), 16);
+
+    let Entry::Object(oscillator) = &entries[0] else {
+        panic!("Expected an object.");
+    };
+    assert_eq!(oscillator.class, "osc~");
+    assert_eq!(oscillator.args, vec!["440"]);
+
+    let Entry::Message(message) = &entries[3] else {
+        panic!("Expected a message.");
+    };
+    assert_eq!(message.content, vec!["1", "8", ",", "0", "0", "10"]);
+
+    let Entry::Comment(comment) = &entries[4] else {
+        panic!("Expected a comment.");
+    };
+    assert_eq!(
+        comment.content,
+        vec!["A", "comment", ";", "with", "a", "semicolon"]
+    );
+
+    let kinds: Vec<GuiKind> = entries
+        .iter()
+        .filter_map(|entry| match entry {
+            Entry::Gui(gui) => Some(gui.kind),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        kinds,
+        vec![
+            GuiKind::Bang,
+            GuiKind::Toggle,
+            GuiKind::FloatAtom,
+            GuiKind::SymbolAtom
+        ]
+    );
+
+    let Entry::Subpatch(graph) = &entries[9] else {
+        panic!("Expected a graph.");
+    };
+    assert_eq!(graph.name, "(subpatch)");
+    assert_eq!(graph.content, vec!["graph"]);
+    let Entry::Array(array) = &graph.canvas.entries[0] else {
+        panic!("Expected an array.");
+    };
+    assert_eq!(array.name, "table");
+    assert_eq!(array.size, 8);
+    assert_eq!(array.data[0].start, 0);
+    assert_eq!(
+        array.data[0].values,
+        vec![0.0, 0.1, 0.2, 0.3, -0.4, 0.5, 0.6, 0.7]
+    );
+    assert!(matches!(graph.canvas.entries[1], Entry::Record(_)));
+
+    let Entry::Subpatch(inner) = &entries[10] else {
+        panic!("Expected a subpatch.");
+    };
+    assert_eq!(inner.name, "inner");
+    assert!(!inner.open);
+    assert_eq!(inner.content, vec!["pd", "inner"]);
+    assert_eq!(inner.canvas.entries.len(), 5);
+    let Entry::Object(add) = &inner.canvas.entries[1] else {
+        panic!("Expected an object.");
+    };
+    assert_eq!(add.args, vec!["$1"]);
+
+    let Entry::Object(receive) = &entries[11] else {
+        panic!("Expected an object.");
+    };
+    assert_eq!(receive.args, vec!["$0-in"]);
+    assert_eq!(receive.width, Some(12));
+
+    let Entry::Connection(connection) = &entries[15] else {
+        panic!("Expected a connection.");
+    };
+    assert_eq!(
+        (
+            connection.source,
+            connection.outlet,
+            connection.sink,
+            connection.inlet
+        ),
+        (11, 0, 1, 1)
+    );
+}
+
+#[test]
+fn edit() {
+    let contents = fs::read_to_string("tests/patches/structure.pd").unwrap();
+    let mut patch: PatchFile = contents.parse().unwrap();
+
+    let Entry::Subpatch(graph) = &mut patch.canvas.entries[9] else {
+        panic!("Expected a graph.");
+    };
+    let Entry::Array(array) = &mut graph.canvas.entries[0] else {
+        panic!("Expected an array.");
+    };
+    array.data[0].values[1] = 0.25;
+    let Entry::Object(oscillator) = &mut patch.canvas.entries[0] else {
+        panic!("Expected an object.");
+    };
+    oscillator.args = vec!["220".to_owned()];
+
+    let edited = contents
+        .replace("osc~ 440", "osc~ 220")
+        .replace("#A 0 0 0.1 0.2", "#A 0 0 0.25 0.2");
+    assert_eq!(patch.to_string(), edited);
+}
+
+#[test]
+fn build() {
+    let mut canvas = Canvas::new(0, 50, 450, 300);
+    canvas.entries.push(Entry::Object(Object::new(
+        20,
+        20,
+        "r",
+        vec!["$0-in".to_owned()],
+    )));
+    let mut array = Array::new("table", 3);
+    array.data.push(ArrayData::new(0, vec![0.5, -1.0, 0.25]));
+    canvas.entries.push(Entry::Array(array));
+    canvas
+        .entries
+        .push(Entry::Connection(Connection::new(0, 0, 1, 0)));
+
+    assert_eq!(
+        PatchFile::new(canvas).to_string(),
+        "#N canvas 0 50 450 300 12;
+#X obj 20 20 r \\$0-in;
+#X array table 3 float 3;
+#A 0 0.5 -1 0.25;
+#X connect 0 0 1 0;
+"
+    );
+}
+
+#[test]
+fn empty_object_box() {
+    let contents = "#N canvas 0 50 450 300 12;
+#X obj 50 50;
+#X obj 50 100 print;
+#X connect 0 0 1 0;
+";
+    let patch: PatchFile = contents.parse().unwrap();
+    assert_eq!(patch.to_string(), contents);
+
+    // The empty box keeps its index so the connection still refers to the boxes around it.
+    let entries = &patch.canvas.entries;
+    assert!(entries.iter().take(2).all(Entry::is_box));
+    let Entry::Object(empty) = &entries[0] else {
+        panic!("Expected an object.");
+    };
+    assert_eq!((empty.x, empty.y), (50, 50));
+    assert!(empty.class.is_empty());
+    assert!(empty.args.is_empty());
+
+    let mut canvas = Canvas::new(0, 50, 450, 300);
+    canvas
+        .entries
+        .push(Entry::Object(Object::new(50, 50, "", vec![])));
+    assert_eq!(
+        PatchFile::new(canvas).to_string(),
+        "#N canvas 0 50 450 300 12;\n#X obj 50 50;\n"
+    );
+}
+
+#[test]
+fn wraps_long_records_like_pd() {
+    let contents = fs::read_to_string("examples/with_nannou/patches/bubble_voice.pd").unwrap();
+    let patch: PatchFile = contents.parse().unwrap();
+
+    // Writing an unedited comment with a new layout gives the same text pd saved.
+    let original = patch
+        .canvas
+        .entries
+        .iter()
+        .find_map(|entry| match entry {
+            Entry::Comment(comment) if comment.width == Some(147) => Some(comment),
+            _ => None,
+        })
+        .unwrap();
+    let mut comment = Comment::new(original.x, original.y, original.content.clone());
+    comment.width = original.width;
+
+    let mut canvas = Canvas::new(0, 0, 0, 0);
+    canvas.entries.push(Entry::Comment(comment));
+    let written = PatchFile::new(canvas).to_string();
+    let record = written.split_once(";\n").unwrap().1;
+    assert!(contents.contains(record));
+    assert!(record.contains('\n'));
+}
+
+#[test]
+fn errors() {
+    assert!(matches!(
+        "".parse::<PatchFile>(),
+        Err(PatchFileError::MissingCanvas)
+    ));
+    assert!(matches!(
+        "#N canvas 0 50 450 300 12;\n#X obj 20 20 osc~".parse::<PatchFile>(),
+        Err(PatchFileError::UnterminatedRecord(_))
+    ));
+    assert!(matches!(
+        "#N canvas 0 50 450 300 12;\n#N canvas 0 50 450 300 sub 0;\n".parse::<PatchFile>(),
+        Err(PatchFileError::UnclosedSubpatch(name)) if name == "sub"
+    ));
+    assert!(matches!(
+        "#N canvas 0 50 450 300 12;\n#X restore 0 0 pd sub;\n".parse::<PatchFile>(),
+        Err(PatchFileError::UnexpectedRestore(_))
+    ));
+    assert!(matches!(
+        "#N canvas 0 50 wide 300 12;\n".parse::<PatchFile>(),
+        Err(PatchFileError::InvalidRecord(_))
+    ));
+}
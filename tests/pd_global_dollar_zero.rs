@@ -2,10 +2,14 @@ use libpd_rs::Pd;
 
 #[test]
 fn dollar_zero() {
-    let mut pd = Pd::init_and_configure(0, 2, 44100).unwrap();
-    pd.open_patch("tests/patches/sine.pd").unwrap();
-    assert!(pd.dollar_zero().is_ok());
-    assert_ne!(pd.dollar_zero().unwrap(), 0);
-    pd.close_patch().unwrap();
-    assert!(pd.dollar_zero().is_err());
+    let pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+    let patch = pd.open_patch("tests/patches/sine.pd").unwrap();
+    let dollar_zero = patch.dollar_zero();
+    assert_ne!(dollar_zero, 0);
+    patch.close().unwrap();
+
+    // A patch opened later gets a new `$0`.
+    let patch = pd.open_patch("tests/patches/sine.pd").unwrap();
+    assert_ne!(patch.dollar_zero(), dollar_zero);
+    patch.close().unwrap();
 }
@@ -3,7 +3,7 @@ use libpd_rs::Pd;
 #[test]
 fn state() {
     let mut pd = Pd::init_and_configure(0, 2, 44100).unwrap();
-    pd.open_patch("tests/patches/sine.pd").unwrap();
+    let _patch = pd.open_patch("tests/patches/sine.pd").unwrap();
     assert!(!pd.audio_active());
     pd.activate_audio(true).unwrap();
     assert!(pd.audio_active());
@@ -8,16 +8,17 @@ fn open_close_patch() {
     let mut pd = Pd::init_and_configure(0, 2, 44100).unwrap();
     let ctx = pd.audio_context();
 
-    assert!(pd.open_patch("tests/patches/sine.pd").is_ok());
+    let patch = pd.open_patch("tests/patches/sine.pd").unwrap();
+    assert!(pd.close_patch(patch).is_ok());
 
-    // Re-opens.
-    assert!(pd.open_patch("tests/patches/sine.pd").is_ok());
-    assert!(pd.close_patch().is_ok());
+    // Opens it again.
+    let patch = pd.open_patch("tests/patches/sine.pd").unwrap();
+    assert!(patch.close().is_ok());
 
     assert!(pd.open_patch("non existent").is_err());
 
     // Runs osc~ on 440Hz.
-    assert!(pd
+    let patch = pd
         .eval_patch(
             r#"
     #N canvas 577 549 158 168 12;
@@ -31,9 +32,9 @@ fn open_close_patch() {
     #X connect 3 0 0 1;
         "#,
         )
-        .is_ok());
+        .unwrap();
 
-    assert!(pd.dollar_zero().is_ok());
+    assert_ne!(patch.dollar_zero(), 0);
 
     let output_channels = pd.output_channels();
     let sample_rate = pd.sample_rate();
@@ -86,5 +87,5 @@ fn open_close_patch() {
     handle.join().unwrap();
 
     assert!(sum.load(std::sync::atomic::Ordering::SeqCst) != 0);
-    assert!(pd.close_patch().is_ok());
+    assert!(patch.close().is_ok());
 }
@@ -0,0 +1,66 @@
+#![allow(clippy::restriction)]
+
+use std::thread;
+
+use libpd_rs::{error::SendError, types::OutgoingMessage, Pd};
+
+fn level(value: f64) -> OutgoingMessage {
+    OutgoingMessage::Float {
+        receiver: "level".to_owned(),
+        value,
+    }
+}
+
+#[test]
+fn pd_sender() {
+    let mut pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+    let _patch = pd.open_patch("tests/patches/level.pd").unwrap();
+    pd.activate_audio(true).unwrap();
+
+    let (ctx, sender) = pd.audio_context_with_sender(4);
+    assert_eq!(sender.capacity(), 4);
+
+    let senders: Vec<_> = (0..2)
+        .map(|_| {
+            let sender = sender.clone();
+            thread::spawn(move || sender.send(level(0.25)).unwrap())
+        })
+        .collect();
+    for handle in senders {
+        handle.join().unwrap();
+    }
+    assert_eq!(sender.len(), 2);
+
+    // The messages are sent on the audio thread before processing.
+    let audio = thread::spawn(move || {
+        let mut output = [0.0_f32; 128];
+        ctx.process_float(1, &[], &mut output);
+        (ctx, output)
+    });
+    let (ctx, output) = audio.join().unwrap();
+    assert!(output.iter().all(|sample| *sample == 0.25));
+    assert!(sender.is_empty());
+
+    // The queue does not grow.
+    for value in 0..4 {
+        sender.send(level(f64::from(value) / 4.0)).unwrap();
+    }
+    assert!(matches!(sender.send(level(1.0)), Err(SendError::QueueFull)));
+
+    // Messages to receivers which do not exist are counted.
+    let mut output = [0.0_f32; 128];
+    ctx.process_float(1, &[], &mut output);
+    assert!(output.iter().all(|sample| *sample == 0.75));
+    sender
+        .send(OutgoingMessage::Bang {
+            receiver: "nowhere".to_owned(),
+        })
+        .unwrap();
+    ctx.processor().process_float(&[], &mut output).unwrap();
+    assert_eq!(sender.failed(), 1);
+
+    // Contexts without a sender leave the queue alone.
+    sender.send(level(0.5)).unwrap();
+    pd.audio_context().process_float(1, &[], &mut output);
+    assert_eq!(sender.len(), 1);
+}
@@ -0,0 +1,49 @@
+#![allow(clippy::restriction)]
+
+use std::sync::{Arc, Mutex};
+
+use libpd_rs::{functions::send::send_float_to, Pd};
+
+#[test]
+fn per_instance_hooks() {
+    let mut pd_a = Pd::init_and_configure(0, 2, 44100).unwrap();
+    let mut pd_b = Pd::init_and_configure(0, 2, 44100).unwrap();
+
+    let patch_a = pd_a.open_patch("tests/patches/echo.pd").unwrap();
+    let patch_b = pd_b.open_patch("tests/patches/echo.pd").unwrap();
+    pd_a.subscribe_to("float_from_pd").unwrap();
+    pd_b.subscribe_to("float_from_pd").unwrap();
+
+    let floats_a: Arc<Mutex<Vec<f64>>> = Arc::new(Mutex::new(vec![]));
+    let floats_b: Arc<Mutex<Vec<f64>>> = Arc::new(Mutex::new(vec![]));
+
+    // Register while the other instance is current to make sure it doesn't matter.
+    pd_b.set_as_current();
+    let floats_to_fill = floats_a.clone();
+    let _hook_a = pd_a.on_double(move |_, value| {
+        floats_to_fill.lock().unwrap().push(value);
+    });
+    pd_a.set_as_current();
+    let floats_to_fill = floats_b.clone();
+    let _hook_b = pd_b.on_double(move |_, value| {
+        floats_to_fill.lock().unwrap().push(value);
+    });
+
+    pd_a.set_as_current();
+    send_float_to("float_from_rust", 1.0).unwrap();
+    pd_b.set_as_current();
+    send_float_to("float_from_rust", 2.0).unwrap();
+    send_float_to("float_from_rust", 3.0).unwrap();
+
+    // Drain both queues while the other instance is current.
+    pd_a.set_as_current();
+    pd_b.inner().receive_messages_from_pd();
+    pd_b.set_as_current();
+    pd_a.inner().receive_messages_from_pd();
+
+    assert_eq!(*floats_a.lock().unwrap(), vec![1.0]);
+    assert_eq!(*floats_b.lock().unwrap(), vec![2.0, 3.0]);
+
+    patch_a.close().unwrap();
+    patch_b.close().unwrap();
+}
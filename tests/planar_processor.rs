@@ -0,0 +1,78 @@
+#![allow(clippy::restriction)]
+
+use libpd_rs::{
+    error::{AudioProcessingError, PdError},
+    Pd,
+};
+
+#[test]
+fn planar_processor() {
+    let mut pd = Pd::init_and_configure(2, 2, 44100).unwrap();
+    // Swaps the channels.
+    let _patch = pd
+        .eval_patch(
+            r#"
+#N canvas 0 50 450 300 12;
+#X obj 20 20 adc~ 1 2;
+#X obj 20 60 dac~ 1 2;
+#X connect 0 0 1 1;
+#X connect 0 1 1 0;
+"#,
+        )
+        .unwrap();
+    pd.activate_audio(true).unwrap();
+
+    let mut processor = pd.audio_context().planar_processor::<f32>();
+
+    let left: Vec<f32> = (0..256).map(|index| index as f32 / 256.0).collect();
+    let right: Vec<f32> = (0..256).map(|index| -index as f32 / 256.0).collect();
+    let mut output = vec![vec![0.0_f32; 256]; 2];
+    processor
+        .process(&[&left[..], &right[..]], &mut output)
+        .unwrap();
+    assert_eq!(output[0], right);
+    assert_eq!(output[1], left);
+
+    // Doubles through the raw double path.
+    let mut processor = pd.audio_context().planar_processor::<f64>();
+    let left = vec![0.5_f64; 128];
+    let right = vec![0.25_f64; 128];
+    let mut output = vec![vec![0.0_f64; 128]; 2];
+    processor.process(&[left, right], &mut output).unwrap();
+    assert!(output[0].iter().all(|sample| *sample == 0.25));
+    assert!(output[1].iter().all(|sample| *sample == 0.5));
+
+    let mut processor = pd.audio_context().planar_processor::<f32>();
+    let result = processor.process(&[[0.0; 64]], &mut [[0.0; 64], [0.0; 64]]);
+    assert!(matches!(
+        result,
+        Err(PdError::AudioProcessingError(
+            AudioProcessingError::ChannelCount {
+                len: 1,
+                expected: 2
+            }
+        ))
+    ));
+
+    let result = processor.process(&[[0.0; 64]; 2], &mut [vec![0.0; 64], vec![0.0; 128]]);
+    assert!(matches!(
+        result,
+        Err(PdError::AudioProcessingError(
+            AudioProcessingError::MismatchedBuffers {
+                len: 128,
+                expected: 64
+            }
+        ))
+    ));
+
+    let result = processor.process(&[[0.0; 100]; 2], &mut [[0.0; 100]; 2]);
+    assert!(matches!(
+        result,
+        Err(PdError::AudioProcessingError(
+            AudioProcessingError::PartialBlock {
+                len: 100,
+                block: 64
+            }
+        ))
+    ));
+}
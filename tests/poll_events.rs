@@ -0,0 +1,104 @@
+#![allow(clippy::restriction)]
+
+use std::sync::{Arc, Mutex};
+
+use libpd_rs::{
+    error::{PdError, SubscriptionError},
+    functions::{
+        receive::receive_messages_from_pd,
+        send::{send_float_to, send_note_on},
+    },
+    midi::{Channel, U7},
+    types::{PdEvent, PdMessage},
+    Pd,
+};
+
+#[test]
+fn poll_events() {
+    let mut pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+
+    let patch = pd
+        .eval_patch(
+            r#"
+    #N canvas 0 50 450 300 12;
+    #X obj 20 20 r float_from_rust;
+    #X obj 20 60 s float_from_pd;
+    #X obj 160 60 print from_rust;
+    #X obj 20 120 notein;
+    #X obj 20 160 noteout;
+    #X connect 0 0 1 0;
+    #X connect 0 0 2 0;
+    #X connect 3 0 4 0;
+    #X connect 3 1 4 1;
+    #X connect 3 2 4 2;
+        "#,
+        )
+        .unwrap();
+    pd.subscribe_to("float_from_pd").unwrap();
+
+    let floats: Arc<Mutex<Vec<f32>>> = Arc::new(Mutex::new(vec![]));
+    let floats_to_fill = floats.clone();
+    let hook = pd.on_float(move |_, value| {
+        floats_to_fill.lock().unwrap().push(value);
+    });
+
+    let mut events = vec![];
+
+    // Polling does not replace the listeners which are registered.
+    assert!(matches!(
+        pd.poll_events(&mut events),
+        Err(PdError::SubscriptionError(
+            SubscriptionError::ListenersAlreadyRegistered
+        ))
+    ));
+    send_float_to("float_from_rust", 1.0).unwrap();
+    receive_messages_from_pd();
+    assert_eq!(*floats.lock().unwrap(), vec![1.0]);
+    drop(hook);
+
+    // Nothing is sent yet.
+    pd.poll_events(&mut events).unwrap();
+    assert!(events.is_empty());
+
+    send_float_to("float_from_rust", 42.0).unwrap();
+    send_note_on(
+        Channel::new(0).unwrap(),
+        U7::new(60).unwrap(),
+        U7::new(100).unwrap(),
+    )
+    .unwrap();
+
+    pd.poll_events(&mut events).unwrap();
+
+    assert!(events.contains(&PdEvent::Message(PdMessage::Float {
+        source: "float_from_pd".to_owned(),
+        value: 42.0
+    })));
+    assert!(events.contains(&PdEvent::Print("from_rust: 42".to_owned())));
+    assert!(events.contains(&PdEvent::NoteOn {
+        channel: Channel::new(0).unwrap(),
+        pitch: U7::new(60).unwrap(),
+        velocity: U7::new(100).unwrap()
+    }));
+
+    // Events are drained.
+    events.clear();
+    pd.poll_events(&mut events).unwrap();
+    assert!(events.is_empty());
+
+    // Stopping frees the slots for the closures of the `on_*` methods.
+    pd.stop_polling_events();
+    let floats_to_fill = floats.clone();
+    let hook = pd.on_float(move |_, value| {
+        floats_to_fill.lock().unwrap().push(value);
+    });
+    send_float_to("float_from_rust", 2.0).unwrap();
+    receive_messages_from_pd();
+    assert_eq!(*floats.lock().unwrap(), vec![1.0, 2.0]);
+    drop(hook);
+    pd.poll_events(&mut events).unwrap();
+    assert!(events.is_empty());
+
+    pd.unsubscribe_from_all();
+    patch.close().unwrap();
+}
@@ -0,0 +1,98 @@
+#![allow(clippy::restriction)]
+
+use std::{io::Cursor, time::Duration};
+
+use hound::{SampleFormat, WavReader, WavSpec, WavWriter};
+use libpd_rs::{
+    audio::{RenderOptions, WavFormat},
+    error::{PdError, WavError},
+    Pd,
+};
+
+#[test]
+fn render_offline() {
+    let mut pd = Pd::init_and_configure(1, 2, 44100).unwrap();
+
+    // A second of the sine patch as 32 bit floats.
+    let patch = pd.open_patch("tests/patches/sine.pd").unwrap();
+    let mut file = Cursor::new(vec![]);
+    pd.render_offline(Duration::from_secs(1), &mut file)
+        .unwrap();
+    assert!(!pd.audio_active());
+    drop(patch);
+
+    file.set_position(0);
+    let reader = WavReader::new(file).unwrap();
+    let spec = reader.spec();
+    assert_eq!(spec.channels, 2);
+    assert_eq!(spec.sample_rate, 44100);
+    assert_eq!(spec.bits_per_sample, 32);
+    assert_eq!(spec.sample_format, SampleFormat::Float);
+    // Not a multiple of the block size.
+    assert_eq!(reader.duration(), 44100);
+    let samples: Vec<f32> = reader.into_samples().map(Result::unwrap).collect();
+    let peak = samples
+        .iter()
+        .fold(0.0_f32, |peak, sample| peak.max(sample.abs()));
+    assert!((peak - 0.1).abs() < 0.001);
+
+    // A wav file played through the patch into 16 bit integers.
+    let _patch = pd
+        .eval_patch(
+            r#"
+#N canvas 0 50 450 300 12;
+#X obj 20 20 adc~;
+#X obj 20 60 dac~;
+#X connect 0 0 1 0;
+#X connect 0 0 1 1;
+"#,
+        )
+        .unwrap();
+    let directory = tempfile::tempdir().unwrap();
+    let input = directory.path().join("input.wav");
+    let spec = WavSpec {
+        channels: 1,
+        sample_rate: 44100,
+        bits_per_sample: 16,
+        sample_format: SampleFormat::Int,
+    };
+    let mut writer = WavWriter::create(&input, spec).unwrap();
+    for index in 0..1000_i16 {
+        writer.write_sample(index * 16).unwrap();
+    }
+    writer.finalize().unwrap();
+
+    let options = RenderOptions {
+        format: WavFormat::Int16,
+        input: Some(input.clone()),
+        ..RenderOptions::default()
+    };
+    let mut file = Cursor::new(vec![]);
+    pd.render_offline_with(Duration::from_millis(100), &options, &mut file)
+        .unwrap();
+
+    file.set_position(0);
+    let reader = WavReader::new(file).unwrap();
+    assert_eq!(reader.spec().bits_per_sample, 16);
+    assert_eq!(reader.duration(), 4410);
+    let samples: Vec<i16> = reader.into_samples().map(Result::unwrap).collect();
+    for (index, frame) in samples.chunks(2).enumerate() {
+        let expected = if index < 1000 { index as i16 * 16 } else { 0 };
+        assert_eq!(frame, [expected, expected]);
+    }
+
+    // The input should have the channels of pd.
+    let spec = WavSpec {
+        channels: 2,
+        ..spec
+    };
+    WavWriter::create(&input, spec).unwrap().finalize().unwrap();
+    let result = pd.render_offline_with(Duration::from_millis(100), &options, Cursor::new(vec![]));
+    assert!(matches!(
+        result,
+        Err(PdError::WavError(WavError::ChannelCount {
+            channels: 2,
+            expected: 1
+        }))
+    ));
+}
@@ -0,0 +1,109 @@
+#![allow(clippy::restriction)]
+
+use std::sync::{Arc, Mutex};
+
+use libpd_rs::{
+    error::{PdError, SubscriptionError},
+    functions::send::{send_float_to, send_symbol_to},
+    types::{PdEvent, PdMessage},
+    Pd,
+};
+
+#[test]
+fn router() {
+    let mut pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+    let ctx = pd.audio_context();
+
+    let patch = pd.open_patch("tests/patches/echo.pd").unwrap();
+
+    // Routes are not registered over the listeners which are registered.
+    let receiver = pd.message_receiver();
+    assert!(matches!(
+        pd.on("float_from_pd", |_| {}),
+        Err(PdError::SubscriptionError(
+            SubscriptionError::ListenersAlreadyRegistered
+        ))
+    ));
+    assert!(pd.subscriptions.is_empty());
+    drop(receiver);
+
+    let floats: Arc<Mutex<Vec<PdMessage>>> = Arc::new(Mutex::new(vec![]));
+    let symbols: Arc<Mutex<Vec<PdMessage>>> = Arc::new(Mutex::new(vec![]));
+
+    let floats_to_fill = floats.clone();
+    pd.on("float_from_pd", move |message| {
+        floats_to_fill.lock().unwrap().push(message.clone());
+    })
+    .unwrap();
+    let symbols_to_fill = symbols.clone();
+    pd.on("symbol_from_pd", move |message| {
+        symbols_to_fill.lock().unwrap().push(message.clone());
+    })
+    .unwrap();
+
+    // Routes subscribe automatically.
+    assert!(pd.subscriptions.contains_key("float_from_pd"));
+    assert!(pd.subscriptions.contains_key("symbol_from_pd"));
+
+    send_float_to("float_from_rust", 1.0).unwrap();
+    send_symbol_to("symbol_from_rust", "hello").unwrap();
+    ctx.receive_messages_from_pd();
+
+    assert_eq!(
+        *floats.lock().unwrap(),
+        vec![PdMessage::Float {
+            source: "float_from_pd".to_owned(),
+            value: 1.0
+        }]
+    );
+    assert_eq!(
+        *symbols.lock().unwrap(),
+        vec![PdMessage::Symbol {
+            source: "symbol_from_pd".to_owned(),
+            value: "hello".to_owned()
+        }]
+    );
+
+    // Unsubscribing removes the route.
+    pd.unsubscribe_from("float_from_pd");
+    assert!(!pd.subscriptions.contains_key("float_from_pd"));
+    assert_eq!(Arc::strong_count(&floats), 1);
+
+    send_float_to("float_from_rust", 2.0).unwrap();
+    send_symbol_to("symbol_from_rust", "world").unwrap();
+    ctx.receive_messages_from_pd();
+
+    assert_eq!(floats.lock().unwrap().len(), 1);
+    assert_eq!(symbols.lock().unwrap().len(), 2);
+
+    // Routes keep working while events are polled, both see the messages.
+    let mut events = vec![];
+    pd.poll_events(&mut events).unwrap();
+    send_symbol_to("symbol_from_rust", "polled").unwrap();
+    pd.poll_events(&mut events).unwrap();
+    assert_eq!(symbols.lock().unwrap().len(), 3);
+    assert_eq!(
+        events,
+        vec![PdEvent::Message(PdMessage::Symbol {
+            source: "symbol_from_pd".to_owned(),
+            value: "polled".to_owned()
+        })]
+    );
+
+    // Routes which are added after polling started are fed as well.
+    let floats_to_fill = floats.clone();
+    pd.on("float_from_pd", move |message| {
+        floats_to_fill.lock().unwrap().push(message.clone());
+    })
+    .unwrap();
+    events.clear();
+    send_float_to("float_from_rust", 3.0).unwrap();
+    pd.poll_events(&mut events).unwrap();
+    assert_eq!(floats.lock().unwrap().len(), 2);
+    assert_eq!(events.len(), 1);
+
+    pd.unsubscribe_from_all();
+    assert_eq!(Arc::strong_count(&floats), 1);
+    assert_eq!(Arc::strong_count(&symbols), 1);
+    patch.close().unwrap();
+}
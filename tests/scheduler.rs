@@ -0,0 +1,73 @@
+#![allow(clippy::restriction)]
+
+use std::time::Duration;
+
+use libpd_rs::{audio::ScheduleAt, types::OutgoingMessage, Pd};
+
+fn level(value: f64) -> OutgoingMessage {
+    OutgoingMessage::Float {
+        receiver: "level".to_owned(),
+        value,
+    }
+}
+
+/// Gets the levels of the left channel at the start of each block.
+fn block_levels(output: &[f32]) -> Vec<f32> {
+    output.chunks(128).map(|block| block[0]).collect()
+}
+
+#[test]
+fn scheduler() {
+    let mut pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+    let _patch = pd.open_patch("tests/patches/level.pd").unwrap();
+    pd.activate_audio(true).unwrap();
+
+    let mut scheduler = pd.audio_context().scheduler(16);
+    assert_eq!(scheduler.frame(), 0);
+
+    // In the third block of the next buffer.
+    scheduler
+        .schedule(ScheduleAt::Offset(130), level(0.25))
+        .unwrap();
+    // In the last block, the frame 448 is at about 10.16 milliseconds.
+    scheduler
+        .schedule(ScheduleAt::Time(Duration::from_micros(10_200)), level(0.75))
+        .unwrap();
+    // After the buffer.
+    scheduler
+        .schedule(ScheduleAt::Frame(600), level(1.0))
+        .unwrap();
+    assert_eq!(scheduler.pending(), 3);
+
+    let mut output = [0.0_f32; 1024];
+    scheduler.process(&[], &mut output).unwrap();
+    assert_eq!(
+        block_levels(&output),
+        [0.5, 0.5, 0.25, 0.25, 0.25, 0.25, 0.25, 0.75]
+    );
+    assert!(output[256..258].iter().all(|sample| *sample == 0.25));
+    assert_eq!(scheduler.frame(), 512);
+    assert_eq!(scheduler.logical_time(), Duration::from_nanos(11_609_977));
+    assert_eq!(scheduler.pending(), 1);
+
+    // Messages in the past are sent before the next block, in the order they are scheduled.
+    scheduler
+        .schedule(ScheduleAt::Frame(0), level(0.1))
+        .unwrap();
+    scheduler
+        .schedule(ScheduleAt::Frame(0), level(0.2))
+        .unwrap();
+    let mut output = [0.0_f32; 256];
+    scheduler.process(&[], &mut output).unwrap();
+    assert_eq!(block_levels(&output), [0.2, 1.0]);
+    assert_eq!(scheduler.pending(), 0);
+
+    // Partial blocks are rejected without sending anything.
+    scheduler
+        .schedule(ScheduleAt::Offset(0), level(0.3))
+        .unwrap();
+    assert!(scheduler.process(&[], &mut [0.0; 100]).is_err());
+    assert_eq!(scheduler.pending(), 1);
+    scheduler.clear();
+    assert_eq!(scheduler.pending(), 0);
+}
@@ -7,6 +7,7 @@ use libpd_rs::{
         block_size, close_patch, open_patch, receive::on_midi_after_touch, send::send_after_touch,
         util::dsp_on,
     },
+    midi::{Channel, U7},
     Pd,
 };
 
@@ -25,8 +26,11 @@ fn send_and_receive_after_touch() {
     let patch_handle = open_patch("tests/patches/echo.pd").unwrap();
 
     let messages_to_fill = after_touch_messages_received.clone();
-    on_midi_after_touch(move |channel, value| {
-        messages_to_fill.lock().unwrap().push((channel, value));
+    let _hook = on_midi_after_touch(move |channel, value| {
+        messages_to_fill
+            .lock()
+            .unwrap()
+            .push((channel.raw(), value.into()));
     });
 
     let (tx, rx) = mpsc::channel::<()>();
@@ -61,7 +65,11 @@ fn send_and_receive_after_touch() {
     #[allow(clippy::explicit_counter_loop)]
     // Send 5 note on messages in sequence.
     for _ in 0..5 {
-        send_after_touch(channel, value).unwrap();
+        send_after_touch(
+            Channel::try_from(channel).unwrap(),
+            U7::try_from(value).unwrap(),
+        )
+        .unwrap();
         channel += 1;
         value += 1;
     }
@@ -27,7 +27,7 @@ fn send_and_receive_bang() {
     let patch_handle = open_patch("tests/patches/echo.pd").unwrap();
 
     let bangs_to_fill = bangs.clone();
-    on_bang(move |source| {
+    let _hook = on_bang(move |source| {
         assert_eq!(source, "bang_from_pd");
         bangs_to_fill.lock().unwrap().push("bang");
     });
@@ -7,6 +7,7 @@ use libpd_rs::{
         block_size, close_patch, open_patch, receive::on_midi_control_change,
         send::send_control_change, util::dsp_on,
     },
+    midi::{Channel, U7},
     Pd,
 };
 
@@ -26,11 +27,12 @@ fn send_and_receive_control_change() {
     let patch_handle = open_patch("tests/patches/echo.pd").unwrap();
 
     let messages_to_fill = control_change_messages_received.clone();
-    on_midi_control_change(move |channel, controller_number, value| {
-        messages_to_fill
-            .lock()
-            .unwrap()
-            .push((channel, controller_number, value));
+    let _hook = on_midi_control_change(move |channel, controller_number, value| {
+        messages_to_fill.lock().unwrap().push((
+            channel.raw(),
+            controller_number.into(),
+            value.into(),
+        ));
     });
 
     let (tx, rx) = mpsc::channel::<()>();
@@ -66,7 +68,12 @@ fn send_and_receive_control_change() {
     #[allow(clippy::explicit_counter_loop)]
     // Send 5 note on messages in sequence.
     for _ in 0..5 {
-        send_control_change(channel, controller_number, value).unwrap();
+        send_control_change(
+            Channel::try_from(channel).unwrap(),
+            U7::try_from(controller_number).unwrap(),
+            U7::try_from(value).unwrap(),
+        )
+        .unwrap();
         channel += 1;
         controller_number += 1;
         value += 1;
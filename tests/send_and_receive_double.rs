@@ -27,7 +27,7 @@ fn send_and_receive_double() {
     let patch_handle = open_patch("tests/patches/echo.pd").unwrap();
 
     let floats_to_fill = floats.clone();
-    on_double(move |source, value| {
+    let _hook = on_double(move |source, value| {
         assert_eq!(source, "float_from_pd");
         floats_to_fill.lock().unwrap().push(value);
     });
@@ -27,7 +27,7 @@ fn send_and_receive_float() {
     let patch_handle = open_patch("tests/patches/echo.pd").unwrap();
 
     let floats_to_fill = floats.clone();
-    on_float(move |source, value| {
+    let _hook = on_float(move |source, value| {
         assert_eq!(source, "float_from_pd");
         floats_to_fill.lock().unwrap().push(value);
     });
@@ -32,7 +32,7 @@ fn send_and_receive_list() {
     let patch_handle = open_patch("tests/patches/echo.pd").unwrap();
 
     let list_to_fill = list_received.clone();
-    on_list(move |source, list| {
+    let _hook = on_list(move |source, list| {
         assert_eq!(source, "list_from_pd");
         for atom in list {
             list_to_fill.lock().unwrap().push(atom.clone());
@@ -15,7 +15,7 @@ fn send_and_receive_midi_byte() {
     let sample_rate = 44100;
     let output_channels = 2;
 
-    let midi_byte_messages_received: Arc<Mutex<Vec<(i32, i32)>>> = Arc::new(Mutex::new(vec![]));
+    let midi_byte_messages_received: Arc<Mutex<Vec<(u8, u8)>>> = Arc::new(Mutex::new(vec![]));
 
     let pd = Pd::init_and_configure(0, output_channels, sample_rate).unwrap();
     let ctx = pd.audio_context();
@@ -25,7 +25,7 @@ fn send_and_receive_midi_byte() {
     let patch_handle = open_patch("tests/patches/echo.pd").unwrap();
 
     let messages_to_fill = midi_byte_messages_received.clone();
-    on_midi_byte(move |port, byte| {
+    let _hook = on_midi_byte(move |port, byte| {
         messages_to_fill.lock().unwrap().push((port, byte));
     });
 
@@ -55,8 +55,8 @@ fn send_and_receive_midi_byte() {
         }
     });
 
-    let mut port: i32 = 0;
-    let mut byte: i32 = 0x7F;
+    let mut port: u8 = 0;
+    let mut byte: u8 = 0x7F;
 
     #[allow(clippy::explicit_counter_loop)]
     // Send 5 note on messages in sequence.
@@ -72,7 +72,7 @@ fn send_and_receive_midi_byte() {
     tx.send(()).unwrap();
     handle.join().unwrap();
 
-    let vales_to_compare: Vec<(i32, i32)> =
+    let vales_to_compare: Vec<(u8, u8)> =
         vec![(0, 0x7F), (1, 0x8F), (2, 0x9F), (3, 0xAF), (4, 0xBF)];
 
     assert_eq!(midi_byte_messages_received.lock().unwrap().len(), 5);
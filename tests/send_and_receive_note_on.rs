@@ -7,6 +7,7 @@ use libpd_rs::{
         block_size, close_patch, open_patch, receive::on_midi_note_on, send::send_note_on,
         util::dsp_on,
     },
+    midi::{Channel, U7},
     Pd,
 };
 
@@ -25,11 +26,11 @@ fn send_and_receive_note_on() {
     let patch_handle = open_patch("tests/patches/echo.pd").unwrap();
 
     let messages_to_fill = note_on_messages_received.clone();
-    on_midi_note_on(move |channel, pitch, velocity| {
+    let _hook = on_midi_note_on(move |channel, pitch, velocity| {
         messages_to_fill
             .lock()
             .unwrap()
-            .push((channel, pitch, velocity));
+            .push((channel.raw(), pitch.into(), velocity.into()));
     });
 
     let (tx, rx) = mpsc::channel::<()>();
@@ -65,7 +66,12 @@ fn send_and_receive_note_on() {
     #[allow(clippy::explicit_counter_loop)]
     // Send 5 note on messages in sequence.
     for _ in 0..5 {
-        send_note_on(channel, pitch, velocity).unwrap();
+        send_note_on(
+            Channel::try_from(channel).unwrap(),
+            U7::try_from(pitch).unwrap(),
+            U7::try_from(velocity).unwrap(),
+        )
+        .unwrap();
         channel += 1;
         pitch += 1;
         velocity += 1;
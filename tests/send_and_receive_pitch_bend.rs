@@ -7,6 +7,7 @@ use libpd_rs::{
         block_size, close_patch, open_patch, receive::on_midi_pitch_bend, send::send_pitch_bend,
         util::dsp_on,
     },
+    midi::{Channel, U14},
     Pd,
 };
 
@@ -25,11 +26,11 @@ fn send_and_receive_pitch_bend() {
     let patch_handle = open_patch("tests/patches/echo.pd").unwrap();
 
     let messages_to_fill = pitch_bend_messages_received.clone();
-    on_midi_pitch_bend(move |channel, bend_amount| {
+    let _hook = on_midi_pitch_bend(move |channel, bend_amount| {
         messages_to_fill
             .lock()
             .unwrap()
-            .push((channel, bend_amount));
+            .push((channel.raw(), bend_amount.bend()));
     });
 
     let (tx, rx) = mpsc::channel::<()>();
@@ -64,7 +65,11 @@ fn send_and_receive_pitch_bend() {
     #[allow(clippy::explicit_counter_loop)]
     // Send 5 note on messages in sequence.
     for _ in 0..5 {
-        send_pitch_bend(channel, bend_amount).unwrap();
+        send_pitch_bend(
+            Channel::try_from(channel).unwrap(),
+            U14::try_from(bend_amount + 8192).unwrap(),
+        )
+        .unwrap();
         channel += 1;
         bend_amount += 1000;
     }
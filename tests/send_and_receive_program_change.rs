@@ -7,6 +7,7 @@ use libpd_rs::{
         block_size, close_patch, open_patch, receive::on_midi_program_change,
         send::send_program_change, util::dsp_on,
     },
+    midi::{Channel, U7},
     Pd,
 };
 
@@ -26,11 +27,11 @@ fn send_and_receive_program_change() {
     let patch_handle = open_patch("tests/patches/echo.pd").unwrap();
 
     let messages_to_fill = program_change_messages_received.clone();
-    on_midi_program_change(move |channel, program_number| {
+    let _hook = on_midi_program_change(move |channel, program_number| {
         messages_to_fill
             .lock()
             .unwrap()
-            .push((channel, program_number));
+            .push((channel.raw(), program_number.into()));
     });
 
     let (tx, rx) = mpsc::channel::<()>();
@@ -65,7 +66,11 @@ fn send_and_receive_program_change() {
     #[allow(clippy::explicit_counter_loop)]
     // Send 5 note on messages in sequence.
     for _ in 0..5 {
-        send_program_change(channel, program_number).unwrap();
+        send_program_change(
+            Channel::try_from(channel).unwrap(),
+            U7::try_from(program_number).unwrap(),
+        )
+        .unwrap();
         channel += 1;
         program_number += 1;
     }
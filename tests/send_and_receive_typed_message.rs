@@ -29,7 +29,7 @@ fn send_and_receive_typed_message() {
 
     let message_count = Arc::new(Mutex::new(0));
     let mc = message_count.clone();
-    on_message(move |source, selector, _list| {
+    let _hook = on_message(move |source, selector, _list| {
         assert_eq!(source, "pd");
         assert_eq!(selector, "audiostatus");
         *mc.lock().unwrap() += 1;
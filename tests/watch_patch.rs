@@ -0,0 +1,116 @@
+#![allow(clippy::restriction)]
+
+use std::{
+    fs::{self, File},
+    path::Path,
+    time::{Duration, SystemTime},
+};
+
+use libpd_rs::{
+    functions::send::send_float_to,
+    types::{PdEvent, PdMessage},
+    Pd,
+};
+
+fn write(path: &Path, contents: &str, age: u64) {
+    fs::write(path, contents).unwrap();
+    // Set the modification time explicitly to not depend on the resolution of the file system.
+    File::options()
+        .write(true)
+        .open(path)
+        .unwrap()
+        .set_modified(SystemTime::now() - Duration::from_secs(age))
+        .unwrap();
+}
+
+fn floats(pd: &mut Pd, events: &mut Vec<PdEvent>) -> Vec<f64> {
+    events.clear();
+    send_float_to("float_from_rust", 10.0).unwrap();
+    pd.poll_events(events).unwrap();
+    events
+        .iter()
+        .filter_map(|event| match event {
+            PdEvent::Message(PdMessage::Float { value, .. }) => Some(*value),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn watch_patch() {
+    let patches = tempfile::tempdir().unwrap();
+    let abstractions = tempfile::tempdir().unwrap();
+    let patch_path = patches.path().join("main.pd");
+    let abstraction_path = abstractions.path().join("multiply.pd");
+
+    write(
+        &patch_path,
+        "#N canvas 0 50 450 300 12;
+#X obj 20 20 r float_from_rust;
+#X obj 20 50 multiply;
+#X obj 20 80 s float_from_pd;
+#X connect 0 0 1 0;
+#X connect 1 0 2 0;
+",
+        100,
+    );
+    let abstraction = |factor: u32| {
+        format!(
+            "#N canvas 0 50 450 300 12;
+#X obj 20 20 inlet;
+#X obj 20 50 * {factor};
+#X obj 20 80 outlet;
+#X connect 0 0 1 0;
+#X connect 1 0 2 0;
+"
+        )
+    };
+    write(&abstraction_path, &abstraction(2), 100);
+
+    let mut pd = Pd::init_and_configure(0, 2, 44100).unwrap();
+    pd.add_path_to_search_paths(abstractions.path()).unwrap();
+    pd.activate_audio(true).unwrap();
+    pd.watch_patch(&patch_path).unwrap();
+    pd.subscribe_to("float_from_pd").unwrap();
+    let first_dollar_zero = pd.watched_patch(&patch_path).unwrap().dollar_zero();
+
+    let mut events = vec![];
+    assert_eq!(floats(&mut pd, &mut events), vec![20.0]);
+
+    // Changing the abstraction reloads the patch.
+    write(&abstraction_path, &abstraction(3), 50);
+    assert_eq!(floats(&mut pd, &mut events), vec![20.0]);
+    assert!(events.contains(&PdEvent::PatchReloaded {
+        path: patch_path.clone()
+    }));
+    assert_ne!(
+        pd.watched_patch(&patch_path).unwrap().dollar_zero(),
+        first_dollar_zero
+    );
+    assert!(pd.audio_active());
+    assert!(pd.subscriptions.contains_key("float_from_pd"));
+    assert_eq!(floats(&mut pd, &mut events), vec![30.0]);
+
+    // Nothing is reloaded without a change.
+    assert!(!events
+        .iter()
+        .any(|event| matches!(event, PdEvent::PatchReloaded { .. })));
+
+    // The previous version keeps running if the patch can't be opened.
+    fs::remove_file(&patch_path).unwrap();
+    assert_eq!(floats(&mut pd, &mut events), vec![30.0]);
+    assert!(events.iter().any(
+        |event| matches!(event, PdEvent::PatchReloadFailed { path, .. } if *path == patch_path)
+    ));
+    assert_eq!(floats(&mut pd, &mut events), vec![30.0]);
+    assert!(!events
+        .iter()
+        .any(|event| matches!(event, PdEvent::PatchReloadFailed { .. })));
+
+    let patch = pd.unwatch_patch(&patch_path).unwrap();
+    assert!(pd.watched_patch(&patch_path).is_none());
+    patch.close().unwrap();
+    assert!(floats(&mut pd, &mut events).is_empty());
+
+    pd.unsubscribe_from_all();
+}